@@ -33,14 +33,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 length: Some(2.0),
                 width: Some(1.5),
                 bar_configuration: Some(node::BarConfiguration::Perpendicular),
+                grate_type: None,
             }),
             curb_opening: Some(node::CurbOpeningProperties {
                 length: Some(3.0),
                 height: Some(0.5),
                 throat_type: Some(node::ThroatType::Horizontal),
             }),
+            slotted: None,
             local_depression: Some(2.0),
             clogging_factor: Some(0.15),
+            bypass_to: None,
         },
     );
 
@@ -64,6 +67,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             boundary_condition: node::BoundaryCondition::NormalDepth,
             tailwater_elevation: Some(116.5),
             tidal_curve: None,
+            tidal_stage: None,
+            storm_start: None,
+            flap_gate: None,
+            tailwater_rule: None,
         },
     );
 
@@ -83,6 +90,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
 
@@ -101,6 +109,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
 
@@ -67,7 +67,7 @@ fn main() {
         0.02,   // 2% roadway slope
         0.01,   // 1% longitudinal slope
         2.0,    // 2 ft gutter width
-        2.0,    // 2 inch local depression
+        2.0 / 12.0, // 2 inch local depression
     );
 
     let flow = 5.0; // cfs
@@ -150,7 +150,7 @@ fn main() {
     let uniform_result = uniform_gutter.result_for_flow(target_flow, GUTTER_K_US);
 
     let composite_result = CompositeGutter::new(
-        0.016, 0.04, 0.02, 0.01, 2.0, 2.0
+        0.016, 0.04, 0.02, 0.01, 2.0, 2.0 / 12.0
     ).result_for_flow(target_flow, GUTTER_K_US);
 
     println!("\n{:<25} {:>10} {:>12}", "Gutter Type", "Spread", "Depth at Curb");
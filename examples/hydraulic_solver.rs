@@ -32,8 +32,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             location: node::InletLocation::OnGrade,
             grate: None,
             curb_opening: None,
+            slotted: None,
             local_depression: Some(2.0),
             clogging_factor: Some(0.15),
+            bypass_to: None,
         },
     );
 
@@ -57,6 +59,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             boundary_condition: node::BoundaryCondition::NormalDepth,
             tailwater_elevation: Some(116.5),
             tidal_curve: None,
+            tidal_stage: None,
+            storm_start: None,
+            flap_gate: None,
+            tailwater_rule: None,
         },
     );
 
@@ -76,6 +82,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
 
@@ -98,6 +105,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
 
@@ -148,7 +156,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Route flows through network
-    let conduit_flows = solver::route_flows(&network, &node_inflows)?;
+    let conduit_flows = solver::route_flows(&network, &node_inflows, project::UnitSystem::US)?;
 
     println!("\nConduit flows:");
     for (conduit_id, flow) in &conduit_flows {
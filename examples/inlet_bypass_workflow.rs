@@ -39,10 +39,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 length: Some(3.0),
                 width: Some(2.0),
                 bar_configuration: Some(node::BarConfiguration::Perpendicular),
+                grate_type: None,
             }),
             curb_opening: None,
+            slotted: None,
             local_depression: Some(2.0),
             clogging_factor: Some(0.15),
+            // Carryover runs down the curb to the next inlet
+            bypass_to: Some("IN-002".to_string()),
         },
     );
 
@@ -57,14 +61,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 length: Some(3.0),
                 width: Some(2.0),
                 bar_configuration: Some(node::BarConfiguration::Perpendicular),
+                grate_type: None,
             }),
             curb_opening: Some(node::CurbOpeningProperties {
                 length: Some(5.0),
                 height: Some(0.5),
                 throat_type: Some(node::ThroatType::Horizontal),
             }),
+            slotted: None,
             local_depression: Some(2.0),
             clogging_factor: Some(0.15),
+            bypass_to: Some("IN-003".to_string()),
         },
     );
 
@@ -74,15 +81,18 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         99.0,
         node::InletProperties {
             inlet_type: node::InletType::Grate,
-            location: node::InletLocation::Sag,  // Sag inlet captures 100%
+            location: node::InletLocation::Sag,  // Sag inlet ponds until it captures the flow
             grate: Some(node::GrateProperties {
                 length: Some(4.0),
                 width: Some(3.0),
                 bar_configuration: Some(node::BarConfiguration::Perpendicular),
+                grate_type: None,
             }),
             curb_opening: None,
+            slotted: None,
             local_depression: None,
             clogging_factor: Some(0.50),
+            bypass_to: None,
         },
     );
 
@@ -106,6 +116,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             boundary_condition: node::BoundaryCondition::NormalDepth,
             tailwater_elevation: Some(91.0),
             tidal_curve: None,
+            tidal_stage: None,
+            storm_start: None,
+            flap_gate: None,
+            tailwater_rule: None,
         },
     );
 
@@ -125,6 +139,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
     pipe1.upstream_invert = Some(99.8);
@@ -145,6 +160,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
     pipe2.upstream_invert = Some(97.8);
@@ -165,6 +181,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
     pipe3.upstream_invert = Some(94.8);
@@ -185,6 +202,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
     pipe4.upstream_invert = Some(92.0);
@@ -299,12 +317,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     println!();
 
-    // Show how bypass affects next inlet
-    println!("Bypass Flow Analysis:");
-    if inlet_results.len() >= 2 {
-        println!("• Inlet 1 bypassed {:.2} cfs", inlet_results[0].bypass_flow);
-        println!("• This bypass would normally flow to the next downstream inlet");
-        println!("  (In this example, inlets drain to separate pipes, so no accumulation)");
+    // Show where each inlet's bypass ends up
+    println!("Bypass Chains:");
+    for result in &inlet_results {
+        if result.bypass_flow <= 0.0 {
+            println!("  {}: no bypass", result.node_id);
+            continue;
+        }
+        let mut chain = vec![result.node_id.clone()];
+        chain.extend(result.bypass_chain.iter().cloned());
+        if result.lost_flow > 0.0 {
+            println!("  {} -> leaves the system ({:.2} cfs)", chain.join(" -> "), result.lost_flow);
+        } else {
+            println!("  {} (captured)", chain.join(" -> "));
+        }
     }
     println!();
 
@@ -368,17 +394,25 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 9. Check for violations
     println!("--- Design Criteria Check ---");
 
-    if let Some(ref violations) = analysis.violations {
-        if violations.is_empty() {
-            println!("✓ No violations - design meets all criteria");
-        } else {
-            println!("⚠ Found {} violation(s):", violations.len());
-            for violation in violations {
-                println!("  [{:?}] {}: {}",
-                         violation.severity,
-                         violation.element_id,
-                         violation.message);
-            }
+    // Spread at each inlet, including ponding at the sag inlet
+    let mut violations = solver::check_inlet_spread(
+        &network,
+        &inlet_results,
+        &HashMap::new(),
+        10.0,
+        project::UnitSystem::US,
+    );
+    violations.extend(analysis.violations.clone().unwrap_or_default());
+
+    if violations.is_empty() {
+        println!("✓ No violations - design meets all criteria");
+    } else {
+        println!("⚠ Found {} violation(s):", violations.len());
+        for violation in &violations {
+            println!("  [{:?}] {}: {}",
+                     violation.severity,
+                     violation.element_id,
+                     violation.message);
         }
     }
     println!();
@@ -4,6 +4,7 @@
 //! including on-grade and sag inlet configurations with bypass flow tracking.
 
 use hec22::gutter::{GutterFlowResult, UniformGutter, GUTTER_K_US};
+use hec22::project::UnitSystem;
 use hec22::inlet::*;
 
 fn main() {
@@ -59,7 +60,7 @@ fn example_1_grate_on_grade() {
         2.0,                             // 2 inch local depression
     );
 
-    let result = inlet.interception(approach_flow, &gutter_result);
+    let result = inlet.interception(approach_flow, &gutter_result, UnitSystem::US);
 
     println!("\nInlet performance:");
     println!("  Intercepted flow: {:.2} cfs", result.intercepted_flow);
@@ -130,7 +131,7 @@ fn example_3_combination_inlet() {
     let curb = CurbOpeningInletOnGrade::new(5.0, 0.5, ThroatType::Horizontal, 0.10);
 
     // Test grate alone
-    let grate_only = grate.interception(approach_flow, &gutter_result);
+    let grate_only = grate.interception(approach_flow, &gutter_result, UnitSystem::US);
     println!("\nGrate alone:");
     println!("  Intercepted: {:.2} cfs", grate_only.intercepted_flow);
     println!("  Bypass: {:.2} cfs", grate_only.bypass_flow);
@@ -138,7 +139,7 @@ fn example_3_combination_inlet() {
 
     // Test combination
     let combo = CombinationInletOnGrade::new(grate, curb);
-    let combo_result = combo.interception(approach_flow, &gutter_result);
+    let combo_result = combo.interception(approach_flow, &gutter_result, UnitSystem::US);
 
     println!("\nCombination inlet:");
     println!("  Intercepted: {:.2} cfs", combo_result.intercepted_flow);
@@ -176,7 +177,7 @@ fn example_4_series_with_bypass() {
         2.0,
     );
     let gutter1 = gutter.result_for_flow(initial_flow, GUTTER_K_US);
-    let result1 = inlet1.interception(initial_flow, &gutter1);
+    let result1 = inlet1.interception(initial_flow, &gutter1, UnitSystem::US);
 
     println!("  Approach flow: {:.2} cfs", result1.approach_flow);
     println!("  Spread: {:.2} ft", result1.spread);
@@ -195,7 +196,7 @@ fn example_4_series_with_bypass() {
 
     let inlet2 = GrateInletOnGrade::new(3.0, 2.0, BarConfiguration::Perpendicular, 0.15, 2.0);
     let gutter2 = gutter.result_for_flow(approach_flow_2, GUTTER_K_US);
-    let result2 = inlet2.interception(approach_flow_2, &gutter2);
+    let result2 = inlet2.interception(approach_flow_2, &gutter2, UnitSystem::US);
 
     println!("  Total approach flow: {:.2} cfs", result2.approach_flow);
     println!("  Spread: {:.2} ft", result2.spread);
@@ -223,7 +224,7 @@ fn example_4_series_with_bypass() {
     // Check if inlet can handle flow without flooding
     let rim_elevation = 100.0;
     let invert_elevation = 95.0;
-    let (flooding, depth) = sag_inlet.check_flooding(approach_flow_3, rim_elevation, invert_elevation);
+    let (flooding, depth) = sag_inlet.check_flooding(approach_flow_3, rim_elevation, invert_elevation, UnitSystem::US);
 
     println!("  Sag inlet capacity check:");
     println!("    Ponding depth: {:.2} ft", depth);
@@ -256,8 +257,8 @@ fn example_5_sag_inlet() {
     // Option 1: Single large grate
     println!("Option 1: Single 4×3 ft grate");
     let grate1 = GrateInletSag::new(4.0, 3.0, 1, 0.50);
-    let (flood1, depth1) = grate1.check_flooding(design_flow, rim_elevation, invert_elevation);
-    let capacity1 = grate1.capacity(depth1);
+    let (flood1, depth1) = grate1.check_flooding(design_flow, rim_elevation, invert_elevation, UnitSystem::US);
+    let capacity1 = grate1.capacity(depth1, UnitSystem::US);
 
     println!("  Ponding depth: {:.2} ft", depth1);
     println!("  Capacity: {:.2} cfs", capacity1);
@@ -267,8 +268,8 @@ fn example_5_sag_inlet() {
     // Option 2: Two smaller grates
     println!("Option 2: Two 3×2 ft grates");
     let grate2 = GrateInletSag::new(3.0, 2.0, 2, 0.50);
-    let (flood2, depth2) = grate2.check_flooding(design_flow, rim_elevation, invert_elevation);
-    let capacity2 = grate2.capacity(depth2);
+    let (flood2, depth2) = grate2.check_flooding(design_flow, rim_elevation, invert_elevation, UnitSystem::US);
+    let capacity2 = grate2.capacity(depth2, UnitSystem::US);
 
     println!("  Ponding depth: {:.2} ft", depth2);
     println!("  Capacity: {:.2} cfs", capacity2);
@@ -281,7 +282,7 @@ fn example_5_sag_inlet() {
 
     // Test at various depths
     for test_depth in [0.5, 1.0, 1.5, 2.0] {
-        let capacity = curb.capacity(test_depth);
+        let capacity = curb.capacity(test_depth, UnitSystem::US);
         println!("  At {:.1} ft depth: {:.2} cfs capacity", test_depth, capacity);
         if capacity >= design_flow {
             println!("    → Adequate capacity at {:.2} ft depth", test_depth);
@@ -0,0 +1,100 @@
+//! Example: Inlet spacing on a continuous grade
+//!
+//! This example places grate inlets down a roadway gutter following the
+//! HEC-22 Section 7.4.2 procedure, using the conditions of Example 7.9.
+
+use hec22::conduit::GutterProperties;
+use hec22::design::{GutterRun, InletSpacer, InletSpacingConfig};
+use hec22::node::{BarConfiguration, GrateProperties, InletLocation, InletProperties, InletType};
+
+fn main() {
+    println!("=== HEC-22 Inlet Spacing Example ===\n");
+
+    // 42.7 ft of pavement (C = 0.73) draining to the gutter, 10-year
+    // intensity at the 5 minute minimum Tc
+    let run = GutterRun {
+        id: "MAIN".to_string(),
+        start_station: 0.0,
+        length: 2000.0,
+        start_elevation: 380.0,
+        tributary_width: 42.7,
+        runoff_coefficient: 0.73,
+        intensity: 7.1,
+        gutter: GutterProperties {
+            cross_slope: 0.04,
+            longitudinal_slope: 0.03,
+            width: Some(2.0),
+            manning_n: 0.016,
+            depression: None,
+            curb_height: None,
+            crown_height: None,
+            width_to_crown: None,
+        },
+    };
+
+    // 2 ft × 3 ft grate
+    let inlet = InletProperties {
+        inlet_type: InletType::Grate,
+        location: InletLocation::OnGrade,
+        grate: Some(GrateProperties {
+            length: Some(3.0),
+            width: Some(2.0),
+            bar_configuration: Some(BarConfiguration::Perpendicular),
+            grate_type: None,
+        }),
+        curb_opening: None,
+        slotted: None,
+        local_depression: None,
+        clogging_factor: Some(0.0),
+        bypass_to: None,
+    };
+
+    for max_spacing in [None, Some(360.0)] {
+        let config = InletSpacingConfig {
+            max_spread: 6.6,
+            max_spacing,
+            ..InletSpacingConfig::us_customary()
+        };
+        match max_spacing {
+            Some(max) => println!("--- Allowable spread 6.6 ft, maximum spacing {:.0} ft ---", max),
+            None => println!("--- Allowable spread 6.6 ft ---"),
+        }
+
+        let result = match InletSpacer::new(config).space(&run, &inlet) {
+            Ok(result) => result,
+            Err(e) => {
+                println!("Spacing failed: {}\n", e);
+                continue;
+            }
+        };
+
+        println!(
+            "{:<8} {:>8} {:>8} {:>7} {:>7} {:>7} {:>7} {:>6} {:>7} {:>7}",
+            "Inlet", "Station", "Spacing", "A (ac)", "Q (cfs)", "Qb in", "Total", "T (ft)", "Qi", "Qb out"
+        );
+        for station in &result.inlets {
+            println!(
+                "{:<8} {:>8.0} {:>8.0} {:>7.2} {:>7.2} {:>7.2} {:>7.2} {:>6.2} {:>7.2} {:>7.2}",
+                station.inlet_id,
+                station.station,
+                station.spacing,
+                station.drainage_area,
+                station.runoff,
+                station.carryover,
+                station.approach_flow,
+                station.spread,
+                station.intercepted_flow,
+                station.bypass_flow
+            );
+        }
+        println!(
+            "Flow passing the end of the run at station {:.0}: {:.2} cfs",
+            result.end_station, result.end_flow
+        );
+        println!(
+            "Generated {} inlet nodes and {} gutter conduits\n",
+            result.nodes.len(),
+            result.conduits.len()
+        );
+    }
+}
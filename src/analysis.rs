@@ -3,6 +3,8 @@
 //! Defines design constraints, computed results, and violation reporting
 //! for drainage network analysis.
 
+use crate::culvert::CulvertResult;
+use crate::gvf::WaterSurfaceProfile;
 use serde::{Deserialize, Serialize};
 
 /// Design criteria and constraints
@@ -201,6 +203,36 @@ pub struct NodeResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "junctionLoss")]
     pub junction_loss: Option<f64>,
+
+    /// Time of peak HGL (minutes from storm start) - unsteady analysis only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "timeOfPeak")]
+    pub time_of_peak: Option<f64>,
+
+    /// Time the HGL is above the highest connected pipe crown (minutes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "surchargeDuration")]
+    pub surcharge_duration: Option<f64>,
+
+    /// Height of the HGL above the highest connected pipe crown (ft or m)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "surchargeDepth")]
+    pub surcharge_depth: Option<f64>,
+
+    /// Height of the HGL above the rim (ft or m)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "depthAboveRim")]
+    pub depth_above_rim: Option<f64>,
+
+    /// Volume lost to surface flooding (ft³ or m³)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "floodVolume")]
+    pub flood_volume: Option<f64>,
+
+    /// Rule that set the tailwater - outfalls only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "tailwaterControl")]
+    pub tailwater_control: Option<TailwaterControl>,
 }
 
 /// Computed results for a conduit
@@ -237,9 +269,46 @@ pub struct ConduitResult {
     #[serde(rename = "flowRegime")]
     pub flow_regime: Option<FlowRegime>,
 
+    /// Distance from the upstream end to a hydraulic jump in the barrel (ft or m)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "jumpLocation")]
+    pub jump_location: Option<f64>,
+
     /// Head loss breakdown
     #[serde(skip_serializing_if = "Option::is_none")]
     pub headloss: Option<HeadLoss>,
+
+    /// Gradually varied flow water surface profile along the conduit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<WaterSurfaceProfile>,
+
+    /// Culvert headwater and control
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub culvert: Option<CulvertResult>,
+
+    /// Time of peak flow (minutes from storm start) - unsteady analysis only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "timeOfPeak")]
+    pub time_of_peak: Option<f64>,
+
+    /// Time of concentration at the upstream end (minutes) - rational design
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "timeOfConcentration")]
+    pub time_of_concentration: Option<f64>,
+
+    /// Rainfall intensity for the time of concentration (in/hr or mm/hr)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intensity: Option<f64>,
+
+    /// Sum of C × A upstream of the conduit (acres or hectares)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "sumCA")]
+    pub sum_ca: Option<f64>,
+
+    /// Travel time through the conduit (minutes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "travelTime")]
+    pub travel_time: Option<f64>,
 }
 
 /// Flow regime
@@ -254,6 +323,20 @@ pub enum FlowRegime {
     Supercritical,
 }
 
+/// Rule that governed the tailwater at an outfall
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TailwaterControl {
+    /// Free outfall, leaving the outlet pipe at its own critical or normal depth
+    Free,
+    /// Normal depth in the outlet conduit
+    NormalDepth,
+    /// (dc + D)/2 in the outlet pipe
+    CriticalDepth,
+    /// Specified tailwater or tide stage
+    ReceivingWater,
+}
+
 /// Head loss components
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct HeadLoss {
@@ -347,6 +430,8 @@ pub enum ViolationType {
     Capacity,
     /// Flooding violation
     Flooding,
+    /// Pipe profile (invert or slope) violation
+    Profile,
 }
 
 /// Severity level
@@ -441,6 +526,30 @@ impl Violation {
         }
     }
 
+    /// Create a new surcharge violation, for when design criteria keep the
+    /// HGL below the pipe crowns
+    pub fn surcharge_violation(
+        element_id: String,
+        hgl: f64,
+        crown: f64,
+        severity: Severity,
+    ) -> Self {
+        Self {
+            violation_type: ViolationType::Hgl,
+            severity,
+            element_id: element_id.clone(),
+            message: format!(
+                "HGL at {:.2} ft surcharges {} by {:.2} ft above the pipe crown at {:.2} ft",
+                hgl,
+                element_id,
+                hgl - crown,
+                crown
+            ),
+            value: Some(hgl),
+            limit: Some(crown),
+        }
+    }
+
     /// Create a new spread violation
     pub fn spread_violation(
         element_id: String,
@@ -480,6 +589,76 @@ impl Violation {
             limit: Some(1.0),
         }
     }
+
+    /// Create a new velocity violation
+    ///
+    /// The message depends on whether `velocity` is below or above `limit`.
+    pub fn velocity_violation(
+        element_id: String,
+        velocity: f64,
+        limit: f64,
+        severity: Severity,
+    ) -> Self {
+        let message = if velocity < limit {
+            format!(
+                "Velocity of {:.2} ft/s in {} is below the minimum self-cleansing velocity of {:.2} ft/s",
+                velocity, element_id, limit
+            )
+        } else {
+            format!(
+                "Velocity of {:.2} ft/s in {} exceeds the maximum velocity of {:.2} ft/s",
+                velocity, element_id, limit
+            )
+        };
+
+        Self {
+            violation_type: ViolationType::Velocity,
+            severity,
+            element_id,
+            message,
+            value: Some(velocity),
+            limit: Some(limit),
+        }
+    }
+
+    /// Create a new cover violation
+    pub fn cover_violation(
+        element_id: String,
+        cover: f64,
+        min_cover: f64,
+        severity: Severity,
+    ) -> Self {
+        Self {
+            violation_type: ViolationType::Cover,
+            severity,
+            element_id: element_id.clone(),
+            message: format!(
+                "Cover of {:.2} ft over {} is less than the minimum cover of {:.2} ft",
+                cover, element_id, min_cover
+            ),
+            value: Some(cover),
+            limit: Some(min_cover),
+        }
+    }
+
+    /// Create a new flooding violation
+    pub fn flooding_violation(
+        element_id: String,
+        flood_volume: f64,
+        severity: Severity,
+    ) -> Self {
+        Self {
+            violation_type: ViolationType::Flooding,
+            severity,
+            element_id: element_id.clone(),
+            message: format!(
+                "Node {} overflows its rim, losing {:.0} ft³ to the surface",
+                element_id, flood_volume
+            ),
+            value: Some(flood_volume),
+            limit: Some(0.0),
+        }
+    }
 }
 
 // Note: Using chrono for timestamps. Add to Cargo.toml if not present:
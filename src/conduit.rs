@@ -5,6 +5,8 @@
 //! - Gutters: Surface flow along roadways
 //! - Channels: Open channels (trapezoidal, natural)
 
+use crate::gutter::{CompositeGutter, GutterSection, ParabolicCrown, UniformGutter};
+use crate::project::UnitSystem;
 use serde::{Deserialize, Serialize};
 
 /// A conduit in the drainage network
@@ -80,15 +82,16 @@ pub struct PipeProperties {
     /// Pipe cross-sectional shape
     pub shape: PipeShape,
 
-    /// Pipe diameter for circular pipes (in or mm)
+    /// Pipe diameter for circular pipes, or the equivalent round size of a
+    /// standard elliptical or arch pipe (in or mm)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub diameter: Option<f64>,
 
-    /// Width for rectangular/elliptical pipes (in or mm)
+    /// Span (inside width) for box, elliptical and arch pipes (in or mm)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub width: Option<f64>,
 
-    /// Height for rectangular/elliptical pipes (in or mm)
+    /// Rise (inside height) for box, elliptical and arch pipes (in or mm)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub height: Option<f64>,
 
@@ -114,6 +117,28 @@ pub struct PipeProperties {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "bendLoss")]
     pub bend_loss: Option<f64>,
+
+    /// Inlet and roadway of a pipe analyzed as a culvert
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub culvert: Option<CulvertProperties>,
+}
+
+impl PipeProperties {
+    /// Inside rise and span in nominal units (in or mm)
+    ///
+    /// Box pipes need `width` and `height`. Elliptical and arch pipes take
+    /// them if given, or else the standard size for the equivalent round
+    /// `diameter`.
+    pub fn rise_and_span(&self, unit_system: UnitSystem) -> Option<(f64, f64)> {
+        match self.shape {
+            PipeShape::Circular => self.diameter.map(|d| (d, d)),
+            PipeShape::Rectangular => Some((self.height?, self.width?)),
+            PipeShape::Elliptical | PipeShape::Arch => match (self.height, self.width) {
+                (Some(rise), Some(span)) => Some((rise, span)),
+                _ => self.shape.standard_size(self.diameter?, unit_system),
+            },
+        }
+    }
 }
 
 /// Pipe cross-sectional shape
@@ -124,12 +149,163 @@ pub enum PipeShape {
     Circular,
     /// Rectangular box culvert
     Rectangular,
-    /// Elliptical pipe
+    /// Horizontal elliptical pipe
     Elliptical,
     /// Arch pipe
     Arch,
 }
 
+impl PipeShape {
+    /// Rise and span of a standard concrete pipe with the given equivalent
+    /// round size
+    ///
+    /// Horizontal elliptical sizes follow ASTM C507 and arch sizes ASTM C506.
+    /// Sizes are in inches (US); millimeter sizes use the metric equivalent
+    /// round size (25 mm per inch), converted exactly.
+    ///
+    /// # Returns
+    /// `(rise, span)` in inches or millimeters, or `None` if the size is not
+    /// standard for the shape
+    pub fn standard_size(&self, equivalent_diameter: f64, unit_system: UnitSystem) -> Option<(f64, f64)> {
+        const ELLIPTICAL: &[(f64, f64, f64)] = &[
+            (18.0, 14.0, 23.0),
+            (24.0, 19.0, 30.0),
+            (27.0, 22.0, 34.0),
+            (30.0, 24.0, 38.0),
+            (33.0, 27.0, 42.0),
+            (36.0, 29.0, 45.0),
+            (39.0, 32.0, 49.0),
+            (42.0, 34.0, 53.0),
+            (48.0, 38.0, 60.0),
+            (54.0, 43.0, 68.0),
+            (60.0, 48.0, 76.0),
+            (66.0, 53.0, 83.0),
+            (72.0, 58.0, 91.0),
+            (78.0, 63.0, 98.0),
+            (84.0, 68.0, 106.0),
+            (90.0, 72.0, 113.0),
+            (96.0, 77.0, 121.0),
+            (102.0, 82.0, 128.0),
+            (108.0, 87.0, 136.0),
+            (114.0, 92.0, 143.0),
+            (120.0, 97.0, 151.0),
+            (132.0, 106.0, 166.0),
+            (144.0, 116.0, 180.0),
+        ];
+        const ARCH: &[(f64, f64, f64)] = &[
+            (15.0, 11.0, 18.0),
+            (18.0, 13.5, 22.0),
+            (21.0, 15.5, 26.0),
+            (24.0, 18.0, 28.5),
+            (30.0, 22.5, 36.25),
+            (36.0, 26.625, 43.75),
+            (42.0, 31.3125, 51.125),
+            (48.0, 36.0, 58.5),
+            (54.0, 40.0, 65.0),
+            (60.0, 45.0, 73.0),
+            (72.0, 54.0, 88.0),
+            (84.0, 62.0, 102.0),
+            (90.0, 72.0, 115.0),
+            (96.0, 77.25, 122.0),
+            (108.0, 87.125, 138.0),
+            (120.0, 96.875, 154.0),
+            (132.0, 106.5, 168.75),
+        ];
+
+        let table = match self {
+            PipeShape::Elliptical => ELLIPTICAL,
+            PipeShape::Arch => ARCH,
+            PipeShape::Circular | PipeShape::Rectangular => return None,
+        };
+        let (inches, scale) = match unit_system {
+            UnitSystem::US => (equivalent_diameter, 1.0),
+            UnitSystem::SI => (equivalent_diameter / 25.0, 25.4),
+        };
+        table
+            .iter()
+            .find(|&&(size, _, _)| (size - inches).abs() < 0.5)
+            .map(|&(_, rise, span)| (rise * scale, span * scale))
+    }
+}
+
+/// Culvert properties
+///
+/// A pipe with culvert properties is analyzed for inlet and outlet control
+/// following FHWA HDS-5, and the governing headwater becomes the HGL at its
+/// upstream node.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CulvertProperties {
+    /// Inlet configuration
+    pub inlet: CulvertInlet,
+
+    /// Roadway crossing the culvert, checked for overtopping
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roadway: Option<RoadwayProperties>,
+}
+
+/// Culvert inlet configuration
+///
+/// The common inlets of HDS-5 Table A.1 for concrete and corrugated metal
+/// pipe and concrete box culverts.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CulvertInlet {
+    /// Concrete pipe, square edge with headwall
+    ConcreteSquareEdge,
+    /// Concrete pipe, groove end with headwall
+    ConcreteGrooveEnd,
+    /// Concrete pipe, groove end projecting
+    ConcreteProjecting,
+    /// Corrugated metal pipe with headwall
+    MetalHeadwall,
+    /// Corrugated metal pipe mitered to the fill slope
+    MetalMitered,
+    /// Corrugated metal pipe projecting
+    MetalProjecting,
+    /// Box with 30° to 75° wingwall flares
+    BoxFlaredWingwalls,
+    /// Box with 90° or 15° wingwall flares
+    BoxWingwalls,
+    /// Box with 0° wingwall flares (extensions of the sides)
+    BoxParallelWingwalls,
+}
+
+impl CulvertInlet {
+    /// Outlet control entrance loss coefficient Ke (HDS-5 Table C.2)
+    pub fn entrance_loss(&self) -> f64 {
+        match self {
+            CulvertInlet::ConcreteSquareEdge => 0.5,
+            CulvertInlet::ConcreteGrooveEnd => 0.2,
+            CulvertInlet::ConcreteProjecting => 0.2,
+            CulvertInlet::MetalHeadwall => 0.5,
+            CulvertInlet::MetalMitered => 0.7,
+            CulvertInlet::MetalProjecting => 0.9,
+            CulvertInlet::BoxFlaredWingwalls => 0.4,
+            CulvertInlet::BoxWingwalls => 0.5,
+            CulvertInlet::BoxParallelWingwalls => 0.7,
+        }
+    }
+}
+
+/// Roadway embankment over a culvert
+///
+/// Once the headwater rises above the crest, the excess flow passes over
+/// the road as a broad-crested weir.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RoadwayProperties {
+    /// Low point of the roadway crest (ft or m)
+    #[serde(rename = "crestElevation")]
+    pub crest_elevation: f64,
+
+    /// Length of roadway overtopped (ft or m)
+    pub length: f64,
+
+    /// Weir coefficient Cd (default 3.0 US, 1.66 SI)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "weirCoefficient")]
+    pub weir_coefficient: Option<f64>,
+}
+
 /// Pipe material types
 ///
 /// Each material has a typical Manning's n value:
@@ -169,12 +345,59 @@ impl PipeMaterial {
             PipeMaterial::DuctileIron => 0.013,
         }
     }
+
+    /// Standard commercial diameters for this material, smallest first
+    ///
+    /// Returns nominal sizes in inches (US) or millimeters (SI).
+    pub fn standard_diameters(&self, unit_system: UnitSystem) -> &'static [f64] {
+        match (self, unit_system) {
+            (PipeMaterial::RCP | PipeMaterial::Concrete, UnitSystem::US) => &[
+                12.0, 15.0, 18.0, 21.0, 24.0, 27.0, 30.0, 33.0, 36.0, 42.0, 48.0, 54.0, 60.0,
+                66.0, 72.0, 78.0, 84.0, 90.0, 96.0, 102.0, 108.0, 120.0, 132.0, 144.0,
+            ],
+            (PipeMaterial::RCP | PipeMaterial::Concrete, UnitSystem::SI) => &[
+                300.0, 375.0, 450.0, 525.0, 600.0, 675.0, 750.0, 825.0, 900.0, 1050.0, 1200.0,
+                1350.0, 1500.0, 1650.0, 1800.0, 1950.0, 2100.0, 2250.0, 2400.0, 2700.0, 3000.0,
+                3300.0, 3600.0,
+            ],
+            (PipeMaterial::CMP, UnitSystem::US) => &[
+                12.0, 15.0, 18.0, 21.0, 24.0, 30.0, 36.0, 42.0, 48.0, 54.0, 60.0, 66.0, 72.0,
+                78.0, 84.0, 90.0, 96.0, 102.0, 108.0, 114.0, 120.0, 132.0, 144.0,
+            ],
+            (PipeMaterial::CMP, UnitSystem::SI) => &[
+                300.0, 375.0, 450.0, 525.0, 600.0, 750.0, 900.0, 1050.0, 1200.0, 1350.0, 1500.0,
+                1650.0, 1800.0, 1950.0, 2100.0, 2250.0, 2400.0, 2700.0, 3000.0, 3300.0, 3600.0,
+            ],
+            (PipeMaterial::PVC, UnitSystem::US) => &[
+                4.0, 6.0, 8.0, 10.0, 12.0, 15.0, 18.0, 21.0, 24.0, 27.0, 30.0, 36.0, 42.0, 48.0,
+            ],
+            (PipeMaterial::PVC, UnitSystem::SI) => &[
+                100.0, 150.0, 200.0, 250.0, 300.0, 375.0, 450.0, 525.0, 600.0, 675.0, 750.0,
+                900.0, 1050.0, 1200.0,
+            ],
+            (PipeMaterial::HDPE, UnitSystem::US) => &[
+                4.0, 6.0, 8.0, 10.0, 12.0, 15.0, 18.0, 24.0, 30.0, 36.0, 42.0, 48.0, 54.0, 60.0,
+            ],
+            (PipeMaterial::HDPE, UnitSystem::SI) => &[
+                100.0, 150.0, 200.0, 250.0, 300.0, 375.0, 450.0, 600.0, 750.0, 900.0, 1050.0,
+                1200.0, 1350.0, 1500.0,
+            ],
+            (PipeMaterial::Steel | PipeMaterial::DuctileIron, UnitSystem::US) => &[
+                4.0, 6.0, 8.0, 10.0, 12.0, 14.0, 16.0, 18.0, 20.0, 24.0, 30.0, 36.0, 42.0, 48.0,
+                54.0, 60.0, 64.0,
+            ],
+            (PipeMaterial::Steel | PipeMaterial::DuctileIron, UnitSystem::SI) => &[
+                100.0, 150.0, 200.0, 250.0, 300.0, 350.0, 400.0, 450.0, 500.0, 600.0, 750.0,
+                900.0, 1050.0, 1200.0, 1350.0, 1500.0, 1600.0,
+            ],
+        }
+    }
 }
 
 /// Gutter properties
 ///
-/// Gutters are surface flow paths along roadways, analyzed using
-/// the triangular gutter section equations from HEC-22 Chapter 7.
+/// Gutters are surface flow paths along roadways, analyzed using the
+/// uniform, composite, or parabolic section equations from HEC-22 Chapter 5.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct GutterProperties {
     /// Cross slope Sx (ft/ft) - perpendicular to flow
@@ -192,6 +415,66 @@ pub struct GutterProperties {
     /// Manning's roughness coefficient n (typical: 0.016 for asphalt)
     #[serde(rename = "manningN")]
     pub manning_n: f64,
+
+    /// Gutter depression a (in or mm); with a width, makes a composite section
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depression: Option<f64>,
+
+    /// Curb height (in or mm), limiting ponding at sag inlets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "curbHeight")]
+    pub curb_height: Option<f64>,
+
+    /// Crown height of a parabolic section (ft or m)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "crownHeight")]
+    pub crown_height: Option<f64>,
+
+    /// Distance from the curb to the crown of a parabolic section (ft or m)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "widthToCrown")]
+    pub width_to_crown: Option<f64>,
+}
+
+impl GutterProperties {
+    /// Gutter section described by these properties
+    ///
+    /// A crown height and width to crown give a parabolic crown, a width
+    /// with a gutter depression gives a composite section, and anything else
+    /// is a uniform cross slope. The depression is converted from in (mm) to
+    /// ft (m).
+    pub fn section(&self, unit_system: UnitSystem) -> GutterSection {
+        if let (Some(crown_height), Some(width_to_crown)) = (self.crown_height, self.width_to_crown) {
+            return GutterSection::ParabolicCrown(ParabolicCrown::new(
+                self.manning_n,
+                crown_height,
+                width_to_crown,
+                self.longitudinal_slope,
+            ));
+        }
+
+        match (self.width, self.depression) {
+            (Some(width), Some(depression)) if width > 0.0 && depression > 0.0 => {
+                GutterSection::Composite(CompositeGutter::new(
+                    self.manning_n,
+                    self.cross_slope,
+                    self.cross_slope,
+                    self.longitudinal_slope,
+                    width,
+                    match unit_system {
+                        UnitSystem::US => depression / 12.0,
+                        UnitSystem::SI => depression / 1000.0,
+                    },
+                ))
+            }
+            _ => GutterSection::Uniform(UniformGutter::new(
+                self.manning_n,
+                self.cross_slope,
+                self.longitudinal_slope,
+                self.width,
+            )),
+        }
+    }
 }
 
 /// Open channel properties
@@ -213,6 +496,41 @@ pub struct ChannelProperties {
     #[serde(rename = "sideSlope")]
     pub side_slope: Option<f64>,
 
+    /// Manning's roughness coefficient n; the default for any part of a
+    /// natural section not covered by a subsection
+    #[serde(rename = "manningN")]
+    pub manning_n: f64,
+
+    /// Station/elevation points across a natural channel, left to right
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "crossSection")]
+    pub cross_section: Option<Vec<StationElevation>>,
+
+    /// Roughness subsections of a natural channel (e.g. left overbank,
+    /// main channel, right overbank)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subsections: Option<Vec<ChannelSubsection>>,
+}
+
+/// A point on a channel cross section
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct StationElevation {
+    /// Horizontal station across the section (ft or m)
+    pub station: f64,
+
+    /// Ground elevation (ft or m)
+    pub elevation: f64,
+}
+
+/// A roughness subsection of a natural channel
+///
+/// A subsection runs from its start station to the start of the next one.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ChannelSubsection {
+    /// Station where the subsection begins (ft or m)
+    #[serde(rename = "startStation")]
+    pub start_station: f64,
+
     /// Manning's roughness coefficient n
     #[serde(rename = "manningN")]
     pub manning_n: f64,
@@ -352,6 +670,7 @@ mod tests {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         };
 
         let conduit = Conduit::new_pipe(
@@ -367,6 +686,43 @@ mod tests {
         assert_eq!(conduit.pipe.as_ref().unwrap().diameter, Some(18.0));
     }
 
+    #[test]
+    fn test_standard_pipe_sizes() {
+        use crate::project::UnitSystem;
+
+        assert_eq!(PipeShape::Elliptical.standard_size(24.0, UnitSystem::US), Some((19.0, 30.0)));
+        assert_eq!(PipeShape::Arch.standard_size(36.0, UnitSystem::US), Some((26.625, 43.75)));
+        assert_eq!(PipeShape::Elliptical.standard_size(25.0, UnitSystem::US), None);
+        assert_eq!(PipeShape::Circular.standard_size(24.0, UnitSystem::US), None);
+
+        let (rise, span) = PipeShape::Elliptical.standard_size(600.0, UnitSystem::SI).unwrap();
+        assert!((rise - 482.6).abs() < 1e-9 && (span - 762.0).abs() < 1e-9);
+
+        let mut pipe = PipeProperties {
+            shape: PipeShape::Elliptical,
+            diameter: Some(24.0),
+            width: None,
+            height: None,
+            material: Some(PipeMaterial::RCP),
+            manning_n: 0.013,
+            entrance_loss: None,
+            exit_loss: None,
+            bend_loss: None,
+            culvert: None,
+        };
+        assert_eq!(pipe.rise_and_span(UnitSystem::US), Some((19.0, 30.0)));
+
+        // Explicit dimensions win over the standard size
+        pipe.width = Some(32.0);
+        pipe.height = Some(20.0);
+        assert_eq!(pipe.rise_and_span(UnitSystem::US), Some((20.0, 32.0)));
+
+        // A box needs both dimensions
+        pipe.shape = PipeShape::Rectangular;
+        pipe.height = None;
+        assert_eq!(pipe.rise_and_span(UnitSystem::US), None);
+    }
+
     #[test]
     fn test_pipe_material_manning_n() {
         assert_eq!(PipeMaterial::RCP.typical_manning_n(), 0.013);
@@ -391,6 +747,7 @@ mod tests {
                 entrance_loss: None,
                 exit_loss: None,
                 bend_loss: None,
+                culvert: None,
             },
         );
 
@@ -408,6 +765,10 @@ mod tests {
             longitudinal_slope: 0.015,
             width: Some(12.0),
             manning_n: 0.016,
+            depression: None,
+            curb_height: None,
+            crown_height: None,
+            width_to_crown: None,
         };
 
         let conduit = Conduit::new_gutter(
@@ -421,4 +782,26 @@ mod tests {
         assert!(conduit.is_gutter());
         assert_eq!(conduit.gutter.as_ref().unwrap().cross_slope, 0.02);
     }
+
+    #[test]
+    fn test_composite_gutter_depression_units() {
+        let props = GutterProperties {
+            cross_slope: 0.02,
+            longitudinal_slope: 0.01,
+            width: Some(2.0),
+            manning_n: 0.016,
+            depression: Some(2.0),
+            curb_height: None,
+            crown_height: None,
+            width_to_crown: None,
+        };
+        let depression = |section: GutterSection| match section {
+            GutterSection::Composite(g) => g.local_depression_ft,
+            _ => panic!("expected a composite section"),
+        };
+
+        assert!((depression(props.section(UnitSystem::US)) - 2.0 / 12.0).abs() < 1e-12);
+        let metric = GutterProperties { width: Some(0.6), depression: Some(50.0), ..props };
+        assert!((depression(metric.section(UnitSystem::SI)) - 0.05).abs() < 1e-12);
+    }
 }
@@ -8,23 +8,56 @@
 //! ## Nodes CSV
 //! Columns: `id`, `type`, `invert_elev`, `rim_elev`, `x`, `y`
 //!
+//! Inlets may name the node receiving their bypass flow in `bypass_to`.
+//! A junction or inlet with a flow divider names the `diverted_conduit` and a
+//! `diversion_method` of `fraction` (with `diversion_fraction`), `weir` (with
+//! `crest_height` and `weir_length`) or `orifice` (with `orifice_height` and
+//! `orifice_area`); `diversion_coefficient` overrides the default weir or
+//! orifice coefficient. Rating table dividers are given in JSON input.
+//! Outfalls take a `boundary_condition`, and a `tailwater_rule` of `hec22`
+//! raises the tailwater to (dc + D)/2 in the outlet pipe where that is higher.
+//!
 //! ## Conduits CSV
 //! Columns: `id`, `from_node`, `to_node`, `diameter`, `length`, `slope`, `manning_n`
 //!
+//! Box, elliptical and arch pipes set `shape` and give their inside `width`
+//! and `height` (in); elliptical and arch pipes may instead give the
+//! equivalent round `diameter` of a standard size.
+//! Pipe inverts may be given in the optional `upstream_invert` and
+//! `downstream_invert` columns; otherwise they follow the node inverts.
+//! A pipe with a `culvert_inlet` is analyzed as a culvert, and checked for
+//! roadway overtopping when `road_crest` and `road_length` are given.
+//! Prismatic channels take `bottom_width` and `side_slope`: a channel with
+//! no side slope is rectangular, and one with no bottom width triangular.
+//!
 //! ## Drainage Areas CSV
 //! Columns: `id`, `area`, `runoff_coef`, `time_of_conc`, `outlet_node`
 //!
+//! `time_of_conc` may be left blank to compute Tc from the optional columns
+//! `tc_method`, `flow_length`, `avg_slope`, `sheet_length`, `sheet_slope`,
+//! `sheet_n`, `p2`, `shallow_length`, `shallow_slope`, `surface_type`,
+//! `channel_length`, `channel_slope`, `channel_n`, `hydraulic_radius`
+//!
 //! ## IDF Curves CSV
 //! Columns: `return_period`, `duration`, `intensity`
 //!
 //! ## Gutter Parameters CSV
 //! Columns: `node_id`, `cross_slope`, `long_slope`, `curb_height`, `gutter_width`
+//!
+//! A `depression` with a gutter width describes a composite gutter, and the
+//! optional `crown_height` and `width_to_crown` columns a parabolic crown.
 
-use crate::conduit::{Conduit, ConduitType, GutterProperties, PipeMaterial, PipeProperties, PipeShape};
-use crate::drainage::{DrainageArea, LandUse, LandUseType};
-use crate::node::{BoundaryCondition, Coordinates, InletLocation, InletProperties, InletType, JunctionProperties, Node, NodeType, OutfallProperties};
-use csv::{Reader, ReaderBuilder};
-use serde::Deserialize;
+use crate::conduit::{
+    ChannelProperties, ChannelShape, Conduit, ConduitType, CulvertInlet, CulvertProperties, GutterProperties,
+    PipeMaterial, PipeProperties, PipeShape, RoadwayProperties,
+};
+use crate::drainage::{
+    ChannelFlow, DrainageArea, LandUse, LandUseType, ShallowConcentratedFlow, SheetFlow, SurfaceType,
+    TcCalculation, TcMethod,
+};
+use crate::node::{BoundaryCondition, Coordinates, DiversionMethod, FlowDivider, InletLocation, InletProperties, InletType, JunctionProperties, Node, NodeType, OutfallProperties, TailwaterRule};
+use csv::{Reader, ReaderBuilder, Writer};
+use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::fs::File;
 use std::path::Path;
@@ -55,6 +88,26 @@ pub struct NodeCsvRecord {
     pub inlet_type: Option<String>,
     /// Boundary condition - optional, for outfalls: "free", "normal", "fixed"
     pub boundary_condition: Option<String>,
+    /// Tailwater rule - optional, for outfalls: "boundary", "hec22"
+    pub tailwater_rule: Option<String>,
+    /// Node receiving bypass flow - optional, for inlets
+    pub bypass_to: Option<String>,
+    /// Conduit receiving diverted flow - optional, for flow dividers
+    pub diverted_conduit: Option<String>,
+    /// Diversion method - optional: "fraction", "weir", "orifice"
+    pub diversion_method: Option<String>,
+    /// Fraction of inflow diverted (0.0-1.0) - optional, for fraction dividers
+    pub diversion_fraction: Option<f64>,
+    /// Weir crest height above the invert (ft) - optional, for weir dividers
+    pub crest_height: Option<f64>,
+    /// Weir length (ft) - optional, for weir dividers
+    pub weir_length: Option<f64>,
+    /// Orifice center height above the invert (ft) - optional, for orifice dividers
+    pub orifice_height: Option<f64>,
+    /// Orifice area (sq ft) - optional, for orifice dividers
+    pub orifice_area: Option<f64>,
+    /// Weir or orifice coefficient - optional
+    pub diversion_coefficient: Option<f64>,
 }
 
 impl NodeCsvRecord {
@@ -80,6 +133,7 @@ impl NodeCsvRecord {
                     Some("grate") => InletType::Grate,
                     Some("curb") => InletType::CurbOpening,
                     Some("combination") => InletType::Combination,
+                    Some("sweeper") => InletType::Sweeper,
                     Some("slotted") => InletType::Slotted,
                     None => InletType::Combination, // default
                     Some(t) => return Err(format!("Unknown inlet type: {}", t).into()),
@@ -94,11 +148,14 @@ impl NodeCsvRecord {
                         location: InletLocation::OnGrade, // default
                         grate: None,
                         curb_opening: None,
+                        slotted: None,
                         local_depression: None,
                         clogging_factor: None,
+                        bypass_to: self.bypass_to.clone(),
                     },
                 );
                 node.coordinates = coordinates;
+                node.divider = self.to_divider()?;
                 Ok(node)
             }
             "junction" | "manhole" => {
@@ -116,6 +173,7 @@ impl NodeCsvRecord {
                     },
                 );
                 node.coordinates = coordinates;
+                node.divider = self.to_divider()?;
                 Ok(node)
             }
             "outfall" => {
@@ -126,6 +184,12 @@ impl NodeCsvRecord {
                     None => BoundaryCondition::Free, // default
                     Some(bc) => return Err(format!("Unknown boundary condition: {}", bc).into()),
                 };
+                let tailwater_rule = match self.tailwater_rule.as_deref() {
+                    Some("boundary") => Some(TailwaterRule::Boundary),
+                    Some("hec22") => Some(TailwaterRule::Hec22),
+                    None => None,
+                    Some(rule) => return Err(format!("Unknown tailwater rule: {}", rule).into()),
+                };
 
                 let mut node = Node::new_outfall(
                     self.id.clone(),
@@ -134,6 +198,10 @@ impl NodeCsvRecord {
                         boundary_condition,
                         tailwater_elevation: None,
                         tidal_curve: None,
+                        tidal_stage: None,
+                        storm_start: None,
+                        flap_gate: None,
+                        tailwater_rule,
                     },
                 );
                 node.coordinates = coordinates;
@@ -142,6 +210,36 @@ impl NodeCsvRecord {
             _ => Err(format!("Unknown node type: {}", self.node_type).into()),
         }
     }
+
+    /// Build the flow divider from the optional diversion columns
+    fn to_divider(&self) -> Result<Option<FlowDivider>, Box<dyn Error>> {
+        let Some(ref diverted_conduit) = self.diverted_conduit else {
+            return Ok(None);
+        };
+
+        let method = match self.diversion_method.as_deref().map(|m| m.to_lowercase()).as_deref() {
+            Some("fraction") => DiversionMethod::Fraction {
+                fraction: self.diversion_fraction.ok_or("diversion_fraction required for fraction dividers")?,
+            },
+            Some("weir") => DiversionMethod::Weir {
+                crest_height: self.crest_height.ok_or("crest_height required for weir dividers")?,
+                length: self.weir_length.ok_or("weir_length required for weir dividers")?,
+                coefficient: self.diversion_coefficient,
+            },
+            Some("orifice") => DiversionMethod::Orifice {
+                center_height: self.orifice_height.ok_or("orifice_height required for orifice dividers")?,
+                area: self.orifice_area.ok_or("orifice_area required for orifice dividers")?,
+                coefficient: self.diversion_coefficient,
+            },
+            None => return Err("diversion_method required with diverted_conduit".into()),
+            Some(m) => return Err(format!("Unknown diversion method: {}", m).into()),
+        };
+
+        Ok(Some(FlowDivider {
+            diverted_conduit: diverted_conduit.clone(),
+            method,
+        }))
+    }
 }
 
 /// Parse nodes from CSV file
@@ -169,7 +267,7 @@ pub fn parse_nodes_csv<P: AsRef<Path>>(path: P) -> Result<Vec<Node>, Box<dyn Err
 // ============================================================================
 
 /// CSV record for a conduit (pipe or gutter)
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ConduitCsvRecord {
     /// Conduit ID
     pub id: String,
@@ -177,11 +275,17 @@ pub struct ConduitCsvRecord {
     pub from_node: String,
     /// To node ID
     pub to_node: String,
-    /// Conduit type: "pipe" or "gutter"
+    /// Conduit type: "pipe", "gutter" or "channel"
     #[serde(rename = "type")]
     pub conduit_type: Option<String>,
     /// Pipe diameter (inches) - for pipes
     pub diameter: Option<f64>,
+    /// Pipe shape - optional: "circular" (default), "box", "elliptical", "arch"
+    pub shape: Option<String>,
+    /// Inside span (inches) - for box, elliptical and arch pipes
+    pub width: Option<f64>,
+    /// Inside rise (inches) - for box, elliptical and arch pipes
+    pub height: Option<f64>,
     /// Conduit length (ft)
     pub length: f64,
     /// Slope (ft/ft) - optional
@@ -194,6 +298,21 @@ pub struct ConduitCsvRecord {
     pub cross_slope: Option<f64>,
     /// Longitudinal slope (ft/ft) - for gutters
     pub long_slope: Option<f64>,
+    /// Bottom width (ft) - for channels
+    pub bottom_width: Option<f64>,
+    /// Side slope (H:V) - for channels
+    pub side_slope: Option<f64>,
+    /// Upstream invert elevation (ft) - optional, defaults to the from node invert
+    pub upstream_invert: Option<f64>,
+    /// Downstream invert elevation (ft) - optional, defaults to the to node invert
+    pub downstream_invert: Option<f64>,
+    /// Culvert inlet configuration - optional, analyzes the pipe as a culvert
+    /// (e.g. "concrete-square-edge", "metal-projecting", "box-flared-wingwalls")
+    pub culvert_inlet: Option<String>,
+    /// Roadway crest elevation over a culvert (ft) - optional
+    pub road_crest: Option<f64>,
+    /// Length of roadway overtopped (ft) - required with `road_crest`
+    pub road_length: Option<f64>,
 }
 
 impl ConduitCsvRecord {
@@ -203,38 +322,83 @@ impl ConduitCsvRecord {
 
         match conduit_type.to_lowercase().as_str() {
             "pipe" => {
-                let diameter = self.diameter.ok_or("diameter required for pipes")?;
+                let shape = match self.shape.as_deref().map(str::to_lowercase).as_deref() {
+                    None | Some("circular") => PipeShape::Circular,
+                    Some("box") | Some("rectangular") => PipeShape::Rectangular,
+                    Some("elliptical") => PipeShape::Elliptical,
+                    Some("arch") => PipeShape::Arch,
+                    Some(s) => return Err(format!("Unknown pipe shape: {}", s).into()),
+                };
+                match shape {
+                    PipeShape::Circular if self.diameter.is_none() => {
+                        return Err("diameter required for pipes".into())
+                    }
+                    PipeShape::Rectangular if self.width.is_none() || self.height.is_none() => {
+                        return Err("width and height required for box pipes".into())
+                    }
+                    PipeShape::Elliptical | PipeShape::Arch
+                        if self.diameter.is_none() && (self.width.is_none() || self.height.is_none()) =>
+                    {
+                        return Err("diameter or width and height required for elliptical and arch pipes".into())
+                    }
+                    _ => {}
+                }
                 let material = match self.material.as_deref() {
                     Some("RCP") | Some("rcp") => Some(PipeMaterial::RCP),
                     Some("CMP") | Some("cmp") => Some(PipeMaterial::CMP),
                     Some("PVC") | Some("pvc") => Some(PipeMaterial::PVC),
                     Some("HDPE") | Some("hdpe") => Some(PipeMaterial::HDPE),
+                    Some("Concrete") | Some("concrete") => Some(PipeMaterial::Concrete),
+                    Some("Steel") | Some("steel") => Some(PipeMaterial::Steel),
+                    Some("DuctileIron") | Some("Ductile Iron") | Some("ductile iron") => Some(PipeMaterial::DuctileIron),
                     None => Some(PipeMaterial::RCP), // default
                     Some(m) => return Err(format!("Unknown material: {}", m).into()),
                 };
 
+                let culvert = match self.culvert_inlet.as_deref() {
+                    Some(inlet) => {
+                        let inlet: CulvertInlet = serde_json::from_value(inlet.to_lowercase().into())
+                            .map_err(|_| format!("Unknown culvert inlet: {}", inlet))?;
+                        let roadway = match (self.road_crest, self.road_length) {
+                            (Some(crest_elevation), Some(length)) => Some(RoadwayProperties {
+                                crest_elevation,
+                                length,
+                                weir_coefficient: None,
+                            }),
+                            (Some(_), None) => return Err("road_length required with road_crest".into()),
+                            _ => None,
+                        };
+                        Some(CulvertProperties { inlet, roadway })
+                    }
+                    None => None,
+                };
+
                 // Use material's typical n value if not specified
                 let manning_n = self.manning_n.unwrap_or_else(|| {
                     material.as_ref().map(|m| m.typical_manning_n()).unwrap_or(0.013)
                 });
 
-                Ok(Conduit::new_pipe(
+                let mut conduit = Conduit::new_pipe(
                     self.id.clone(),
                     self.from_node.clone(),
                     self.to_node.clone(),
                     self.length,
                     PipeProperties {
-                        shape: PipeShape::Circular,
-                        diameter: Some(diameter),
-                        width: None,
-                        height: None,
+                        shape,
+                        diameter: self.diameter,
+                        width: self.width,
+                        height: self.height,
                         material,
                         manning_n,
                         entrance_loss: None,
                         exit_loss: None,
                         bend_loss: None,
+                        culvert,
                     },
-                ))
+                );
+                conduit.upstream_invert = self.upstream_invert;
+                conduit.downstream_invert = self.downstream_invert;
+                Ok(conduit)
             }
             "gutter" => {
                 let cross_slope = self.cross_slope.ok_or("cross_slope required for gutters")?;
@@ -251,12 +415,113 @@ impl ConduitCsvRecord {
                         longitudinal_slope: long_slope,
                         width: None,
                         manning_n,
+                        depression: None,
+                        curb_height: None,
+                        crown_height: None,
+                        width_to_crown: None,
                     },
                 ))
             }
+            "channel" => {
+                let bottom_width = self.bottom_width.filter(|&w| w > 0.0);
+                let side_slope = self.side_slope.filter(|&z| z > 0.0);
+                let shape = match (bottom_width, side_slope) {
+                    (Some(_), Some(_)) => ChannelShape::Trapezoidal,
+                    (Some(_), None) => ChannelShape::Rectangular,
+                    (None, Some(_)) => ChannelShape::Triangular,
+                    (None, None) => return Err("bottom_width or side_slope required for channels".into()),
+                };
+                let manning_n = self.manning_n.unwrap_or(0.030); // default for a grass-lined channel
+
+                let mut conduit = Conduit::new_channel(
+                    self.id.clone(),
+                    self.from_node.clone(),
+                    self.to_node.clone(),
+                    self.length,
+                    ChannelProperties {
+                        shape,
+                        bottom_width,
+                        side_slope,
+                        manning_n,
+                        cross_section: None,
+                        subsections: None,
+                    },
+                );
+                conduit.upstream_invert = self.upstream_invert;
+                conduit.downstream_invert = self.downstream_invert;
+                Ok(conduit)
+            }
             _ => Err(format!("Unknown conduit type: {}", conduit_type).into()),
         }
     }
+
+    /// Create a CSV record from a pipe, gutter or prismatic channel conduit
+    pub fn from_conduit(conduit: &Conduit) -> Self {
+        let channel = conduit.channel.as_ref();
+        let pipe = conduit.pipe.as_ref().filter(|_| conduit.conduit_type == ConduitType::Pipe);
+        let culvert = pipe.and_then(|p| p.culvert.as_ref());
+        let roadway = culvert.and_then(|c| c.roadway.as_ref());
+        let (conduit_type, diameter, manning_n, material, cross_slope, long_slope) = match conduit.conduit_type {
+            ConduitType::Gutter => {
+                let gutter = conduit.gutter.as_ref();
+                (
+                    "gutter",
+                    None,
+                    gutter.map(|g| g.manning_n),
+                    None,
+                    gutter.map(|g| g.cross_slope),
+                    gutter.map(|g| g.longitudinal_slope),
+                )
+            }
+            ConduitType::Channel => ("channel", None, channel.map(|c| c.manning_n), None, None, None),
+            _ => {
+                (
+                    "pipe",
+                    pipe.and_then(|p| p.diameter),
+                    pipe.map(|p| p.manning_n),
+                    pipe.and_then(|p| p.material).map(|m| format!("{:?}", m)),
+                    None,
+                    None,
+                )
+            }
+        };
+
+        Self {
+            id: conduit.id.clone(),
+            from_node: conduit.from_node.clone(),
+            to_node: conduit.to_node.clone(),
+            conduit_type: Some(conduit_type.to_string()),
+            diameter,
+            shape: pipe.map(|p| format!("{:?}", p.shape).to_lowercase()),
+            width: pipe.and_then(|p| p.width),
+            height: pipe.and_then(|p| p.height),
+            length: conduit.length,
+            slope: conduit.effective_slope(),
+            manning_n,
+            material,
+            cross_slope,
+            long_slope,
+            bottom_width: channel.and_then(|c| c.bottom_width),
+            side_slope: channel.and_then(|c| c.side_slope),
+            upstream_invert: conduit.upstream_invert,
+            downstream_invert: conduit.downstream_invert,
+            culvert_inlet: culvert
+                .and_then(|c| serde_json::to_value(c.inlet).ok())
+                .and_then(|v| v.as_str().map(str::to_string)),
+            road_crest: roadway.map(|r| r.crest_elevation),
+            road_length: roadway.map(|r| r.length),
+        }
+    }
+}
+
+/// Write conduits to a CSV file in the format read by [`parse_conduits_csv`]
+pub fn write_conduits_csv<P: AsRef<Path>>(path: P, conduits: &[Conduit]) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(path)?;
+    for conduit in conduits {
+        writer.serialize(ConduitCsvRecord::from_conduit(conduit))?;
+    }
+    writer.flush()?;
+    Ok(())
 }
 
 /// Parse conduits from CSV file
@@ -292,17 +557,45 @@ pub struct DrainageAreaCsvRecord {
     pub area: f64,
     /// Runoff coefficient (0-1)
     pub runoff_coef: f64,
-    /// Time of concentration (minutes)
-    pub time_of_conc: f64,
+    /// Time of concentration (minutes) - optional, computed from the Tc columns if blank
+    pub time_of_conc: Option<f64>,
     /// Outlet node ID
     pub outlet_node: String,
     /// Land use description - optional
     pub land_use: Option<String>,
+    /// Tc method (velocity, kinematic wave, kirpich, faa) - optional
+    pub tc_method: Option<String>,
+    /// Longest flow path length for Kirpich/FAA (ft) - optional
+    pub flow_length: Option<f64>,
+    /// Average flow path slope for Kirpich/FAA (ft/ft) - optional
+    pub avg_slope: Option<f64>,
+    /// Sheet flow length (ft) - optional
+    pub sheet_length: Option<f64>,
+    /// Sheet flow slope (ft/ft) - optional
+    pub sheet_slope: Option<f64>,
+    /// Sheet flow Manning's n - optional
+    pub sheet_n: Option<f64>,
+    /// 2-year, 24-hour rainfall depth (in) - optional
+    pub p2: Option<f64>,
+    /// Shallow concentrated flow length (ft) - optional
+    pub shallow_length: Option<f64>,
+    /// Shallow concentrated flow slope (ft/ft) - optional
+    pub shallow_slope: Option<f64>,
+    /// Shallow concentrated surface type (paved, unpaved, grassed waterway) - optional
+    pub surface_type: Option<String>,
+    /// Channel flow length (ft) - optional
+    pub channel_length: Option<f64>,
+    /// Channel slope (ft/ft) - optional
+    pub channel_slope: Option<f64>,
+    /// Channel Manning's n - optional
+    pub channel_n: Option<f64>,
+    /// Channel hydraulic radius (ft) - optional
+    pub hydraulic_radius: Option<f64>,
 }
 
 impl DrainageAreaCsvRecord {
     /// Convert CSV record to DrainageArea
-    pub fn to_drainage_area(&self) -> DrainageArea {
+    pub fn to_drainage_area(&self) -> Result<DrainageArea, Box<dyn Error>> {
         // Convert land use string to LandUseType
         let land_use = self.land_use.as_ref().and_then(|lu_str| {
             let land_use_type = match lu_str.to_lowercase().as_str() {
@@ -323,18 +616,96 @@ impl DrainageAreaCsvRecord {
             })
         });
 
-        DrainageArea {
+        let tc_calculation = self.to_tc_calculation()?;
+        if self.time_of_conc.is_none() && tc_calculation.is_none() {
+            return Err("time_of_conc or Tc flow path columns required".into());
+        }
+
+        Ok(DrainageArea {
             id: self.id.clone(),
             name: None,
             area: self.area,
             outlet: self.outlet_node.clone(),
             land_use,
             runoff_coefficient: Some(self.runoff_coef),
-            time_of_concentration: Some(self.time_of_conc),
-            tc_calculation: None,
+            time_of_concentration: self.time_of_conc,
+            tc_calculation,
             curve_number: None,
             geometry: None,
+        })
+    }
+
+    /// Build the Tc breakdown from the optional flow path columns
+    fn to_tc_calculation(&self) -> Result<Option<TcCalculation>, Box<dyn Error>> {
+        let method = match self.tc_method.as_deref().map(|m| m.to_lowercase()) {
+            None => None,
+            Some(m) => Some(match m.as_str() {
+                "velocity" | "tr-55" | "tr55" => TcMethod::Velocity,
+                "kinematic wave" | "kinematic_wave" | "kinematicwave" => TcMethod::KinematicWave,
+                "kirpich" => TcMethod::Kirpich,
+                "faa" => TcMethod::Faa,
+                _ => return Err(format!("Unknown Tc method: {}", m).into()),
+            }),
+        };
+
+        let sheet_flow = match (self.sheet_length, self.sheet_slope, self.sheet_n) {
+            (Some(length), Some(slope), Some(roughness)) => Some(SheetFlow {
+                length,
+                slope,
+                roughness,
+                rainfall_depth_2yr: self.p2,
+                time: None,
+            }),
+            (None, None, None) => None,
+            _ => return Err("sheet_length, sheet_slope and sheet_n must be given together".into()),
+        };
+
+        let shallow_concentrated = match (self.shallow_length, self.shallow_slope) {
+            (Some(length), Some(slope)) => {
+                let surface_type = match self.surface_type.as_deref().map(|t| t.to_lowercase()) {
+                    None => SurfaceType::Paved,
+                    Some(t) => match t.as_str() {
+                        "paved" => SurfaceType::Paved,
+                        "unpaved" => SurfaceType::Unpaved,
+                        "grassed waterway" | "grassed_waterway" | "grass" => SurfaceType::GrassedWaterway,
+                        _ => return Err(format!("Unknown surface type: {}", t).into()),
+                    },
+                };
+                Some(ShallowConcentratedFlow {
+                    length,
+                    slope,
+                    surface_type,
+                    time: None,
+                })
+            }
+            (None, None) => None,
+            _ => return Err("shallow_length and shallow_slope must be given together".into()),
+        };
+
+        let channel_flow = self.channel_length.map(|length| ChannelFlow {
+            length,
+            velocity: None,
+            roughness: self.channel_n,
+            slope: self.channel_slope,
+            hydraulic_radius: self.hydraulic_radius,
+            time: None,
+        });
+
+        let has_flow_path = self.flow_length.is_some() || self.avg_slope.is_some();
+        if method.is_none() && sheet_flow.is_none() && shallow_concentrated.is_none()
+            && channel_flow.is_none() && !has_flow_path
+        {
+            return Ok(None);
         }
+
+        Ok(Some(TcCalculation {
+            method,
+            sheet_flow,
+            shallow_concentrated,
+            channel_flow,
+            flow_length: self.flow_length,
+            slope: self.avg_slope,
+        }))
     }
 }
 
@@ -350,7 +721,9 @@ pub fn parse_drainage_areas_csv<P: AsRef<Path>>(path: P) -> Result<Vec<DrainageA
     for (line_num, result) in reader.deserialize().enumerate() {
         let record: DrainageAreaCsvRecord = result
             .map_err(|e| format!("Line {}: {}", line_num + 2, e))?;
-        areas.push(record.to_drainage_area());
+        let area = record.to_drainage_area()
+            .map_err(|e| format!("Line {} (drainage area {}): {}", line_num + 2, record.id, e))?;
+        areas.push(area);
     }
 
     Ok(areas)
@@ -443,6 +816,29 @@ pub struct GutterParametersCsvRecord {
     pub depression: Option<f64>,
     /// Depression width (ft) - optional
     pub depression_width: Option<f64>,
+    /// Parabolic crown height (ft) - optional
+    pub crown_height: Option<f64>,
+    /// Distance from curb to crown (ft) - optional
+    pub width_to_crown: Option<f64>,
+}
+
+impl GutterParametersCsvRecord {
+    /// Gutter properties for the inlet, defaulting Manning's n to 0.016
+    ///
+    /// The gutter width falls back to the depression width, so a depressed
+    /// gutter can be described by either column.
+    pub fn to_gutter_properties(&self) -> GutterProperties {
+        GutterProperties {
+            cross_slope: self.cross_slope,
+            longitudinal_slope: self.long_slope,
+            width: self.gutter_width.or(self.depression_width),
+            manning_n: self.manning_n.unwrap_or(0.016),
+            depression: self.depression,
+            curb_height: self.curb_height,
+            crown_height: self.crown_height,
+            width_to_crown: self.width_to_crown,
+        }
+    }
 }
 
 /// Parse gutter parameters from CSV file
@@ -465,6 +861,8 @@ pub fn parse_gutter_parameters_csv<P: AsRef<Path>>(path: P) -> Result<Vec<Gutter
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::gutter::GutterSectionType;
+    use crate::project::UnitSystem;
 
     #[test]
     fn test_node_csv_record_to_inlet() {
@@ -478,11 +876,22 @@ mod tests {
             diameter: None,
             inlet_type: Some("grate".to_string()),
             boundary_condition: None,
+            tailwater_rule: None,
+            bypass_to: Some("IN-002".to_string()),
+            diverted_conduit: None,
+            diversion_method: None,
+            diversion_fraction: None,
+            crest_height: None,
+            weir_length: None,
+            orifice_height: None,
+            orifice_area: None,
+            diversion_coefficient: None,
         };
 
         let node = record.to_node().unwrap();
         assert_eq!(node.id, "IN-001");
         assert_eq!(node.node_type, NodeType::Inlet);
+        assert_eq!(node.inlet.unwrap().bypass_to.as_deref(), Some("IN-002"));
     }
 
     #[test]
@@ -497,11 +906,61 @@ mod tests {
             diameter: Some(4.0),
             inlet_type: None,
             boundary_condition: None,
+            tailwater_rule: None,
+            bypass_to: None,
+            diverted_conduit: Some("P-RELIEF".to_string()),
+            diversion_method: Some("weir".to_string()),
+            diversion_fraction: None,
+            crest_height: Some(1.5),
+            weir_length: Some(4.0),
+            orifice_height: None,
+            orifice_area: None,
+            diversion_coefficient: None,
         };
 
         let node = record.to_node().unwrap();
         assert_eq!(node.id, "MH-001");
         assert_eq!(node.node_type, NodeType::Junction);
+        assert_eq!(
+            node.divider,
+            Some(FlowDivider {
+                diverted_conduit: "P-RELIEF".to_string(),
+                method: DiversionMethod::Weir { crest_height: 1.5, length: 4.0, coefficient: None },
+            })
+        );
+
+        // A weir divider needs its crest and length
+        let record = NodeCsvRecord { weir_length: None, ..record };
+        assert!(record.to_node().is_err());
+    }
+
+    #[test]
+    fn test_node_csv_record_to_outfall() {
+        let record = NodeCsvRecord {
+            id: "OUT-001".to_string(),
+            node_type: "outfall".to_string(),
+            invert_elev: 90.0,
+            rim_elev: None,
+            x: None,
+            y: None,
+            diameter: None,
+            inlet_type: None,
+            boundary_condition: Some("normal".to_string()),
+            tailwater_rule: Some("hec22".to_string()),
+            bypass_to: None,
+            diverted_conduit: None,
+            diversion_method: None,
+            diversion_fraction: None,
+            crest_height: None,
+            weir_length: None,
+            orifice_height: None,
+            orifice_area: None,
+            diversion_coefficient: None,
+        };
+
+        let outfall = record.to_node().unwrap().outfall.unwrap();
+        assert_eq!(outfall.boundary_condition, BoundaryCondition::NormalDepth);
+        assert_eq!(outfall.tailwater_rule, Some(TailwaterRule::Hec22));
     }
 
     #[test]
@@ -512,17 +971,141 @@ mod tests {
             to_node: "MH-002".to_string(),
             conduit_type: Some("pipe".to_string()),
             diameter: Some(18.0),
+            shape: None,
+            width: None,
+            height: None,
             length: 120.0,
             slope: Some(0.005),
             manning_n: Some(0.013),
             material: Some("RCP".to_string()),
             cross_slope: None,
             long_slope: None,
+            bottom_width: None,
+            side_slope: None,
+            upstream_invert: None,
+            downstream_invert: None,
+            culvert_inlet: None,
+            road_crest: None,
+            road_length: None,
         };
 
         let conduit = record.to_conduit().unwrap();
         assert_eq!(conduit.id, "P-001");
         assert_eq!(conduit.conduit_type, ConduitType::Pipe);
+
+        let record = ConduitCsvRecord {
+            shape: Some("box".to_string()),
+            diameter: None,
+            ..record
+        };
+        assert!(record.to_conduit().is_err());
+
+        let record = ConduitCsvRecord {
+            width: Some(48.0),
+            height: Some(36.0),
+            ..record
+        };
+        let conduit = record.to_conduit().unwrap();
+        let pipe = conduit.pipe.as_ref().unwrap();
+        assert_eq!(pipe.shape, PipeShape::Rectangular);
+        assert_eq!((pipe.width, pipe.height), (Some(48.0), Some(36.0)));
+        assert_eq!(ConduitCsvRecord::from_conduit(&conduit).to_conduit().unwrap(), conduit);
+
+        let record = ConduitCsvRecord {
+            culvert_inlet: Some("box-flared-wingwalls".to_string()),
+            road_crest: Some(110.0),
+            ..record
+        };
+        assert!(record.to_conduit().is_err());
+
+        let record = ConduitCsvRecord {
+            road_length: Some(80.0),
+            ..record
+        };
+        let conduit = record.to_conduit().unwrap();
+        let culvert = conduit.pipe.as_ref().unwrap().culvert.as_ref().unwrap();
+        assert_eq!(culvert.inlet, CulvertInlet::BoxFlaredWingwalls);
+        assert_eq!(culvert.roadway.as_ref().map(|r| r.length), Some(80.0));
+        assert_eq!(ConduitCsvRecord::from_conduit(&conduit).to_conduit().unwrap(), conduit);
+
+        let record = ConduitCsvRecord {
+            culvert_inlet: Some("trash-rack".to_string()),
+            ..record
+        };
+        assert!(record.to_conduit().is_err());
+    }
+
+    #[test]
+    fn test_conduit_csv_round_trip() {
+        let record = ConduitCsvRecord {
+            id: "P-001".to_string(),
+            from_node: "MH-001".to_string(),
+            to_node: "MH-002".to_string(),
+            conduit_type: Some("pipe".to_string()),
+            diameter: Some(24.0),
+            shape: None,
+            width: None,
+            height: None,
+            length: 120.0,
+            slope: Some(0.005),
+            manning_n: Some(0.013),
+            material: Some("Concrete".to_string()),
+            cross_slope: None,
+            long_slope: None,
+            bottom_width: None,
+            side_slope: None,
+            upstream_invert: Some(101.0),
+            downstream_invert: Some(100.4),
+            culvert_inlet: None,
+            road_crest: None,
+            road_length: None,
+        };
+        let conduit = record.to_conduit().unwrap();
+
+        let mut writer = Writer::from_writer(Vec::new());
+        writer.serialize(ConduitCsvRecord::from_conduit(&conduit)).unwrap();
+        let data = writer.into_inner().unwrap();
+
+        let mut reader = ReaderBuilder::new().from_reader(data.as_slice());
+        let parsed: ConduitCsvRecord = reader.deserialize().next().unwrap().unwrap();
+        assert_eq!(parsed.to_conduit().unwrap(), conduit);
+    }
+
+    #[test]
+    fn test_conduit_csv_record_to_channel() {
+        let record = |bottom_width: Option<f64>, side_slope: Option<f64>| ConduitCsvRecord {
+            id: "CH-001".to_string(),
+            from_node: "MH-001".to_string(),
+            to_node: "OUT-001".to_string(),
+            conduit_type: Some("channel".to_string()),
+            diameter: None,
+            shape: None,
+            width: None,
+            height: None,
+            length: 300.0,
+            slope: None,
+            manning_n: None,
+            material: None,
+            cross_slope: None,
+            long_slope: None,
+            bottom_width,
+            side_slope,
+            upstream_invert: Some(101.0),
+            downstream_invert: Some(100.0),
+            culvert_inlet: None,
+            road_crest: None,
+            road_length: None,
+        };
+
+        let shape = |record: ConduitCsvRecord| record.to_conduit().unwrap().channel.unwrap().shape;
+        assert_eq!(shape(record(Some(4.0), Some(3.0))), ChannelShape::Trapezoidal);
+        assert_eq!(shape(record(Some(4.0), None)), ChannelShape::Rectangular);
+        assert_eq!(shape(record(None, Some(3.0))), ChannelShape::Triangular);
+        assert!(record(None, None).to_conduit().is_err());
+
+        let conduit = record(Some(4.0), Some(3.0)).to_conduit().unwrap();
+        assert_eq!(conduit.conduit_type, ConduitType::Channel);
+        assert_eq!(ConduitCsvRecord::from_conduit(&conduit).to_conduit().unwrap(), conduit);
     }
 
     #[test]
@@ -531,14 +1114,68 @@ mod tests {
             id: "DA-001".to_string(),
             area: 2.5,
             runoff_coef: 0.75,
-            time_of_conc: 15.0,
+            time_of_conc: Some(15.0),
             outlet_node: "IN-001".to_string(),
             land_use: Some("Commercial".to_string()),
+            tc_method: None,
+            flow_length: None,
+            avg_slope: None,
+            sheet_length: None,
+            sheet_slope: None,
+            sheet_n: None,
+            p2: None,
+            shallow_length: None,
+            shallow_slope: None,
+            surface_type: None,
+            channel_length: None,
+            channel_slope: None,
+            channel_n: None,
+            hydraulic_radius: None,
         };
 
-        let area = record.to_drainage_area();
+        let area = record.to_drainage_area().unwrap();
         assert_eq!(area.id, "DA-001");
         assert_eq!(area.area, 2.5);
         assert_eq!(area.runoff_coefficient, Some(0.75));
     }
+
+    #[test]
+    fn test_drainage_area_csv_computed_tc() {
+        let data = "id,area,runoff_coef,time_of_conc,outlet_node,tc_method,flow_length,avg_slope\n\
+                    DA-001,2.5,0.75,,IN-001,kirpich,1000,0.02\n";
+        let mut reader = ReaderBuilder::new().flexible(true).from_reader(data.as_bytes());
+        let record: DrainageAreaCsvRecord = reader.deserialize().next().unwrap().unwrap();
+
+        let area = record.to_drainage_area().unwrap();
+        assert_eq!(area.time_of_concentration, None);
+
+        let tc = area.design_tc(crate::project::UnitSystem::US, None).unwrap();
+        assert!((tc - 7.2).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_gutter_parameters_to_composite_section() {
+        let record = GutterParametersCsvRecord {
+            node_id: "IN-001".to_string(),
+            cross_slope: 0.02,
+            long_slope: 0.015,
+            curb_height: Some(6.0),
+            gutter_width: Some(2.0),
+            manning_n: None,
+            depression: Some(2.0),
+            depression_width: Some(2.0),
+            crown_height: None,
+            width_to_crown: None,
+        };
+
+        let gutter = record.to_gutter_properties();
+        assert_eq!(gutter.manning_n, 0.016);
+        assert_eq!(gutter.section(UnitSystem::US).section_type(), GutterSectionType::Composite);
+
+        let uniform = GutterParametersCsvRecord { depression: None, ..record };
+        assert_eq!(
+            uniform.to_gutter_properties().section(UnitSystem::US).section_type(),
+            GutterSectionType::UniformSlope
+        );
+    }
 }
@@ -0,0 +1,493 @@
+//! Culvert hydraulics (FHWA HDS-5)
+//!
+//! A culvert's headwater is set by whichever of two controls demands more
+//! head.
+//!
+//! Under **inlet control** the entrance limits the flow and the headwater
+//! follows the HDS-5 regression equations for the inlet configuration. The
+//! unsubmerged (form 1) and submerged equations are
+//!
+//! ```text
+//! HWi/D = Hc/D + K (Ku Q / A D^0.5)^M + Ks S
+//! HWi/D = c (Ku Q / A D^0.5)^2 + Y + Ks S
+//! ```
+//!
+//! The unsubmerged equation applies up to Ku Q / A D^0.5 = 3.5 and the
+//! submerged equation from 4.0, with headwater interpolated between them.
+//!
+//! Under **outlet control** the barrel and tailwater limit the flow. The
+//! headwater is the energy balance through a full barrel
+//!
+//! ```text
+//! HWo = ho + He + Hf + Ho + Hb - S L,    ho = max(TW, (dc + D) / 2)
+//! ```
+//!
+//! with entrance, friction, exit and bend losses added to the tailwater, or
+//! to the midpoint between critical depth and the crown when the tailwater
+//! is lower.
+//!
+//! When the headwater would top the roadway, the flow divides between the
+//! barrel and a weir over the road, Qo = Cd L (HW - crest)^1.5, at the
+//! headwater both share.
+//!
+//! ## References
+//!
+//! FHWA HDS-5 (3rd Edition, 2012), *Hydraulic Design of Highway Culverts*,
+//! Chapters 3 and 5 and Appendix A
+
+use crate::conduit::{CulvertInlet, RoadwayProperties};
+use crate::gvf::Reach;
+use crate::hydraulics::{EnergyLoss, ManningsEquation};
+use serde::{Deserialize, Serialize};
+
+/// Discharge intensity Ku Q / A D^0.5 up to which an inlet is unsubmerged
+const UNSUBMERGED_LIMIT: f64 = 3.5;
+
+/// Discharge intensity from which an inlet is submerged
+const SUBMERGED_LIMIT: f64 = 4.0;
+
+/// Bisection iterations when dividing flow between the barrel and roadway
+const SPLIT_ITERATIONS: usize = 60;
+
+/// Inlet control coefficients for the form 1 equations (HDS-5 Table A.1)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InletControlCoefficients {
+    /// Unsubmerged coefficient K
+    pub k: f64,
+    /// Unsubmerged exponent M
+    pub m: f64,
+    /// Submerged coefficient c
+    pub c: f64,
+    /// Submerged coefficient Y
+    pub y: f64,
+    /// Slope correction Ks (-0.5, or +0.7 for mitered inlets)
+    pub ks: f64,
+}
+
+impl InletControlCoefficients {
+    /// Coefficients for an inlet configuration
+    pub fn for_inlet(inlet: CulvertInlet) -> Self {
+        let (k, m, c, y) = match inlet {
+            CulvertInlet::ConcreteSquareEdge => (0.0098, 2.0, 0.0398, 0.67),
+            CulvertInlet::ConcreteGrooveEnd => (0.0018, 2.0, 0.0292, 0.74),
+            CulvertInlet::ConcreteProjecting => (0.0045, 2.0, 0.0317, 0.69),
+            CulvertInlet::MetalHeadwall => (0.0078, 2.0, 0.0379, 0.69),
+            CulvertInlet::MetalMitered => (0.0210, 1.33, 0.0463, 0.75),
+            CulvertInlet::MetalProjecting => (0.0340, 1.50, 0.0553, 0.54),
+            CulvertInlet::BoxFlaredWingwalls => (0.026, 1.0, 0.0347, 0.81),
+            CulvertInlet::BoxWingwalls => (0.061, 0.75, 0.0400, 0.80),
+            CulvertInlet::BoxParallelWingwalls => (0.061, 0.75, 0.0423, 0.82),
+        };
+        let ks = match inlet {
+            CulvertInlet::MetalMitered => 0.7,
+            _ => -0.5,
+        };
+        Self { k, m, c, y, ks }
+    }
+}
+
+/// A culvert barrel and its inlet
+#[derive(Debug)]
+pub struct Culvert {
+    /// Barrel reach; the section must be closed
+    pub reach: Reach,
+    /// Barrel rise D (ft or m)
+    pub rise: f64,
+    /// Inlet configuration
+    pub inlet: CulvertInlet,
+    /// Entrance loss coefficient Ke
+    pub entrance_loss: f64,
+    /// Exit loss coefficient
+    pub exit_loss: f64,
+    /// Bend loss coefficient
+    pub bend_loss: f64,
+    /// Roadway checked for overtopping
+    pub roadway: Option<RoadwayProperties>,
+}
+
+impl Culvert {
+    /// Invert elevation at the inlet (ft or m)
+    pub fn inlet_invert(&self) -> f64 {
+        self.reach.downstream_invert + self.reach.slope * self.reach.length
+    }
+}
+
+/// Control governing a culvert's headwater
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CulvertControl {
+    /// The entrance limits the flow
+    Inlet,
+    /// The barrel and tailwater limit the flow
+    Outlet,
+}
+
+/// Outlet control head through a full barrel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutletControl {
+    /// Head ho above the outlet invert the losses are added to (ft or m)
+    pub outlet_head: f64,
+    /// Entrance loss He (ft or m)
+    pub entrance: f64,
+    /// Friction loss Hf (ft or m)
+    pub friction: f64,
+    /// Exit loss Ho (ft or m)
+    pub exit: f64,
+    /// Bend loss Hb (ft or m)
+    pub bend: f64,
+}
+
+impl OutletControl {
+    /// Sum of the barrel losses (ft or m)
+    pub fn losses(&self) -> f64 {
+        self.entrance + self.friction + self.exit + self.bend
+    }
+}
+
+/// Culvert analysis result
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CulvertResult {
+    /// Control governing the headwater
+    pub control: CulvertControl,
+
+    /// Governing headwater elevation (ft or m)
+    pub headwater: f64,
+
+    /// Headwater elevation under inlet control (ft or m)
+    #[serde(rename = "inletControlHeadwater")]
+    pub inlet_control_headwater: f64,
+
+    /// Headwater elevation under outlet control (ft or m)
+    #[serde(rename = "outletControlHeadwater")]
+    pub outlet_control_headwater: f64,
+
+    /// Flow through the barrel (cfs or cms)
+    #[serde(rename = "barrelFlow")]
+    pub barrel_flow: f64,
+
+    /// Flow over the roadway (cfs or cms); present when a roadway is checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "overtoppingFlow")]
+    pub overtopping_flow: Option<f64>,
+}
+
+/// HDS-5 culvert analysis
+pub struct CulvertHydraulics {
+    /// Manning's equation for barrel friction
+    pub mannings: ManningsEquation,
+    /// Gravitational acceleration (ft/s² or m/s²)
+    pub gravity: f64,
+    /// Inlet control unit conversion Ku (1.0 US, 1.811 SI)
+    pub ku: f64,
+    /// Default roadway weir coefficient Cd (3.0 US, 1.66 SI)
+    pub weir_coefficient: f64,
+}
+
+impl CulvertHydraulics {
+    /// Create for US customary units
+    pub fn us_customary() -> Self {
+        Self {
+            mannings: ManningsEquation::us_customary(),
+            gravity: 32.17,
+            ku: 1.0,
+            weir_coefficient: 3.0,
+        }
+    }
+
+    /// Create for SI metric units
+    pub fn si_metric() -> Self {
+        Self {
+            mannings: ManningsEquation::si_metric(),
+            gravity: 9.81,
+            ku: 1.811,
+            weir_coefficient: 1.66,
+        }
+    }
+
+    /// Analyze a culvert carrying a flow
+    ///
+    /// Flow that would raise the headwater above the roadway crest is
+    /// divided between the barrel and the roadway weir.
+    ///
+    /// # Arguments
+    /// * `culvert` - The culvert
+    /// * `flow` - Total flow reaching the culvert (cfs or cms)
+    /// * `tailwater` - Tailwater elevation at the outlet (ft or m)
+    /// * `downstream_velocity` - Velocity carried away from the outlet (ft/s or m/s)
+    pub fn analyze(
+        &self,
+        culvert: &Culvert,
+        flow: f64,
+        tailwater: f64,
+        downstream_velocity: f64,
+    ) -> CulvertResult {
+        let barrel = |q: f64| self.barrel(culvert, q, tailwater, downstream_velocity);
+        let result = barrel(flow);
+        let Some(roadway) = &culvert.roadway else {
+            return result;
+        };
+        if result.headwater <= roadway.crest_elevation {
+            return CulvertResult {
+                overtopping_flow: Some(0.0),
+                ..result
+            };
+        }
+
+        // Barrel headwater rises and the weir head falls as more of the
+        // flow passes through the barrel
+        let cd = roadway.weir_coefficient.unwrap_or(self.weir_coefficient);
+        let weir_surface = |q: f64| roadway.crest_elevation + (q / (cd * roadway.length)).powf(2.0 / 3.0);
+        let (mut low, mut high) = (0.0, flow);
+        for _ in 0..SPLIT_ITERATIONS {
+            let mid = 0.5 * (low + high);
+            if barrel(mid).headwater > weir_surface(flow - mid) {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+
+        let barrel_flow = 0.5 * (low + high);
+        CulvertResult {
+            overtopping_flow: Some(flow - barrel_flow),
+            ..barrel(barrel_flow)
+        }
+    }
+
+    /// Inlet control headwater depth above the inlet invert (ft or m)
+    pub fn inlet_control(&self, culvert: &Culvert, flow: f64) -> f64 {
+        let section = &culvert.reach.section;
+        let rise = culvert.rise;
+        let area = section.area(rise);
+        let coefficients = InletControlCoefficients::for_inlet(culvert.inlet);
+        let slope_term = coefficients.ks * culvert.reach.slope;
+        let intensity = |q: f64| self.ku * q / (area * rise.sqrt());
+
+        let unsubmerged = |q: f64| {
+            let critical_depth = section.critical_depth(q, self.gravity).min(rise);
+            let critical_velocity = q / section.area(critical_depth);
+            let critical_head = critical_depth + critical_velocity.powi(2) / (2.0 * self.gravity);
+            critical_head + rise * (coefficients.k * intensity(q).powf(coefficients.m) + slope_term)
+        };
+        let submerged =
+            |q: f64| rise * (coefficients.c * intensity(q).powi(2) + coefficients.y + slope_term);
+
+        let x = intensity(flow);
+        if x <= UNSUBMERGED_LIMIT {
+            unsubmerged(flow)
+        } else if x >= SUBMERGED_LIMIT {
+            submerged(flow)
+        } else {
+            let flow_at = |limit: f64| limit * area * rise.sqrt() / self.ku;
+            let low = unsubmerged(flow_at(UNSUBMERGED_LIMIT));
+            let high = submerged(flow_at(SUBMERGED_LIMIT));
+            low + (high - low) * (x - UNSUBMERGED_LIMIT) / (SUBMERGED_LIMIT - UNSUBMERGED_LIMIT)
+        }
+    }
+
+    /// Outlet control head and losses through the full barrel
+    ///
+    /// # Arguments
+    /// * `culvert` - The culvert
+    /// * `flow` - Barrel flow (cfs or cms)
+    /// * `tailwater` - Tailwater elevation at the outlet (ft or m)
+    /// * `downstream_velocity` - Velocity carried away from the outlet (ft/s or m/s)
+    pub fn outlet_control(
+        &self,
+        culvert: &Culvert,
+        flow: f64,
+        tailwater: f64,
+        downstream_velocity: f64,
+    ) -> OutletControl {
+        let section = &culvert.reach.section;
+        let rise = culvert.rise;
+        let velocity = flow / section.area(rise);
+        let velocity_head = velocity.powi(2) / (2.0 * self.gravity);
+        let critical_depth = section.critical_depth(flow, self.gravity).min(rise);
+        let conveyance = section.conveyance(rise, culvert.reach.manning_n, self.mannings.k);
+        let energy_loss = EnergyLoss { gravity: self.gravity };
+
+        OutletControl {
+            outlet_head: (tailwater - culvert.reach.downstream_invert).max(0.5 * (critical_depth + rise)),
+            entrance: energy_loss.entrance_loss(velocity, culvert.entrance_loss),
+            friction: culvert.reach.length * (flow / conveyance).powi(2),
+            exit: energy_loss.exit_loss(velocity, downstream_velocity, culvert.exit_loss),
+            bend: culvert.bend_loss * velocity_head,
+        }
+    }
+
+    /// Depth at the outlet (ft or m)
+    ///
+    /// The barrel leaves an inlet-controlled culvert at normal depth, and an
+    /// outlet-controlled one at the higher of critical depth and tailwater,
+    /// in either case no deeper than the rise.
+    pub fn outlet_depth(&self, culvert: &Culvert, result: &CulvertResult, tailwater: f64) -> f64 {
+        let section = &culvert.reach.section;
+        let flow = result.barrel_flow;
+        let critical_depth = section.critical_depth(flow, self.gravity);
+        let depth = match result.control {
+            CulvertControl::Inlet => section
+                .normal_depth(flow, culvert.reach.slope, culvert.reach.manning_n, self.mannings.k)
+                .unwrap_or(critical_depth),
+            CulvertControl::Outlet => critical_depth.max(tailwater - culvert.reach.downstream_invert),
+        };
+        depth.min(culvert.rise)
+    }
+
+    /// Headwater of the barrel alone carrying a flow
+    fn barrel(&self, culvert: &Culvert, flow: f64, tailwater: f64, downstream_velocity: f64) -> CulvertResult {
+        let inlet = culvert.inlet_invert() + self.inlet_control(culvert, flow);
+        let outlet_control = self.outlet_control(culvert, flow, tailwater, downstream_velocity);
+        let outlet = culvert.reach.downstream_invert + outlet_control.outlet_head + outlet_control.losses();
+
+        CulvertResult {
+            control: if inlet >= outlet {
+                CulvertControl::Inlet
+            } else {
+                CulvertControl::Outlet
+            },
+            headwater: inlet.max(outlet),
+            inlet_control_headwater: inlet,
+            outlet_control_headwater: outlet,
+            barrel_flow: flow,
+            overtopping_flow: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::section::{Circular, Rectangular};
+    use approx::assert_relative_eq;
+
+    /// 200 ft of 48 in concrete pipe on a 1% grade, outlet invert at 100 ft
+    fn concrete_pipe(inlet: CulvertInlet) -> Culvert {
+        Culvert {
+            reach: Reach {
+                section: Box::new(Circular { diameter: 4.0 }),
+                length: 200.0,
+                slope: 0.01,
+                manning_n: 0.012,
+                downstream_invert: 100.0,
+            },
+            rise: 4.0,
+            inlet,
+            entrance_loss: inlet.entrance_loss(),
+            exit_loss: 1.0,
+            bend_loss: 0.0,
+            roadway: None,
+        }
+    }
+
+    #[test]
+    fn test_submerged_inlet_control() {
+        // Q / A D^0.5 = 200 / (4π × 2) = 7.96, well into the submerged range
+        let hydraulics = CulvertHydraulics::us_customary();
+        let culvert = concrete_pipe(CulvertInlet::ConcreteSquareEdge);
+        let x = 200.0 / (4.0 * std::f64::consts::PI * 2.0);
+        let expected = 4.0 * (0.0398 * x * x + 0.67 - 0.5 * 0.01);
+
+        let headwater = hydraulics.inlet_control(&culvert, 200.0);
+        assert_relative_eq!(headwater, expected, epsilon = 1e-9);
+        // HDS-5 Chart 1B reads HW/D ≈ 3.2 for this pipe
+        assert_relative_eq!(headwater / 4.0, 3.19, epsilon = 0.01);
+    }
+
+    #[test]
+    fn test_unsubmerged_inlet_control() {
+        let hydraulics = CulvertHydraulics::us_customary();
+        let culvert = concrete_pipe(CulvertInlet::MetalProjecting);
+        let area = 4.0 * std::f64::consts::PI;
+        let flow = 50.0;
+
+        let yc = culvert.reach.section.critical_depth(flow, 32.17);
+        let vc = flow / culvert.reach.section.area(yc);
+        let x = flow / (area * 2.0);
+        let expected = yc + vc * vc / (2.0 * 32.17) + 4.0 * (0.0340 * x.powf(1.5) - 0.5 * 0.01);
+        assert_relative_eq!(hydraulics.inlet_control(&culvert, flow), expected, epsilon = 1e-9);
+
+        // The transition joins both forms without a step
+        let at = |x: f64| hydraulics.inlet_control(&culvert, x * area * 2.0);
+        assert_relative_eq!(at(3.5), at(3.5 - 1e-9), epsilon = 1e-6);
+        assert_relative_eq!(at(4.0), at(4.0 + 1e-9), epsilon = 1e-6);
+        assert!(at(3.75) > at(3.5) && at(3.75) < at(4.0));
+    }
+
+    #[test]
+    fn test_outlet_control() {
+        // Submerged outlet: ho is the tailwater depth
+        let hydraulics = CulvertHydraulics::us_customary();
+        let culvert = concrete_pipe(CulvertInlet::ConcreteGrooveEnd);
+        let flow = 200.0;
+
+        let velocity_head = (flow / (4.0 * std::f64::consts::PI)).powi(2) / (2.0 * 32.17);
+        let conveyance = 1.486 / 0.012 * 4.0 * std::f64::consts::PI;
+        let friction = 200.0 * (flow / conveyance).powi(2);
+
+        let outlet = hydraulics.outlet_control(&culvert, flow, 105.0, 0.0);
+        assert_relative_eq!(outlet.outlet_head, 5.0);
+        assert_relative_eq!(outlet.friction, friction, epsilon = 1e-9);
+        assert_relative_eq!(outlet.losses(), 1.2 * velocity_head + friction, epsilon = 1e-9);
+
+        // The groove end inlet passes the flow easily, so the barrel governs
+        let result = hydraulics.analyze(&culvert, flow, 105.0, 0.0);
+        assert_eq!(result.control, CulvertControl::Outlet);
+        assert_relative_eq!(result.headwater, 105.0 + 1.2 * velocity_head + friction, epsilon = 1e-9);
+        assert_relative_eq!(hydraulics.outlet_depth(&culvert, &result, 105.0), 4.0);
+
+        // Low tailwater: ho is midway between critical depth and the crown
+        let yc = culvert.reach.section.critical_depth(100.0, 32.17);
+        let outlet = hydraulics.outlet_control(&culvert, 100.0, 100.5, 0.0);
+        assert_relative_eq!(outlet.outlet_head, 0.5 * (yc + 4.0), epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_box_inlet_control() {
+        // 6 ft × 4 ft box with flared wingwalls at Q/AD^0.5 = 2
+        let hydraulics = CulvertHydraulics::us_customary();
+        let mut culvert = concrete_pipe(CulvertInlet::BoxFlaredWingwalls);
+        culvert.reach.section = Box::new(Rectangular { width: 6.0, height: Some(4.0) });
+        let flow: f64 = 2.0 * 24.0 * 2.0;
+
+        let yc = (flow * flow / (36.0 * 32.17)).powf(1.0 / 3.0);
+        let expected = 1.5 * yc + 4.0 * (0.026 * 2.0 - 0.5 * 0.01);
+        assert_relative_eq!(hydraulics.inlet_control(&culvert, flow), expected, epsilon = 1e-6);
+
+        // Inlet control leaves the barrel at normal depth
+        let result = hydraulics.analyze(&culvert, flow, 100.0, 0.0);
+        assert_eq!(result.control, CulvertControl::Inlet);
+        let yn = culvert.reach.section.normal_depth(flow, 0.01, 0.012, 1.486).unwrap();
+        assert_relative_eq!(hydraulics.outlet_depth(&culvert, &result, 100.0), yn);
+    }
+
+    #[test]
+    fn test_roadway_overtopping() {
+        let hydraulics = CulvertHydraulics::us_customary();
+        let mut culvert = concrete_pipe(CulvertInlet::ConcreteSquareEdge);
+        culvert.roadway = Some(RoadwayProperties {
+            crest_elevation: 110.0,
+            length: 100.0,
+            weir_coefficient: None,
+        });
+
+        // Below the crest everything passes through the barrel
+        let result = hydraulics.analyze(&culvert, 100.0, 101.0, 0.0);
+        assert_eq!(result.overtopping_flow, Some(0.0));
+        assert_relative_eq!(result.barrel_flow, 100.0);
+
+        // Above it the barrel and weir share one headwater
+        let flow = 400.0;
+        let result = hydraulics.analyze(&culvert, flow, 101.0, 0.0);
+        let overtopping = result.overtopping_flow.unwrap();
+        assert!(overtopping > 0.0 && result.barrel_flow < flow);
+        assert_relative_eq!(result.barrel_flow + overtopping, flow);
+        assert!(result.headwater > 110.0);
+        assert_relative_eq!(
+            overtopping,
+            3.0 * 100.0 * (result.headwater - 110.0).powf(1.5),
+            epsilon = 1e-6
+        );
+        assert!(result.headwater < hydraulics.analyze(&concrete_pipe(CulvertInlet::ConcreteSquareEdge), flow, 101.0, 0.0).headwater);
+    }
+}
@@ -0,0 +1,1258 @@
+//! Storm drain design
+//!
+//! Design routines that modify the network rather than just analyze it,
+//! following the preliminary design procedure of HEC-22 Section 9.3.
+//!
+//! ## Pipe Sizing
+//!
+//! [`PipeSizer`] selects the smallest standard diameter for each pipe's
+//! material that carries the design flow within the capacity and velocity
+//! criteria, working from upstream to downstream.
+//!
+//! ## Profile
+//!
+//! [`ProfileDesigner`] lays out pipe inverts below the rims. Each pipe starts
+//! as high as minimum cover and the junction rule allow, then falls at the
+//! steeper of the self-cleansing slope and the slope that keeps minimum cover
+//! at its downstream end. Pipes into an outfall end at the outfall invert.
+//!
+//! ## Inlet Spacing
+//!
+//! [`InletSpacer`] places on-grade inlets down a gutter run so the spread
+//! never exceeds the allowable, carrying each inlet's bypass to the next
+//! (HEC-22 Section 7.4.2).
+
+use crate::analysis::{DesignCriteria, Severity, Violation, ViolationType};
+use crate::conduit::{Conduit, GutterProperties, PipeMaterial, PipeProperties, PipeShape};
+use crate::gutter::{GUTTER_K_SI, GUTTER_K_US};
+use crate::hydraulics::{DesignCalculations, ManningsEquation};
+use crate::network::Network;
+use crate::node::{InletLocation, InletProperties, Node, NodeType};
+use crate::project::UnitSystem;
+use crate::solver::{on_grade_interception, topological_sort_upstream_to_downstream};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// Pipe sizing configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipeSizingConfig {
+    /// Unit system
+    pub unit_system: UnitSystem,
+    /// Gravitational constant (32.17 for US, 9.81 for SI)
+    pub gravity: f64,
+    /// Manning's constant (1.486 for US, 1.0 for SI)
+    pub manning_k: f64,
+    /// Minimum Q_full / Q_design ratio
+    pub min_capacity_ratio: f64,
+    /// Minimum full-flow velocity for self-cleansing (ft/s or m/s)
+    pub min_velocity: Option<f64>,
+    /// Maximum design flow velocity (ft/s or m/s)
+    pub max_velocity: Option<f64>,
+    /// Smallest diameter to use (in or mm)
+    pub minimum_diameter: Option<f64>,
+    /// Never select a diameter smaller than any pipe immediately upstream
+    pub no_decrease_downstream: bool,
+}
+
+impl PipeSizingConfig {
+    /// Create configuration for US customary units
+    pub fn us_customary() -> Self {
+        Self {
+            unit_system: UnitSystem::US,
+            gravity: 32.17,
+            manning_k: 1.486,
+            min_capacity_ratio: 1.0,
+            min_velocity: None,
+            max_velocity: None,
+            minimum_diameter: None,
+            no_decrease_downstream: true,
+        }
+    }
+
+    /// Create configuration for SI metric units
+    pub fn si_metric() -> Self {
+        Self {
+            unit_system: UnitSystem::SI,
+            gravity: 9.81,
+            manning_k: 1.0,
+            ..Self::us_customary()
+        }
+    }
+
+    /// Take capacity and velocity limits from project design criteria
+    pub fn with_criteria(mut self, criteria: &DesignCriteria) -> Self {
+        if let Some(ratio) = criteria.capacity.as_ref().and_then(|c| c.min_capacity_ratio) {
+            self.min_capacity_ratio = ratio;
+        }
+        if let Some(ref velocity) = criteria.velocity {
+            self.min_velocity = velocity.min_velocity.or(self.min_velocity);
+            self.max_velocity = velocity.max_velocity.or(self.max_velocity);
+        }
+        self
+    }
+}
+
+/// Selected size for one pipe
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipeSize {
+    /// Conduit ID
+    pub conduit_id: String,
+    /// Material whose standard sizes were used
+    pub material: PipeMaterial,
+    /// Diameter before sizing (in or mm)
+    pub original_diameter: Option<f64>,
+    /// Selected diameter (in or mm)
+    pub diameter: f64,
+    /// Design flow (cfs or cms)
+    pub design_flow: f64,
+    /// Full-flow capacity of the selected pipe (cfs or cms)
+    pub full_capacity: f64,
+    /// Design flow velocity at normal depth (ft/s or m/s)
+    pub design_velocity: f64,
+    /// Full-flow velocity (ft/s or m/s)
+    pub full_velocity: f64,
+    /// Pipe slope (ft/ft or m/m)
+    pub slope: f64,
+    /// Slope needed for the minimum full-flow velocity (HEC-22 Eq. 9.33)
+    pub minimum_slope: Option<f64>,
+    /// Whether every criterion is met
+    pub meets_criteria: bool,
+}
+
+impl PipeSize {
+    /// Q_full / Q_design
+    pub fn capacity_ratio(&self) -> f64 {
+        if self.design_flow > 0.0 {
+            self.full_capacity / self.design_flow
+        } else {
+            f64::INFINITY
+        }
+    }
+}
+
+/// Result of sizing a network
+#[derive(Debug, Clone)]
+pub struct PipeSizingResult {
+    /// Selected sizes, in upstream-to-downstream order
+    pub pipes: Vec<PipeSize>,
+    /// Criteria that could not be met
+    pub violations: Vec<Violation>,
+}
+
+impl PipeSizingResult {
+    /// Size selected for a conduit
+    pub fn pipe(&self, conduit_id: &str) -> Option<&PipeSize> {
+        self.pipes.iter().find(|p| p.conduit_id == conduit_id)
+    }
+
+    /// Write the selected diameters into the network
+    ///
+    /// # Returns
+    /// `true` if any diameter changed
+    pub fn apply_to(&self, network: &mut Network) -> bool {
+        let mut changed = false;
+        for size in &self.pipes {
+            let pipe = network
+                .conduits
+                .iter_mut()
+                .find(|c| c.id == size.conduit_id)
+                .and_then(|c| c.pipe.as_mut());
+            if let Some(pipe) = pipe {
+                if pipe.diameter != Some(size.diameter) {
+                    pipe.diameter = Some(size.diameter);
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+}
+
+/// Automatic pipe sizing
+pub struct PipeSizer {
+    config: PipeSizingConfig,
+    mannings: ManningsEquation,
+    design: DesignCalculations,
+}
+
+impl PipeSizer {
+    /// Create a new sizer with the given configuration
+    pub fn new(config: PipeSizingConfig) -> Self {
+        let mannings = ManningsEquation { k: config.manning_k };
+        let design = DesignCalculations { k: config.manning_k };
+        Self {
+            config,
+            mannings,
+            design,
+        }
+    }
+
+    /// Size every circular pipe in the network for the given design flows
+    ///
+    /// For each pipe, from upstream to downstream, the smallest standard
+    /// diameter of its material (RCP if unspecified) is chosen such that:
+    /// - Q_full ≥ min_capacity_ratio × Q_design
+    /// - the design velocity at normal depth does not exceed `max_velocity`
+    /// - the diameter is not smaller than any upstream pipe (if enabled)
+    ///
+    /// The minimum velocity criterion is checked on the full-flow velocity of
+    /// the selected pipe. It depends on slope rather than size, so a pipe that
+    /// is too flat is reported with the slope it needs instead of upsized.
+    ///
+    /// # Arguments
+    /// * `network` - The drainage network
+    /// * `flows` - Design flow per conduit ID (cfs or cms)
+    pub fn size(&self, network: &Network, flows: &HashMap<String, f64>) -> Result<PipeSizingResult, String> {
+        let order = topological_sort_upstream_to_downstream(network)?;
+        let mut sized: HashMap<String, f64> = HashMap::new();
+        let mut pipes = Vec::new();
+        let mut violations = Vec::new();
+
+        for node_id in &order {
+            // Largest pipe entering this node
+            let upstream_diameter = network
+                .upstream_conduits(node_id)
+                .iter()
+                .filter_map(|c| {
+                    sized
+                        .get(&c.id)
+                        .copied()
+                        .or_else(|| c.pipe.as_ref().and_then(|p| p.diameter))
+                })
+                .fold(0.0, f64::max);
+
+            for conduit in network.downstream_conduits(node_id) {
+                let is_circular = conduit
+                    .pipe
+                    .as_ref()
+                    .is_some_and(|p| p.shape == PipeShape::Circular);
+                if !is_circular {
+                    continue;
+                }
+
+                let flow = flows.get(&conduit.id).copied().unwrap_or(0.0);
+                let lower_bound = if self.config.no_decrease_downstream {
+                    upstream_diameter
+                } else {
+                    0.0
+                };
+                let size = self.size_pipe(network, conduit, flow, lower_bound, &mut violations)?;
+                sized.insert(conduit.id.clone(), size.diameter);
+                pipes.push(size);
+            }
+        }
+
+        Ok(PipeSizingResult { pipes, violations })
+    }
+
+    /// Size the network again after an earlier result was applied to it
+    ///
+    /// [`Self::size`] records the network's current diameters as the
+    /// original sizes, which after [`PipeSizingResult::apply_to`] are the
+    /// previous pass's choices. This keeps the original diameters from
+    /// `previous` instead, so repeated passes still report the input sizes.
+    pub fn resize(
+        &self,
+        network: &Network,
+        flows: &HashMap<String, f64>,
+        previous: &PipeSizingResult,
+    ) -> Result<PipeSizingResult, String> {
+        let mut result = self.size(network, flows)?;
+        for pipe in &mut result.pipes {
+            if let Some(earlier) = previous.pipe(&pipe.conduit_id) {
+                pipe.original_diameter = earlier.original_diameter;
+            }
+        }
+        Ok(result)
+    }
+
+    fn size_pipe(
+        &self,
+        network: &Network,
+        conduit: &Conduit,
+        flow: f64,
+        lower_bound: f64,
+        violations: &mut Vec<Violation>,
+    ) -> Result<PipeSize, String> {
+        let pipe = conduit
+            .pipe
+            .as_ref()
+            .ok_or_else(|| format!("Conduit {} is not a pipe", conduit.id))?;
+        let slope = network
+            .conduit_slope(conduit)
+            .filter(|&s| s > 0.0)
+            .ok_or_else(|| format!("Pipe {} needs a positive slope to be sized", conduit.id))?;
+        let material = pipe.material.unwrap_or(PipeMaterial::RCP);
+        let minimum = lower_bound.max(self.config.minimum_diameter.unwrap_or(0.0));
+
+        let candidates: Vec<f64> = material
+            .standard_diameters(self.config.unit_system)
+            .iter()
+            .copied()
+            .filter(|&d| d >= minimum - 1e-9)
+            .collect();
+        if candidates.is_empty() {
+            return Err(format!(
+                "No standard {:?} size for pipe {} is at least {}",
+                material, conduit.id, minimum
+            ));
+        }
+
+        let fits = |d: f64| {
+            let (capacity, velocity) = self.capacity_and_velocity(d, slope, pipe.manning_n, flow);
+            capacity >= self.config.min_capacity_ratio * flow
+                && self.config.max_velocity.is_none_or(|max| velocity <= max)
+        };
+        let (diameter, mut meets_criteria) = match candidates.iter().copied().find(|&d| fits(d)) {
+            Some(d) => (d, true),
+            None => (candidates[candidates.len() - 1], false),
+        };
+
+        let d = self.to_length(diameter);
+        let (full_capacity, design_velocity) = self.capacity_and_velocity(diameter, slope, pipe.manning_n, flow);
+        let full_velocity = full_capacity / (PI * d * d / 4.0);
+
+        if full_capacity < self.config.min_capacity_ratio * flow {
+            violations.push(Violation::capacity_violation(
+                conduit.id.clone(),
+                flow / full_capacity,
+                Severity::Error,
+            ));
+        }
+        if let Some(max) = self.config.max_velocity.filter(|&max| design_velocity > max) {
+            violations.push(Violation::velocity_violation(
+                conduit.id.clone(),
+                design_velocity,
+                max,
+                Severity::Warning,
+            ));
+        }
+
+        let minimum_slope = self
+            .config
+            .min_velocity
+            .map(|v| self.design.minimum_slope_for_velocity(d, pipe.manning_n, v));
+        if let (Some(min_slope), Some(min_velocity)) = (minimum_slope, self.config.min_velocity) {
+            if slope < min_slope {
+                meets_criteria = false;
+                violations.push(Violation::velocity_violation(
+                    conduit.id.clone(),
+                    full_velocity,
+                    min_velocity,
+                    Severity::Warning,
+                ));
+            }
+        }
+
+        Ok(PipeSize {
+            conduit_id: conduit.id.clone(),
+            material,
+            original_diameter: pipe.diameter,
+            diameter,
+            design_flow: flow,
+            full_capacity,
+            design_velocity,
+            full_velocity,
+            slope,
+            minimum_slope,
+            meets_criteria,
+        })
+    }
+
+    /// Full-flow capacity and design flow velocity for a nominal diameter
+    fn capacity_and_velocity(&self, diameter: f64, slope: f64, manning_n: f64, flow: f64) -> (f64, f64) {
+        let d = self.to_length(diameter);
+        let capacity = self.mannings.full_pipe_capacity(d, slope, manning_n);
+        if flow <= 0.0 || flow >= capacity {
+            return (capacity, flow.max(0.0) / (PI * d * d / 4.0));
+        }
+
+        let velocity = self
+            .mannings
+            .normal_depth(flow, d, slope, manning_n, self.config.gravity)
+            .map(|y| {
+                self.mannings
+                    .partial_pipe_flow(d, y, slope, manning_n, self.config.gravity)
+                    .velocity
+            })
+            .unwrap_or(0.0);
+        (capacity, velocity)
+    }
+
+    /// Convert a nominal diameter (in or mm) to ft or m
+    fn to_length(&self, diameter: f64) -> f64 {
+        match self.config.unit_system {
+            UnitSystem::US => diameter / 12.0,
+            UnitSystem::SI => diameter / 1000.0,
+        }
+    }
+}
+
+/// Rule for setting an outlet pipe against the pipes entering a structure
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JunctionRule {
+    /// Outlet crown no higher than the lowest inlet crown
+    MatchCrowns,
+    /// Outlet invert this far below the lowest inlet invert (ft or m)
+    InvertDrop(f64),
+}
+
+/// Profile design configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileConfig {
+    /// Unit system
+    pub unit_system: UnitSystem,
+    /// Manning's constant (1.486 for US, 1.0 for SI)
+    pub manning_k: f64,
+    /// Minimum cover from rim to pipe crown (ft or m)
+    pub min_cover: f64,
+    /// Minimum full-flow velocity for self-cleansing (ft/s or m/s)
+    pub min_velocity: Option<f64>,
+    /// Flattest slope allowed regardless of velocity (ft/ft or m/m)
+    pub minimum_slope: f64,
+    /// Rule applied at structures with pipes entering
+    pub junction_rule: JunctionRule,
+}
+
+impl ProfileConfig {
+    /// Create configuration for US customary units
+    ///
+    /// Uses 3 ft of cover (HEC-22 Section 9.2.4) and 3 ft/s full-flow velocity.
+    pub fn us_customary() -> Self {
+        Self {
+            unit_system: UnitSystem::US,
+            manning_k: 1.486,
+            min_cover: 3.0,
+            min_velocity: Some(3.0),
+            minimum_slope: 0.0,
+            junction_rule: JunctionRule::MatchCrowns,
+        }
+    }
+
+    /// Create configuration for SI metric units
+    pub fn si_metric() -> Self {
+        Self {
+            unit_system: UnitSystem::SI,
+            manning_k: 1.0,
+            min_cover: 0.9,
+            min_velocity: Some(0.9),
+            ..Self::us_customary()
+        }
+    }
+
+    /// Take minimum cover and velocity from project design criteria
+    pub fn with_criteria(mut self, criteria: &DesignCriteria) -> Self {
+        if let Some(cover) = criteria.cover.as_ref().and_then(|c| c.min_cover) {
+            self.min_cover = cover;
+        }
+        if let Some(velocity) = criteria.velocity.as_ref().and_then(|v| v.min_velocity) {
+            self.min_velocity = Some(velocity);
+        }
+        self
+    }
+}
+
+/// Designed profile of one pipe
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipeProfile {
+    /// Conduit ID
+    pub conduit_id: String,
+    /// Inside height of the pipe (ft or m)
+    pub rise: f64,
+    /// Upstream invert elevation (ft or m)
+    pub upstream_invert: f64,
+    /// Downstream invert elevation (ft or m)
+    pub downstream_invert: f64,
+    /// Pipe slope (ft/ft or m/m)
+    pub slope: f64,
+    /// Slope needed for the minimum full-flow velocity (ft/ft or m/m)
+    pub minimum_slope: f64,
+    /// Cover over the crown at the upstream rim (ft or m)
+    pub upstream_cover: Option<f64>,
+    /// Cover over the crown at the downstream rim (ft or m)
+    pub downstream_cover: Option<f64>,
+}
+
+/// Result of a profile design
+#[derive(Debug, Clone)]
+pub struct ProfileResult {
+    /// Pipe profiles, in upstream-to-downstream order
+    pub pipes: Vec<PipeProfile>,
+    /// Structure inverts (lowest connected pipe invert), excluding outfalls
+    pub node_inverts: HashMap<String, f64>,
+    /// Cover and outfall invert constraints that could not be met
+    pub violations: Vec<Violation>,
+}
+
+impl ProfileResult {
+    /// Profile of a conduit
+    pub fn pipe(&self, conduit_id: &str) -> Option<&PipeProfile> {
+        self.pipes.iter().find(|p| p.conduit_id == conduit_id)
+    }
+
+    /// Write pipe and structure inverts into the network
+    ///
+    /// # Returns
+    /// `true` if any elevation or slope changed
+    pub fn apply_to(&self, network: &mut Network) -> bool {
+        const TOLERANCE: f64 = 1e-6;
+        let differs = |old: Option<f64>, new: f64| old.is_none_or(|old| (old - new).abs() > TOLERANCE);
+        let mut changed = false;
+
+        for profile in &self.pipes {
+            if let Some(conduit) = network.conduits.iter_mut().find(|c| c.id == profile.conduit_id) {
+                changed |= differs(conduit.upstream_invert, profile.upstream_invert)
+                    || differs(conduit.downstream_invert, profile.downstream_invert)
+                    || differs(conduit.slope, profile.slope);
+                conduit.upstream_invert = Some(profile.upstream_invert);
+                conduit.downstream_invert = Some(profile.downstream_invert);
+                conduit.slope = Some(profile.slope);
+            }
+        }
+        for node in network.nodes.iter_mut() {
+            if let Some(&invert) = self.node_inverts.get(&node.id) {
+                changed |= differs(Some(node.invert_elevation), invert);
+                node.invert_elevation = invert;
+            }
+        }
+        changed
+    }
+}
+
+/// Invert elevation design
+pub struct ProfileDesigner {
+    config: ProfileConfig,
+    design: DesignCalculations,
+}
+
+impl ProfileDesigner {
+    /// Create a new designer with the given configuration
+    pub fn new(config: ProfileConfig) -> Self {
+        let design = DesignCalculations { k: config.manning_k };
+        Self { config, design }
+    }
+
+    /// Lay out inverts for every pipe from upstream to the outfalls
+    ///
+    /// The upstream invert of each pipe is the lowest of:
+    /// - rim − minimum cover − rise
+    /// - the junction rule applied to each pipe entering the structure
+    ///
+    /// Pipes into an outfall end at the outfall invert. Where that would leave
+    /// the pipe flatter than its minimum slope, the pipe is laid at minimum
+    /// slope instead and a profile violation reports how far below the outfall
+    /// invert it arrives. Gutters and channels are left untouched.
+    ///
+    /// # Arguments
+    /// * `network` - The drainage network, with pipe sizes and rim elevations
+    pub fn design(&self, network: &Network) -> Result<ProfileResult, String> {
+        let order = topological_sort_upstream_to_downstream(network)?;
+        let mut inverts: HashMap<String, (f64, f64)> = HashMap::new();
+        let mut pipes = Vec::new();
+        let mut node_inverts = HashMap::new();
+        let mut violations = Vec::new();
+
+        for node_id in &order {
+            let node = network
+                .find_node(node_id)
+                .ok_or_else(|| format!("Node {} not found", node_id))?;
+
+            // (downstream invert, rise) of the pipes entering this node
+            let inflows: Vec<(f64, f64)> = network
+                .upstream_conduits(node_id)
+                .iter()
+                .filter_map(|c| {
+                    let &(_, down) = inverts.get(&c.id)?;
+                    Some((down, self.rise(c)?))
+                })
+                .collect();
+
+            for conduit in network.downstream_conduits(node_id) {
+                let Some(rise) = self.rise(conduit) else {
+                    continue;
+                };
+                let rim = node
+                    .rim_elevation
+                    .ok_or_else(|| format!("Node {} needs a rim elevation for profile design", node_id))?;
+                let upstream_limit = inflows
+                    .iter()
+                    .map(|&(down, inflow_rise)| match self.config.junction_rule {
+                        JunctionRule::MatchCrowns => down + inflow_rise - rise,
+                        JunctionRule::InvertDrop(drop) => down - drop,
+                    })
+                    .fold(rim - self.config.min_cover - rise, f64::min);
+
+                let profile = self.lay_pipe(network, conduit, rise, upstream_limit, &mut violations)?;
+                inverts.insert(conduit.id.clone(), (profile.upstream_invert, profile.downstream_invert));
+                pipes.push(profile);
+            }
+        }
+
+        for node in &network.nodes {
+            if node.node_type == NodeType::Outfall {
+                continue;
+            }
+            let connected = network
+                .downstream_conduits(&node.id)
+                .into_iter()
+                .filter_map(|c| inverts.get(&c.id).map(|&(up, _)| up))
+                .chain(
+                    network
+                        .upstream_conduits(&node.id)
+                        .into_iter()
+                        .filter_map(|c| inverts.get(&c.id).map(|&(_, down)| down)),
+                )
+                .reduce(f64::min);
+            if let Some(invert) = connected {
+                node_inverts.insert(node.id.clone(), invert);
+            }
+        }
+
+        Ok(ProfileResult {
+            pipes,
+            node_inverts,
+            violations,
+        })
+    }
+
+    fn lay_pipe(
+        &self,
+        network: &Network,
+        conduit: &Conduit,
+        rise: f64,
+        upstream_invert: f64,
+        violations: &mut Vec<Violation>,
+    ) -> Result<PipeProfile, String> {
+        let pipe = conduit
+            .pipe
+            .as_ref()
+            .ok_or_else(|| format!("Conduit {} is not a pipe", conduit.id))?;
+        let to_node = network
+            .find_node(&conduit.to_node)
+            .ok_or_else(|| format!("Node {} not found", conduit.to_node))?;
+        if conduit.length <= 0.0 {
+            return Err(format!("Pipe {} needs a positive length for profile design", conduit.id));
+        }
+
+        let minimum_slope = self
+            .config
+            .min_velocity
+            .map(|v| self.design.minimum_slope_for_velocity(self.hydraulic_diameter(pipe, rise), pipe.manning_n, v))
+            .unwrap_or(0.0)
+            .max(self.config.minimum_slope);
+        let lowest_at_minimum_slope = upstream_invert - minimum_slope * conduit.length;
+
+        let downstream_invert = if to_node.node_type == NodeType::Outfall {
+            if lowest_at_minimum_slope < to_node.invert_elevation {
+                violations.push(Violation {
+                    violation_type: ViolationType::Profile,
+                    severity: Severity::Error,
+                    element_id: conduit.id.clone(),
+                    message: format!(
+                        "Pipe {} reaches outfall {} at invert {:.2} ft, {:.2} ft below the outfall invert of {:.2} ft",
+                        conduit.id,
+                        to_node.id,
+                        lowest_at_minimum_slope,
+                        to_node.invert_elevation - lowest_at_minimum_slope,
+                        to_node.invert_elevation
+                    ),
+                    value: Some(lowest_at_minimum_slope),
+                    limit: Some(to_node.invert_elevation),
+                });
+                lowest_at_minimum_slope
+            } else {
+                to_node.invert_elevation
+            }
+        } else {
+            let rim = to_node
+                .rim_elevation
+                .ok_or_else(|| format!("Node {} needs a rim elevation for profile design", to_node.id))?;
+            lowest_at_minimum_slope.min(rim - self.config.min_cover - rise)
+        };
+
+        let cover = |node_id: &str, invert: f64| {
+            network
+                .find_node(node_id)
+                .and_then(|n| n.rim_elevation)
+                .map(|rim| rim - invert - rise)
+        };
+        let downstream_cover = cover(&to_node.id, downstream_invert);
+        if let Some(c) = downstream_cover.filter(|&c| c < self.config.min_cover - 1e-9) {
+            violations.push(Violation::cover_violation(
+                conduit.id.clone(),
+                c,
+                self.config.min_cover,
+                Severity::Warning,
+            ));
+        }
+
+        Ok(PipeProfile {
+            conduit_id: conduit.id.clone(),
+            rise,
+            upstream_invert,
+            downstream_invert,
+            slope: (upstream_invert - downstream_invert) / conduit.length,
+            minimum_slope,
+            upstream_cover: cover(&conduit.from_node, upstream_invert),
+            downstream_cover,
+        })
+    }
+
+    /// Inside height of a pipe (ft or m), or `None` for gutters and channels
+    fn rise(&self, conduit: &Conduit) -> Option<f64> {
+        let pipe = conduit.pipe.as_ref()?;
+        let rise = match pipe.shape {
+            PipeShape::Circular => pipe.diameter,
+            _ => pipe.height.or(pipe.diameter),
+        }?;
+        Some(self.to_length(rise))
+    }
+
+    /// Diameter of the circle with the same full-flow hydraulic radius (ft or m)
+    fn hydraulic_diameter(&self, pipe: &PipeProperties, rise: f64) -> f64 {
+        match (pipe.shape, pipe.width) {
+            (PipeShape::Rectangular, Some(width)) => {
+                let width = self.to_length(width);
+                4.0 * width * rise / (2.0 * (width + rise))
+            }
+            _ => rise,
+        }
+    }
+
+    /// Convert a nominal dimension (in or mm) to ft or m
+    fn to_length(&self, dimension: f64) -> f64 {
+        match self.config.unit_system {
+            UnitSystem::US => dimension / 12.0,
+            UnitSystem::SI => dimension / 1000.0,
+        }
+    }
+}
+
+/// A roadway gutter draining from a high point on a continuous grade
+#[derive(Debug, Clone, PartialEq)]
+pub struct GutterRun {
+    /// Run ID, used as the prefix of generated inlet and gutter IDs
+    pub id: String,
+    /// Station at the high point (ft or m); stations increase downgrade
+    pub start_station: f64,
+    /// Length of the run from the high point (ft or m)
+    pub length: f64,
+    /// Gutter flowline elevation at the high point (ft or m)
+    pub start_elevation: f64,
+    /// Width of pavement and roadside draining to the gutter (ft or m)
+    pub tributary_width: f64,
+    /// Runoff coefficient C
+    pub runoff_coefficient: f64,
+    /// Design rainfall intensity (in/hr or mm/hr)
+    pub intensity: f64,
+    /// Gutter section and longitudinal slope
+    pub gutter: GutterProperties,
+}
+
+impl GutterRun {
+    /// Runoff reaching the gutter per unit length of run (cfs/ft or cms/m)
+    fn runoff_per_length(&self, unit_system: UnitSystem) -> f64 {
+        let cia = self.runoff_coefficient * self.intensity * self.tributary_width;
+        match unit_system {
+            UnitSystem::US => cia / 43_560.0,
+            UnitSystem::SI => cia / 10_000.0 / 360.0,
+        }
+    }
+}
+
+/// Inlet spacing configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct InletSpacingConfig {
+    /// Unit system
+    pub unit_system: UnitSystem,
+    /// Allowable spread (ft or m)
+    pub max_spread: f64,
+    /// Longest spacing allowed, e.g. for maintenance (ft or m)
+    pub max_spacing: Option<f64>,
+    /// Depth from rim to invert of generated inlet structures (ft or m)
+    pub structure_depth: f64,
+}
+
+impl InletSpacingConfig {
+    /// Create configuration for US customary units
+    pub fn us_customary() -> Self {
+        Self {
+            unit_system: UnitSystem::US,
+            max_spread: 10.0,
+            max_spacing: None,
+            structure_depth: 4.0,
+        }
+    }
+
+    /// Create configuration for SI metric units
+    pub fn si_metric() -> Self {
+        Self {
+            unit_system: UnitSystem::SI,
+            max_spread: 3.0,
+            max_spacing: None,
+            structure_depth: 1.2,
+        }
+    }
+
+    /// Take the allowable spread from project design criteria
+    pub fn with_criteria(mut self, criteria: &DesignCriteria) -> Self {
+        if let Some(spread) = criteria.gutter_spread.as_ref().and_then(|g| g.max_spread) {
+            self.max_spread = spread;
+        }
+        self
+    }
+}
+
+/// One placed inlet in the station table
+#[derive(Debug, Clone, PartialEq)]
+pub struct InletStation {
+    /// Generated inlet node ID
+    pub inlet_id: String,
+    /// Inlet station (ft or m)
+    pub station: f64,
+    /// Distance from the previous inlet or the high point (ft or m)
+    pub spacing: f64,
+    /// Area draining directly to this inlet (acres or hectares)
+    pub drainage_area: f64,
+    /// Runoff from that area (cfs or cms)
+    pub runoff: f64,
+    /// Bypass arriving from the inlet upgrade (cfs or cms)
+    pub carryover: f64,
+    /// Total gutter flow at the inlet (cfs or cms)
+    pub approach_flow: f64,
+    /// Spread approaching the inlet (ft or m)
+    pub spread: f64,
+    /// Depth at the curb (ft or m)
+    pub depth_at_curb: f64,
+    /// Gutter velocity (ft/s or m/s)
+    pub velocity: f64,
+    /// Intercepted flow (cfs or cms)
+    pub intercepted_flow: f64,
+    /// Bypass continuing downgrade (cfs or cms)
+    pub bypass_flow: f64,
+    /// Interception efficiency E
+    pub efficiency: f64,
+}
+
+/// Inlets placed along a gutter run
+#[derive(Debug, Clone)]
+pub struct InletSpacingResult {
+    /// Station table, upgrade to downgrade
+    pub inlets: Vec<InletStation>,
+    /// Station at the end of the run (ft or m)
+    pub end_station: f64,
+    /// Gutter flow passing the end of the run (cfs or cms)
+    pub end_flow: f64,
+    /// Inlet nodes at the placed stations
+    pub nodes: Vec<Node>,
+    /// Gutter conduits linking consecutive inlets
+    pub conduits: Vec<Conduit>,
+}
+
+/// Inlet spacing design on a continuous grade
+pub struct InletSpacer {
+    config: InletSpacingConfig,
+}
+
+impl InletSpacer {
+    /// Create a new spacer with the given configuration
+    pub fn new(config: InletSpacingConfig) -> Self {
+        Self { config }
+    }
+
+    /// Place inlets down a gutter run
+    ///
+    /// Runoff builds up uniformly along the run, so the gutter reaches the
+    /// allowable spread at the distance where
+    ///
+    /// ```text
+    /// Q_bypass + q × L = Q_T
+    /// ```
+    ///
+    /// where q is the runoff per unit length and Q_T the gutter capacity at
+    /// the allowable spread. Each inlet is placed there (or at the maximum
+    /// spacing, if shorter) and its bypass becomes the carryover to the next.
+    /// Placement stops when the rest of the run stays within the allowable
+    /// spread; the flow passing the end of the run is reported as `end_flow`.
+    ///
+    /// # Arguments
+    /// * `run` - The gutter run
+    /// * `inlet` - Inlet to place; treated as on-grade
+    pub fn space(&self, run: &GutterRun, inlet: &InletProperties) -> Result<InletSpacingResult, String> {
+        let k = match self.config.unit_system {
+            UnitSystem::US => GUTTER_K_US,
+            UnitSystem::SI => GUTTER_K_SI,
+        };
+        let area_factor = match self.config.unit_system {
+            UnitSystem::US => 43_560.0,
+            UnitSystem::SI => 10_000.0,
+        };
+        let gutter = run.gutter.section(self.config.unit_system);
+        let capacity = gutter.flow_capacity(self.config.max_spread, k);
+        let runoff_per_length = run.runoff_per_length(self.config.unit_system);
+        if runoff_per_length <= 0.0 {
+            return Err(format!("Gutter run {} has no runoff to space inlets for", run.id));
+        }
+        let first_spacing = capacity / runoff_per_length;
+
+        let end_station = run.start_station + run.length;
+        let mut station = run.start_station;
+        let mut carryover = 0.0;
+        let mut inlets: Vec<InletStation> = Vec::new();
+
+        loop {
+            let mut spacing = (capacity - carryover) / runoff_per_length;
+            if let Some(max) = self.config.max_spacing {
+                spacing = spacing.min(max);
+            }
+            if station + spacing >= end_station {
+                break;
+            }
+            if spacing < 0.01 * first_spacing {
+                let upstream = inlets.last().map(|i| i.inlet_id.as_str()).unwrap_or("the high point");
+                return Err(format!(
+                    "Bypass of {:.2} from {} leaves no room for the next inlet on gutter run {}; use a larger inlet",
+                    carryover, upstream, run.id
+                ));
+            }
+
+            station += spacing;
+            let runoff = runoff_per_length * spacing;
+            let approach_flow = carryover + runoff;
+            let gutter_result = gutter.result_for_flow(approach_flow, k);
+            let interception = on_grade_interception(inlet, approach_flow, &gutter, &gutter_result, self.config.unit_system);
+
+            inlets.push(InletStation {
+                inlet_id: format!("{}-IN{}", run.id, inlets.len() + 1),
+                station,
+                spacing,
+                drainage_area: run.tributary_width * spacing / area_factor,
+                runoff,
+                carryover,
+                approach_flow,
+                spread: gutter_result.spread,
+                depth_at_curb: gutter_result.depth_at_curb,
+                velocity: gutter_result.velocity,
+                intercepted_flow: interception.intercepted_flow,
+                bypass_flow: interception.bypass_flow,
+                efficiency: interception.efficiency,
+            });
+            carryover = interception.bypass_flow;
+        }
+
+        let end_flow = carryover + runoff_per_length * (end_station - station);
+        let (nodes, conduits) = self.network_elements(run, inlet, &inlets);
+        Ok(InletSpacingResult {
+            inlets,
+            end_station,
+            end_flow,
+            nodes,
+            conduits,
+        })
+    }
+
+    /// Inlet nodes at the gutter elevation of each station, linked by gutters
+    fn network_elements(
+        &self,
+        run: &GutterRun,
+        inlet: &InletProperties,
+        inlets: &[InletStation],
+    ) -> (Vec<Node>, Vec<Conduit>) {
+        let properties = InletProperties {
+            location: InletLocation::OnGrade,
+            ..inlet.clone()
+        };
+        let nodes = inlets
+            .iter()
+            .map(|station| {
+                let rim = run.start_elevation
+                    - run.gutter.longitudinal_slope * (station.station - run.start_station);
+                Node::new_inlet(
+                    station.inlet_id.clone(),
+                    rim - self.config.structure_depth,
+                    rim,
+                    properties.clone(),
+                )
+            })
+            .collect();
+        let conduits = inlets
+            .windows(2)
+            .enumerate()
+            .map(|(i, pair)| {
+                Conduit::new_gutter(
+                    format!("{}-G{}", run.id, i + 1),
+                    pair[0].inlet_id.clone(),
+                    pair[1].inlet_id.clone(),
+                    pair[1].spacing,
+                    run.gutter.clone(),
+                )
+            })
+            .collect();
+        (nodes, conduits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{CapacityCriteria, VelocityCriteria};
+    use approx::assert_relative_eq;
+    use crate::node::{BarConfiguration, BoundaryCondition, GrateProperties, InletType};
+    use crate::test_support::{junction, outfall, pipe};
+
+    /// MH-1 -> MH-2 -> OUT-1 at 1%, with 12" placeholder pipes
+    fn network() -> Network {
+        let mut network = Network::new();
+        network.add_node(junction("MH-1", 106.0, 114.0));
+        network.add_node(junction("MH-2", 103.0, 111.0));
+        network.add_node(outfall("OUT-1", 100.0, BoundaryCondition::Free, None));
+        network.add_conduit(pipe("P-1", "MH-1", "MH-2", 300.0, 12.0));
+        network.add_conduit(pipe("P-2", "MH-2", "OUT-1", 300.0, 12.0));
+        network
+    }
+
+    fn flows(p1: f64, p2: f64) -> HashMap<String, f64> {
+        HashMap::from([("P-1".to_string(), p1), ("P-2".to_string(), p2)])
+    }
+
+    #[test]
+    fn test_smallest_standard_size() {
+        let sizer = PipeSizer::new(PipeSizingConfig::us_customary());
+        let result = sizer.size(&network(), &flows(8.0, 12.0)).unwrap();
+
+        // 18" RCP at 1% carries about 10.5 cfs; 15" about 6.5 cfs
+        let p1 = result.pipe("P-1").unwrap();
+        assert_eq!(p1.diameter, 18.0);
+        assert!(p1.full_capacity >= 8.0);
+
+        let p2 = result.pipe("P-2").unwrap();
+        assert_eq!(p2.diameter, 21.0);
+        assert!(result.violations.is_empty());
+
+        let mut sized = network();
+        assert!(result.apply_to(&mut sized));
+        assert_eq!(sized.find_conduit("P-2").unwrap().pipe.as_ref().unwrap().diameter, Some(21.0));
+
+        // Later passes still report the input size
+        assert_eq!(p2.original_diameter, Some(12.0));
+        let resized = sizer.resize(&sized, &flows(8.0, 12.0), &result).unwrap();
+        assert_eq!(resized.pipe("P-2").unwrap().original_diameter, Some(12.0));
+        assert!(!resized.apply_to(&mut sized));
+    }
+
+    #[test]
+    fn test_never_decrease_downstream() {
+        let sizer = PipeSizer::new(PipeSizingConfig::us_customary());
+        let result = sizer.size(&network(), &flows(8.0, 2.0)).unwrap();
+        assert_eq!(result.pipe("P-2").unwrap().diameter, 18.0);
+
+        let config = PipeSizingConfig {
+            no_decrease_downstream: false,
+            ..PipeSizingConfig::us_customary()
+        };
+        let result = PipeSizer::new(config).size(&network(), &flows(8.0, 2.0)).unwrap();
+        assert_eq!(result.pipe("P-2").unwrap().diameter, 12.0);
+    }
+
+    #[test]
+    fn test_criteria() {
+        let criteria = DesignCriteria {
+            gutter_spread: None,
+            hgl_criteria: None,
+            velocity: Some(VelocityCriteria {
+                min_velocity: Some(3.0),
+                max_velocity: None,
+            }),
+            cover: None,
+            capacity: Some(CapacityCriteria {
+                min_capacity_ratio: Some(1.25),
+            }),
+        };
+        let sizer = PipeSizer::new(PipeSizingConfig::us_customary().with_criteria(&criteria));
+
+        // 8 × 1.25 = 10 cfs still fits an 18" pipe; 9 × 1.25 does not
+        let result = sizer.size(&network(), &flows(9.0, 9.0)).unwrap();
+        assert_eq!(result.pipe("P-1").unwrap().diameter, 21.0);
+
+        // 1% is steep enough for 3 ft/s full flow
+        assert!(result.pipe("P-1").unwrap().minimum_slope.unwrap() < 0.01);
+        assert!(result.violations.is_empty());
+
+        // Demand beyond the largest standard size is reported
+        let result = sizer.size(&network(), &flows(5000.0, 5000.0)).unwrap();
+        assert!(!result.pipe("P-1").unwrap().meets_criteria);
+        assert!(result
+            .violations
+            .iter()
+            .any(|v| v.violation_type == ViolationType::Capacity));
+    }
+
+    /// Network with the given rims, outfall invert and pipe diameters
+    fn profile_network(rims: (f64, f64), outfall_invert: f64, diameters: (f64, f64)) -> Network {
+        let mut network = network();
+        for (id, rim) in [("MH-1", rims.0), ("MH-2", rims.1)] {
+            network.nodes.iter_mut().find(|n| n.id == id).unwrap().rim_elevation = Some(rim);
+        }
+        network.nodes.iter_mut().find(|n| n.id == "OUT-1").unwrap().invert_elevation = outfall_invert;
+        for (id, d) in [("P-1", diameters.0), ("P-2", diameters.1)] {
+            network.conduits.iter_mut().find(|c| c.id == id).unwrap().pipe.as_mut().unwrap().diameter = Some(d);
+        }
+        network
+    }
+
+    #[test]
+    fn test_profile_from_cover_and_crowns() {
+        let network = profile_network((110.0, 106.0), 100.0, (18.0, 24.0));
+        let result = ProfileDesigner::new(ProfileConfig::us_customary()).design(&network).unwrap();
+
+        // Rim - 3 ft cover - 1.5 ft pipe at both ends; steeper than 3 ft/s needs
+        let p1 = result.pipe("P-1").unwrap();
+        assert_relative_eq!(p1.upstream_invert, 105.5);
+        assert_relative_eq!(p1.downstream_invert, 101.5);
+        assert!(p1.minimum_slope < p1.slope);
+
+        // 24" crown matched to the 18" crown, then down to the outfall
+        let p2 = result.pipe("P-2").unwrap();
+        assert_relative_eq!(p2.upstream_invert, 101.0);
+        assert_relative_eq!(p2.downstream_invert, 100.0);
+        assert_relative_eq!(p2.upstream_cover.unwrap(), 3.0);
+        assert!(result.violations.is_empty());
+
+        let mut designed = network.clone();
+        assert!(result.apply_to(&mut designed));
+        assert_relative_eq!(designed.find_node("MH-2").unwrap().invert_elevation, 101.0);
+        assert_relative_eq!(designed.find_conduit("P-1").unwrap().slope.unwrap(), 4.0 / 300.0);
+        assert!(!result.apply_to(&mut designed));
+    }
+
+    #[test]
+    fn test_invert_drop_and_minimum_slope() {
+        // Flat ground: the self-cleansing slope sets the fall
+        let network = profile_network((110.0, 110.0), 100.0, (18.0, 18.0));
+        let config = ProfileConfig {
+            junction_rule: JunctionRule::InvertDrop(0.2),
+            ..ProfileConfig::us_customary()
+        };
+        let result = ProfileDesigner::new(config).design(&network).unwrap();
+
+        let p1 = result.pipe("P-1").unwrap();
+        assert_relative_eq!(p1.slope, p1.minimum_slope);
+        assert_relative_eq!(p1.minimum_slope, 0.00254, epsilon = 1e-5);
+
+        let p2 = result.pipe("P-2").unwrap();
+        assert_relative_eq!(p2.upstream_invert, p1.downstream_invert - 0.2);
+        assert_relative_eq!(result.node_inverts["MH-2"], p2.upstream_invert);
+    }
+
+    #[test]
+    fn test_outfall_invert_conflict() {
+        let network = profile_network((110.0, 110.0), 104.5, (18.0, 18.0));
+        let result = ProfileDesigner::new(ProfileConfig::us_customary()).design(&network).unwrap();
+
+        let p2 = result.pipe("P-2").unwrap();
+        assert!(p2.downstream_invert < 104.5);
+        assert_relative_eq!(p2.slope, p2.minimum_slope, epsilon = 1e-12);
+
+        let violation = result
+            .violations
+            .iter()
+            .find(|v| v.violation_type == ViolationType::Profile)
+            .unwrap();
+        assert_eq!(violation.element_id, "P-2");
+        assert_eq!(violation.limit, Some(104.5));
+    }
+
+    /// HEC-22 Example 7.9: 42.7 ft of pavement draining to a 4% cross slope
+    /// gutter on a 3% grade, with 2 ft × 3 ft grates
+    fn example_7_9() -> (GutterRun, InletProperties) {
+        let run = GutterRun {
+            id: "R1".to_string(),
+            start_station: 0.0,
+            length: 3000.0,
+            start_elevation: 200.0,
+            tributary_width: 42.7,
+            runoff_coefficient: 0.73,
+            intensity: 7.1,
+            gutter: GutterProperties {
+                cross_slope: 0.04,
+                longitudinal_slope: 0.03,
+                width: Some(2.0),
+                manning_n: 0.016,
+                depression: None,
+                curb_height: None,
+                crown_height: None,
+                width_to_crown: None,
+            },
+        };
+        let inlet = InletProperties {
+            inlet_type: InletType::Grate,
+            location: InletLocation::OnGrade,
+            grate: Some(GrateProperties {
+                length: Some(3.0),
+                width: Some(2.0),
+                bar_configuration: Some(BarConfiguration::Perpendicular),
+                grate_type: None,
+            }),
+            curb_opening: None,
+            slotted: None,
+            local_depression: None,
+            clogging_factor: Some(0.0),
+            bypass_to: None,
+        };
+        (run, inlet)
+    }
+
+    #[test]
+    fn test_inlet_spacing_by_spread() {
+        let (run, inlet) = example_7_9();
+        let config = InletSpacingConfig {
+            max_spread: 6.6,
+            ..InletSpacingConfig::us_customary()
+        };
+        let result = InletSpacer::new(config).space(&run, &inlet).unwrap();
+
+        // First inlet where the spread reaches the allowable
+        let first = &result.inlets[0];
+        assert_relative_eq!(first.spread, 6.6, epsilon = 0.01);
+        assert_eq!(first.carryover, 0.0);
+
+        // Later inlets take the bypass from upgrade, so they sit closer
+        for pair in result.inlets.windows(2) {
+            assert_relative_eq!(pair[1].carryover, pair[0].bypass_flow);
+            assert_relative_eq!(pair[1].approach_flow, pair[1].carryover + pair[1].runoff);
+            assert!(pair[1].spacing < first.spacing);
+            assert_relative_eq!(pair[1].spread, 6.6, epsilon = 0.01);
+        }
+        assert!(result.inlets.len() >= 3);
+
+        // Generated inlets step down the grade and are linked by gutters
+        assert_eq!(result.nodes.len(), result.inlets.len());
+        assert_eq!(result.conduits.len(), result.inlets.len() - 1);
+        let rim = |i: usize| result.nodes[i].rim_elevation.unwrap();
+        assert_relative_eq!(rim(0) - rim(1), 0.03 * result.inlets[1].spacing, epsilon = 1e-9);
+        assert_eq!(result.conduits[0].from_node, "R1-IN1");
+        assert_eq!(result.conduits[0].to_node, "R1-IN2");
+        assert!(result.conduits[0].is_gutter());
+    }
+
+    #[test]
+    fn test_inlet_spacing_maximum_spacing() {
+        let (run, inlet) = example_7_9();
+        let config = InletSpacingConfig {
+            max_spread: 6.6,
+            max_spacing: Some(360.0),
+            ..InletSpacingConfig::us_customary()
+        };
+        let result = InletSpacer::new(config).space(&run, &inlet).unwrap();
+
+        // 360 ft × 42.7 ft = 0.35 ac; Q = (0.73)(7.1)(0.35) ≈ 1.8 cfs
+        let first = &result.inlets[0];
+        assert_relative_eq!(first.drainage_area, 0.353, epsilon = 0.001);
+        assert_relative_eq!(first.runoff, 1.83, epsilon = 0.01);
+
+        assert_eq!(result.inlets.len(), 8);
+        assert!(result.inlets.iter().all(|i| i.spacing == 360.0 && i.spread < 6.6));
+        assert_relative_eq!(result.end_station, 3000.0);
+        assert!(result.end_flow > result.inlets[7].bypass_flow);
+    }
+}
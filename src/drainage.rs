@@ -3,6 +3,8 @@
 //! Drainage areas represent the contributing areas that generate runoff
 //! to specific inlet points in the network.
 
+use crate::project::UnitSystem;
+use crate::rainfall::IdfCurve;
 use serde::{Deserialize, Serialize};
 
 /// Drainage area (subcatchment) definition
@@ -109,9 +111,15 @@ pub struct LandUseComponent {
 
 /// Time of concentration calculation breakdown
 ///
-/// Tc = sheet flow + shallow concentrated flow + channel flow
+/// With the default velocity method, Tc = sheet flow + shallow concentrated
+/// flow + channel flow. The Kirpich and FAA methods instead use the total
+/// `flow_length` and average `slope` of the area.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TcCalculation {
+    /// Method used to compute Tc (defaults to the velocity method)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<TcMethod>,
+
     /// Sheet flow component (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "sheetFlow")]
@@ -126,6 +134,32 @@ pub struct TcCalculation {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "channelFlow")]
     pub channel_flow: Option<ChannelFlow>,
+
+    /// Longest flow path length for Kirpich/FAA (ft or m)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "flowLength")]
+    pub flow_length: Option<f64>,
+
+    /// Average slope along the flow path for Kirpich/FAA (ft/ft or m/m)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slope: Option<f64>,
+}
+
+/// Time of concentration method
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum TcMethod {
+    /// Segment velocity method with TR-55 sheet flow (HEC-22 Eq. 4.3 - 4.6)
+    #[default]
+    Velocity,
+    /// Velocity method with the kinematic wave overland flow equation for
+    /// the sheet flow segment (iterated against an IDF curve)
+    #[serde(rename = "Kinematic Wave")]
+    KinematicWave,
+    /// Kirpich formula for small rural watersheds
+    Kirpich,
+    /// FAA overland flow formula for airfields and urban areas
+    #[serde(rename = "FAA")]
+    Faa,
 }
 
 /// Sheet flow component of Tc
@@ -137,11 +171,17 @@ pub struct SheetFlow {
     /// Average slope (ft/ft or m/m)
     pub slope: f64,
 
-    /// Surface roughness coefficient (Manning's n)
+    /// Surface roughness coefficient (Manning's n, HEC-22 Table 4.2)
     pub roughness: f64,
 
-    /// Travel time (minutes)
-    pub time: f64,
+    /// 2-year, 24-hour rainfall depth P2 (in or mm)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "rainfallDepth2yr")]
+    pub rainfall_depth_2yr: Option<f64>,
+
+    /// Travel time (minutes); computed from the segment when omitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<f64>,
 }
 
 /// Shallow concentrated flow component of Tc
@@ -157,8 +197,9 @@ pub struct ShallowConcentratedFlow {
     #[serde(rename = "surfaceType")]
     pub surface_type: SurfaceType,
 
-    /// Travel time (minutes)
-    pub time: f64,
+    /// Travel time (minutes); computed from the segment when omitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<f64>,
 }
 
 /// Surface type for shallow concentrated flow
@@ -168,19 +209,274 @@ pub enum SurfaceType {
     Paved,
     /// Unpaved surface (lower velocity)
     Unpaved,
+    /// Grassed waterway
+    #[serde(rename = "Grassed Waterway")]
+    GrassedWaterway,
+}
+
+impl SurfaceType {
+    /// Intercept coefficient k for the velocity-slope relationship
+    ///
+    /// Values from HEC-22 Table 4.3.
+    pub fn intercept_coefficient(&self) -> f64 {
+        match self {
+            SurfaceType::Paved => 0.619,
+            SurfaceType::Unpaved => 0.491,
+            SurfaceType::GrassedWaterway => 0.457,
+        }
+    }
 }
 
 /// Channel flow component of Tc
+///
+/// The velocity is either given directly or computed with Manning's equation
+/// from `roughness`, `slope` and `hydraulic_radius`.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ChannelFlow {
     /// Flow length (ft or m)
     pub length: f64,
 
     /// Average velocity (ft/s or m/s)
-    pub velocity: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub velocity: Option<f64>,
 
-    /// Travel time (minutes)
-    pub time: f64,
+    /// Manning's roughness coefficient
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roughness: Option<f64>,
+
+    /// Channel slope (ft/ft or m/m)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slope: Option<f64>,
+
+    /// Hydraulic radius, flow area / wetted perimeter (ft or m)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "hydraulicRadius")]
+    pub hydraulic_radius: Option<f64>,
+
+    /// Travel time (minutes); computed from the segment when omitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<f64>,
+}
+
+impl SheetFlow {
+    /// Sheet flow travel time (minutes)
+    ///
+    /// Returns the stored `time` if present, otherwise applies the TR-55
+    /// kinematic wave approximation:
+    ///
+    /// **HEC-22 Equation 4.3**
+    /// ```text
+    /// Tt = (Ku / (P2^0.5 × S^0.4)) × (n × L)^0.8
+    /// ```
+    /// where Ku = 0.42 (US) or 5.5 (SI).
+    ///
+    /// # Returns
+    /// Travel time, or an error if neither a time nor P2 is given
+    pub fn travel_time(&self, unit_system: UnitSystem) -> Result<f64, String> {
+        if let Some(time) = self.time {
+            return Ok(time);
+        }
+
+        let p2 = self.rainfall_depth_2yr.ok_or_else(|| {
+            "Sheet flow segment needs a travel time or 2-year, 24-hour rainfall depth".to_string()
+        })?;
+        if p2 <= 0.0 || self.slope <= 0.0 {
+            return Err("Sheet flow rainfall depth and slope must be positive".to_string());
+        }
+
+        let ku = match unit_system {
+            UnitSystem::US => 0.42,
+            UnitSystem::SI => 5.5,
+        };
+
+        Ok(ku * (self.roughness * self.length).powf(0.8) / (p2.sqrt() * self.slope.powf(0.4)))
+    }
+
+    /// Kinematic wave overland flow travel time for a rainfall intensity (minutes)
+    ///
+    /// ```text
+    /// Tt = (Ku / i^0.4) × (n × L / S^0.5)^0.6
+    /// ```
+    /// where Ku = 0.933 (US, i in in/hr) or 6.92 (SI, i in mm/hr).
+    ///
+    /// Because `i` depends on Tc, this is solved iteratively against an IDF
+    /// curve (see [`TcCalculation::time_of_concentration`]).
+    pub fn kinematic_wave_time(&self, intensity: f64, unit_system: UnitSystem) -> f64 {
+        let ku = match unit_system {
+            UnitSystem::US => 0.933,
+            UnitSystem::SI => 6.92,
+        };
+
+        ku / intensity.powf(0.4) * (self.roughness * self.length / self.slope.sqrt()).powf(0.6)
+    }
+}
+
+impl ShallowConcentratedFlow {
+    /// Shallow concentrated flow velocity (ft/s or m/s)
+    ///
+    /// **HEC-22 Equation 4.4**
+    /// ```text
+    /// V = Ku × k × Sp^0.5
+    /// ```
+    /// where Sp is the slope in percent and Ku = 3.281 (US) or 1.0 (SI).
+    pub fn velocity(&self, unit_system: UnitSystem) -> f64 {
+        let ku = match unit_system {
+            UnitSystem::US => 3.281,
+            UnitSystem::SI => 1.0,
+        };
+
+        ku * self.surface_type.intercept_coefficient() * (self.slope * 100.0).sqrt()
+    }
+
+    /// Travel time (minutes), Tt = L / (60 V)
+    pub fn travel_time(&self, unit_system: UnitSystem) -> Result<f64, String> {
+        if let Some(time) = self.time {
+            return Ok(time);
+        }
+
+        travel_time(self.length, self.velocity(unit_system))
+    }
+}
+
+impl ChannelFlow {
+    /// Channel velocity (ft/s or m/s)
+    ///
+    /// Uses the stored velocity if present, otherwise Manning's equation:
+    ///
+    /// **HEC-22 Equation 4.5**
+    /// ```text
+    /// V = (Ku / n) × R^(2/3) × S^(1/2)
+    /// ```
+    pub fn velocity(&self, unit_system: UnitSystem) -> Option<f64> {
+        if let Some(velocity) = self.velocity {
+            return Some(velocity);
+        }
+
+        let n = self.roughness?;
+        let slope = self.slope?;
+        let radius = self.hydraulic_radius?;
+        let ku = match unit_system {
+            UnitSystem::US => 1.49,
+            UnitSystem::SI => 1.0,
+        };
+
+        Some(ku / n * radius.powf(2.0 / 3.0) * slope.sqrt())
+    }
+
+    /// Travel time (minutes), Tt = L / (60 V)
+    pub fn travel_time(&self, unit_system: UnitSystem) -> Result<f64, String> {
+        if let Some(time) = self.time {
+            return Ok(time);
+        }
+
+        let velocity = self.velocity(unit_system).ok_or_else(|| {
+            "Channel flow segment needs a velocity or Manning roughness, slope and hydraulic radius"
+                .to_string()
+        })?;
+        travel_time(self.length, velocity)
+    }
+}
+
+impl TcCalculation {
+    /// Time of concentration by the selected method (minutes)
+    ///
+    /// # Arguments
+    /// * `unit_system` - Unit system of the segment data
+    /// * `runoff_coefficient` - Rational C, required by the FAA method
+    /// * `idf` - IDF curve, required by the kinematic wave method
+    pub fn time_of_concentration(
+        &self,
+        unit_system: UnitSystem,
+        runoff_coefficient: Option<f64>,
+        idf: Option<&IdfCurve>,
+    ) -> Result<f64, String> {
+        match self.method.unwrap_or_default() {
+            TcMethod::Velocity => Ok(self.sheet_flow_time(unit_system)? + self.downstream_time(unit_system)?),
+            TcMethod::KinematicWave => self.kinematic_wave_tc(unit_system, idf),
+            TcMethod::Kirpich => {
+                let (length, slope) = self.flow_path()?;
+                let k = match unit_system {
+                    UnitSystem::US => 0.0078,
+                    UnitSystem::SI => 0.0195,
+                };
+                Ok(k * length.powf(0.77) * slope.powf(-0.385))
+            }
+            TcMethod::Faa => {
+                let (length, slope) = self.flow_path()?;
+                let c = runoff_coefficient
+                    .ok_or_else(|| "FAA Tc method requires a runoff coefficient".to_string())?;
+                let k = match unit_system {
+                    UnitSystem::US => 1.8,
+                    UnitSystem::SI => 3.26,
+                };
+                Ok(k * (1.1 - c) * length.sqrt() / (slope * 100.0).cbrt())
+            }
+        }
+    }
+
+    fn sheet_flow_time(&self, unit_system: UnitSystem) -> Result<f64, String> {
+        self.sheet_flow
+            .as_ref()
+            .map_or(Ok(0.0), |s| s.travel_time(unit_system))
+    }
+
+    /// Shallow concentrated plus channel travel time
+    fn downstream_time(&self, unit_system: UnitSystem) -> Result<f64, String> {
+        let shallow = self
+            .shallow_concentrated
+            .as_ref()
+            .map_or(Ok(0.0), |s| s.travel_time(unit_system))?;
+        let channel = self
+            .channel_flow
+            .as_ref()
+            .map_or(Ok(0.0), |c| c.travel_time(unit_system))?;
+        Ok(shallow + channel)
+    }
+
+    /// Iterate Tc = Tt,sheet(i(Tc)) + Tt,downstream until it stops changing
+    fn kinematic_wave_tc(&self, unit_system: UnitSystem, idf: Option<&IdfCurve>) -> Result<f64, String> {
+        let downstream = self.downstream_time(unit_system)?;
+        let sheet = match &self.sheet_flow {
+            Some(sheet) if sheet.time.is_none() => sheet,
+            _ => return Ok(self.sheet_flow_time(unit_system)? + downstream),
+        };
+        let idf = idf.ok_or_else(|| "Kinematic wave Tc requires an IDF curve".to_string())?;
+        if sheet.slope <= 0.0 {
+            return Err("Sheet flow slope must be positive".to_string());
+        }
+
+        let mut tc = downstream.max(5.0);
+        for _ in 0..50 {
+            let intensity = idf
+                .get_intensity(tc)
+                .filter(|&i| i > 0.0)
+                .ok_or_else(|| format!("IDF curve has no intensity for duration {:.1} min", tc))?;
+            let next = sheet.kinematic_wave_time(intensity, unit_system) + downstream;
+            if (next - tc).abs() < 0.01 {
+                return Ok(next);
+            }
+            tc = next;
+        }
+
+        Err("Kinematic wave Tc did not converge".to_string())
+    }
+
+    fn flow_path(&self) -> Result<(f64, f64), String> {
+        match (self.flow_length, self.slope) {
+            (Some(length), Some(slope)) if length > 0.0 && slope > 0.0 => Ok((length, slope)),
+            _ => Err("Tc method requires a positive flow length and slope".to_string()),
+        }
+    }
+}
+
+/// Travel time (minutes) over `length` at `velocity`
+///
+/// **HEC-22 Equation 4.6**
+fn travel_time(length: f64, velocity: f64) -> Result<f64, String> {
+    if velocity <= 0.0 {
+        return Err("Travel velocity must be positive".to_string());
+    }
+    Ok(length / (60.0 * velocity))
 }
 
 /// Spatial geometry (GeoJSON-compatible)
@@ -197,19 +493,33 @@ pub struct Geometry {
 
 impl DrainageArea {
     /// Calculate total time of concentration from components
-    pub fn calculate_total_tc(&self) -> Option<f64> {
-        self.tc_calculation.as_ref().map(|calc| {
-            let sheet = calc.sheet_flow.as_ref().map(|s| s.time).unwrap_or(0.0);
-            let shallow = calc
-                .shallow_concentrated
-                .as_ref()
-                .map(|s| s.time)
-                .unwrap_or(0.0);
-            let channel = calc.channel_flow.as_ref().map(|c| c.time).unwrap_or(0.0);
-            sheet + shallow + channel
+    ///
+    /// Returns `None` if there is no Tc breakdown or it cannot be evaluated
+    /// without an IDF curve; see [`DrainageArea::design_tc`].
+    pub fn calculate_total_tc(&self, unit_system: UnitSystem) -> Option<f64> {
+        self.tc_calculation.as_ref().and_then(|calc| {
+            calc.time_of_concentration(unit_system, self.runoff_coefficient, None)
+                .ok()
         })
     }
 
+    /// Time of concentration used for design (minutes)
+    ///
+    /// An explicit `time_of_concentration` takes precedence; otherwise Tc is
+    /// computed from `tc_calculation`.
+    pub fn design_tc(&self, unit_system: UnitSystem, idf: Option<&IdfCurve>) -> Result<f64, String> {
+        if let Some(tc) = self.time_of_concentration {
+            return Ok(tc);
+        }
+
+        let calc = self
+            .tc_calculation
+            .as_ref()
+            .ok_or_else(|| format!("Drainage area {} has no time of concentration", self.id))?;
+        calc.time_of_concentration(unit_system, self.runoff_coefficient, idf)
+            .map_err(|e| format!("Drainage area {}: {}", self.id, e))
+    }
+
     /// Calculate runoff using Rational Method: Q = C × i × A
     ///
     /// Returns flow in cfs (or cms if SI units)
@@ -222,6 +532,8 @@ impl DrainageArea {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rainfall::IdfPoint;
+    use approx::assert_relative_eq;
 
     #[test]
     fn test_calculate_tc() {
@@ -234,32 +546,143 @@ mod tests {
             runoff_coefficient: Some(0.85),
             time_of_concentration: None,
             tc_calculation: Some(TcCalculation {
+                method: None,
                 sheet_flow: Some(SheetFlow {
                     length: 50.0,
                     slope: 0.02,
                     roughness: 0.011,
-                    time: 3.0,
+                    rainfall_depth_2yr: None,
+                    time: Some(3.0),
                 }),
                 shallow_concentrated: Some(ShallowConcentratedFlow {
                     length: 200.0,
                     slope: 0.015,
                     surface_type: SurfaceType::Paved,
-                    time: 5.0,
+                    time: Some(5.0),
                 }),
                 channel_flow: Some(ChannelFlow {
                     length: 150.0,
-                    velocity: 3.5,
-                    time: 2.0,
+                    velocity: Some(3.5),
+                    roughness: None,
+                    slope: None,
+                    hydraulic_radius: None,
+                    time: Some(2.0),
                 }),
+                flow_length: None,
+                slope: None,
             }),
             curve_number: None,
             geometry: None,
         };
 
-        let total_tc = drainage_area.calculate_total_tc().unwrap();
+        let total_tc = drainage_area.calculate_total_tc(UnitSystem::US).unwrap();
         assert_eq!(total_tc, 10.0);
     }
 
+    /// HEC-22 Example 4.2: sheet, grassed waterway and 15-inch pipe segments
+    fn example_4_2() -> TcCalculation {
+        TcCalculation {
+            method: None,
+            sheet_flow: Some(SheetFlow {
+                length: 223.0,
+                slope: 0.010,
+                roughness: 0.41,
+                rainfall_depth_2yr: Some(4.35),
+                time: None,
+            }),
+            shallow_concentrated: Some(ShallowConcentratedFlow {
+                length: 259.0,
+                slope: 0.006,
+                surface_type: SurfaceType::GrassedWaterway,
+                time: None,
+            }),
+            channel_flow: Some(ChannelFlow {
+                length: 479.0,
+                velocity: None,
+                roughness: Some(0.011),
+                slope: Some(0.008),
+                hydraulic_radius: Some(1.25 / 4.0),
+                time: None,
+            }),
+            flow_length: None,
+            slope: None,
+        }
+    }
+
+    #[test]
+    fn test_example_4_2_velocity_method() {
+        let calc = example_4_2();
+
+        let sheet = calc.sheet_flow.as_ref().unwrap();
+        assert_relative_eq!(sheet.travel_time(UnitSystem::US).unwrap(), 47.1, epsilon = 0.1);
+
+        let shallow = calc.shallow_concentrated.as_ref().unwrap();
+        assert_relative_eq!(shallow.velocity(UnitSystem::US), 1.16, epsilon = 0.01);
+
+        let channel = calc.channel_flow.as_ref().unwrap();
+        assert_relative_eq!(channel.velocity(UnitSystem::US).unwrap(), 5.58, epsilon = 0.05);
+
+        let tc = calc.time_of_concentration(UnitSystem::US, None, None).unwrap();
+        assert_relative_eq!(tc, 52.2, epsilon = 0.2);
+    }
+
+    #[test]
+    fn test_kirpich_and_faa() {
+        let calc = TcCalculation {
+            method: Some(TcMethod::Kirpich),
+            sheet_flow: None,
+            shallow_concentrated: None,
+            channel_flow: None,
+            flow_length: Some(1000.0),
+            slope: Some(0.02),
+        };
+
+        // Kirpich: 0.0078 × 1000^0.77 × 0.02^-0.385 = 7.2 min
+        let kirpich = calc.time_of_concentration(UnitSystem::US, None, None).unwrap();
+        assert_relative_eq!(kirpich, 7.2, epsilon = 0.1);
+
+        // FAA: 1.8 × (1.1 - 0.3) × 1000^0.5 / 2^(1/3) = 36.1 min
+        let faa = TcCalculation {
+            method: Some(TcMethod::Faa),
+            ..calc
+        };
+        assert!(faa.time_of_concentration(UnitSystem::US, None, None).is_err());
+        let tc = faa.time_of_concentration(UnitSystem::US, Some(0.3), None).unwrap();
+        assert_relative_eq!(tc, 36.1, epsilon = 0.1);
+    }
+
+    #[test]
+    fn test_kinematic_wave_iterates_on_idf() {
+        let idf = IdfCurve {
+            return_period: 10.0,
+            equation: None,
+            points: vec![
+                IdfPoint { duration: 5.0, intensity: 7.0 },
+                IdfPoint { duration: 15.0, intensity: 5.0 },
+                IdfPoint { duration: 30.0, intensity: 3.6 },
+                IdfPoint { duration: 60.0, intensity: 2.4 },
+            ],
+        };
+        let calc = TcCalculation {
+            method: Some(TcMethod::KinematicWave),
+            ..example_4_2()
+        };
+
+        assert!(calc.time_of_concentration(UnitSystem::US, None, None).is_err());
+
+        let tc = calc.time_of_concentration(UnitSystem::US, None, Some(&idf)).unwrap();
+        let intensity = idf.get_intensity(tc).unwrap();
+        let sheet = calc.sheet_flow.as_ref().unwrap();
+
+        // Tc is consistent with the intensity read from the IDF curve at Tc
+        assert_relative_eq!(
+            tc,
+            sheet.kinematic_wave_time(intensity, UnitSystem::US)
+                + calc.downstream_time(UnitSystem::US).unwrap(),
+            epsilon = 0.05
+        );
+    }
+
     #[test]
     fn test_rational_method() {
         let drainage_area = DrainageArea {
@@ -21,7 +21,11 @@
 //! - S_x = cross slope (ft/ft)
 //! - S_L = longitudinal slope (ft/ft)
 //! - T = spread (ft)
+//!
+//! Flow areas come from each section's [`CrossSection`] geometry, with the
+//! depth measured at the curb.
 
+use crate::section::{CrossSection, Triangular};
 use std::f64::consts::PI;
 
 /// Gutter section type
@@ -57,6 +61,7 @@ pub struct GutterFlowResult {
 /// Uniform cross-slope gutter calculator
 ///
 /// For simple triangular gutter sections with uniform cross slope
+#[derive(Debug, Clone, PartialEq)]
 pub struct UniformGutter {
     /// Manning's roughness coefficient
     pub manning_n: f64,
@@ -124,8 +129,7 @@ impl UniformGutter {
         // Depth at curb
         let depth_at_curb = spread * self.cross_slope;
 
-        // Flow area (triangular)
-        let area = 0.5 * spread * depth_at_curb;
+        let area = self.area(depth_at_curb);
 
         // Velocity
         let velocity = if area > 0.0 { flow / area } else { 0.0 };
@@ -150,35 +154,64 @@ impl UniformGutter {
         let spread = self.spread_for_flow(flow, k);
         self.flow_result(spread, k)
     }
+
+    /// Triangle between the vertical curb face and the pavement
+    fn triangle(&self) -> Triangular {
+        Triangular {
+            left_slope: 0.0,
+            right_slope: 1.0 / self.cross_slope,
+        }
+    }
+}
+
+impl CrossSection for UniformGutter {
+    fn area(&self, depth: f64) -> f64 {
+        self.triangle().area(depth)
+    }
+
+    fn wetted_perimeter(&self, depth: f64) -> f64 {
+        self.triangle().wetted_perimeter(depth)
+    }
+
+    fn top_width(&self, depth: f64) -> f64 {
+        self.triangle().top_width(depth)
+    }
+
+    fn max_depth(&self) -> Option<f64> {
+        None
+    }
 }
 
 /// Composite gutter section calculator
 ///
 /// For sections with a depressed gutter section and roadway with different slopes
+#[derive(Debug, Clone, PartialEq)]
 pub struct CompositeGutter {
     /// Manning's roughness coefficient
     pub manning_n: f64,
-    /// Gutter cross slope S_x (ft/ft)
+    /// Gutter cross slope before the depression is added (ft/ft)
     pub gutter_slope: f64,
-    /// Roadway cross slope S_w (ft/ft)
+    /// Roadway cross slope S_x (ft/ft)
     pub roadway_slope: f64,
     /// Longitudinal slope S_L (ft/ft)
     pub longitudinal_slope: f64,
     /// Gutter width W (ft)
     pub gutter_width: f64,
-    /// Local depression a (in or mm)
-    pub local_depression: f64,
+    /// Gutter depression a as a length (ft or m, not in or mm)
+    pub local_depression_ft: f64,
 }
 
 impl CompositeGutter {
     /// Create a new composite gutter calculator
+    ///
+    /// The depression is a length in ft (m), e.g. `2.0 / 12.0` for 2 in.
     pub fn new(
         manning_n: f64,
         gutter_slope: f64,
         roadway_slope: f64,
         longitudinal_slope: f64,
         gutter_width: f64,
-        local_depression: f64,
+        local_depression_ft: f64,
     ) -> Self {
         Self {
             manning_n,
@@ -186,84 +219,83 @@ impl CompositeGutter {
             roadway_slope,
             longitudinal_slope,
             gutter_width,
-            local_depression,
+            local_depression_ft,
         }
     }
 
-    /// Calculate equivalent cross slope S_x'
+    /// Depressed section cross slope S_w (HEC-22 Equation 5.8)
     ///
-    /// S_x' = S_x + a/W
-    /// where a is local depression (converted to ft) and W is gutter width
-    fn equivalent_cross_slope(&self, depression_ft: f64) -> f64 {
-        self.gutter_slope + (depression_ft / self.gutter_width)
+    /// S_w = S_x + a/W
+    /// where a is the depression (ft or m) and W is gutter width
+    fn depressed_slope(&self, depression: f64) -> f64 {
+        self.gutter_slope + (depression / self.gutter_width)
     }
 
-    /// Calculate flow efficiency ratio E_o
+    /// Ratio of depressed section flow to total flow E_o (HEC-22 Equation 5.7)
     ///
-    /// E_o = (1 + S_w/S_x')^(8/3) / [1 + (S_w/S_x')^(8/3)]
-    fn flow_efficiency_ratio(&self, sx_prime: f64) -> f64 {
-        let ratio = self.roadway_slope / sx_prime;
-        let term = (1.0 + ratio).powf(8.0 / 3.0);
-        term / (1.0 + ratio.powf(8.0 / 3.0))
-    }
-
-    /// Calculate spread width ratio W/T
-    fn width_ratio(&self, spread: f64) -> f64 {
-        self.gutter_width / spread
+    /// E_o = 1 / {1 + (S_w/S_x) / [(1 + (S_w/S_x)/(T/W - 1))^(8/3) - 1]}
+    ///
+    /// All flow is in the depressed section when the spread does not extend
+    /// past the gutter width.
+    fn flow_efficiency_ratio(&self, sw: f64, spread: f64) -> f64 {
+        if spread <= self.gutter_width {
+            return 1.0;
+        }
+        let sw_over_sx = sw / self.roadway_slope;
+        let term = (1.0 + sw_over_sx / (spread / self.gutter_width - 1.0)).powf(8.0 / 3.0) - 1.0;
+        1.0 / (1.0 + sw_over_sx / term)
     }
 
     /// Calculate frontal flow Q_w (flow in gutter section)
     ///
-    /// Q_w = Q × E_o
-    pub fn frontal_flow(&self, total_flow: f64, spread: f64, depression_ft: f64, k: f64) -> f64 {
-        let sx_prime = self.equivalent_cross_slope(depression_ft);
-        let eo = self.flow_efficiency_ratio(sx_prime);
-        total_flow * eo
+    /// Q_w = Q × E_o (HEC-22 Equation 5.5)
+    pub fn frontal_flow(&self, total_flow: f64, spread: f64, depression_ft: f64, _k: f64) -> f64 {
+        let sw = self.depressed_slope(depression_ft);
+        total_flow * self.flow_efficiency_ratio(sw, spread)
     }
 
     /// Calculate side flow Q_s (flow on roadway)
     ///
-    /// Q_s = Q × (1 - E_o)
-    pub fn side_flow(&self, total_flow: f64, spread: f64, depression_ft: f64, k: f64) -> f64 {
-        let sx_prime = self.equivalent_cross_slope(depression_ft);
-        let eo = self.flow_efficiency_ratio(sx_prime);
-        total_flow * (1.0 - eo)
+    /// Q_s = Q × (1 - E_o) (HEC-22 Equation 5.6)
+    pub fn side_flow(&self, total_flow: f64, spread: f64, depression_ft: f64, _k: f64) -> f64 {
+        let sw = self.depressed_slope(depression_ft);
+        total_flow * (1.0 - self.flow_efficiency_ratio(sw, spread))
     }
 
     /// Calculate total flow capacity for composite section
     ///
-    /// Uses modified gutter equation accounting for composite geometry
+    /// The side flow comes from the triangular gutter equation on the
+    /// roadway spread T_s = T - W, and the total follows from
+    /// Q = Q_s / (1 - E_o). Spreads within the gutter width are treated as
+    /// a triangular section at S_w.
     pub fn flow_capacity(&self, spread: f64, k: f64) -> f64 {
-        // Convert depression to feet if in inches
-        let depression_ft = if self.local_depression < 1.0 {
-            self.local_depression // Already in feet
-        } else {
-            self.local_depression / 12.0 // Convert inches to feet
-        };
-
-        let sx_prime = self.equivalent_cross_slope(depression_ft);
+        let sw = self.depressed_slope(self.local_depression_ft);
 
-        // Calculate spread ratio
-        let w_over_t = self.width_ratio(spread);
-
-        // Calculate efficiency for spread ratio
-        let sw_over_sx = self.roadway_slope / sx_prime;
+        if spread <= self.gutter_width {
+            return (k / self.manning_n)
+                * sw.powf(5.0 / 3.0)
+                * self.longitudinal_slope.sqrt()
+                * spread.powf(8.0 / 3.0);
+        }
 
-        // Total flow using composite section equation
-        let q_total = (k / self.manning_n)
-            * sx_prime.powf(5.0 / 3.0)
+        let q_side = (k / self.manning_n)
+            * self.roadway_slope.powf(5.0 / 3.0)
             * self.longitudinal_slope.sqrt()
-            * spread.powf(8.0 / 3.0)
-            * (1.0 + sw_over_sx.powf(8.0 / 3.0) - (w_over_t).powf(8.0 / 3.0) * sw_over_sx.powf(8.0 / 3.0));
+            * (spread - self.gutter_width).powf(8.0 / 3.0);
 
-        q_total
+        q_side / (1.0 - self.flow_efficiency_ratio(sw, spread))
     }
 
     /// Calculate spread for a given flow rate (iterative)
     pub fn spread_for_flow(&self, flow: f64, k: f64) -> f64 {
-        // Iterative solution using bisection
-        let mut t_low = self.gutter_width;
-        let mut t_high = 50.0; // Maximum spread assumption
+        // Bracket the spread, then bisect
+        let mut t_low = 0.0;
+        let mut t_high = self.gutter_width.max(1.0);
+        while self.flow_capacity(t_high, k) < flow {
+            t_low = t_high;
+            t_high *= 2.0;
+        }
+
         let tolerance = 0.001;
         let max_iterations = 50;
 
@@ -291,21 +323,20 @@ impl CompositeGutter {
 
     /// Calculate complete flow result for given spread
     pub fn flow_result(&self, spread: f64, k: f64) -> GutterFlowResult {
-        let depression_ft = if self.local_depression < 1.0 {
-            self.local_depression
-        } else {
-            self.local_depression / 12.0
-        };
+        let depression = self.local_depression_ft;
+        let sw = self.depressed_slope(depression);
 
         let flow = self.flow_capacity(spread, k);
-        let frontal = self.frontal_flow(flow, spread, depression_ft, k);
-        let side = self.side_flow(flow, spread, depression_ft, k);
-
-        // Depth at curb (including depression)
-        let depth_at_curb = spread * self.gutter_slope + depression_ft;
+        let frontal = self.frontal_flow(flow, spread, depression, k);
+        let side = self.side_flow(flow, spread, depression, k);
 
-        // Approximate area (simplified)
-        let area = 0.5 * spread * depth_at_curb;
+        // Depth at curb across the depressed gutter and the roadway beyond
+        let depth_at_curb = if spread <= self.gutter_width {
+            spread * sw
+        } else {
+            (spread - self.gutter_width) * self.roadway_slope + self.gutter_width * sw
+        };
+        let area = self.cross_section().area(depth_at_curb);
 
         // Velocity
         let velocity = if area > 0.0 { flow / area } else { 0.0 };
@@ -326,11 +357,78 @@ impl CompositeGutter {
         let spread = self.spread_for_flow(flow, k);
         self.flow_result(spread, k)
     }
+
+    /// Geometry of the depressed gutter and roadway
+    pub fn cross_section(&self) -> CompositeGutterSection {
+        CompositeGutterSection {
+            gutter_width: self.gutter_width,
+            depressed_slope: self.depressed_slope(self.local_depression_ft),
+            roadway_slope: self.roadway_slope,
+        }
+    }
+}
+
+/// Geometry of a composite gutter: a depressed gutter at the curb and the
+/// roadway cross slope beyond it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompositeGutterSection {
+    /// Gutter width W (ft or m)
+    pub gutter_width: f64,
+    /// Depressed gutter cross slope S_w (ft/ft)
+    pub depressed_slope: f64,
+    /// Roadway cross slope S_x (ft/ft)
+    pub roadway_slope: f64,
+}
+
+impl CompositeGutterSection {
+    /// Depth at the curb where the water reaches the edge of the gutter
+    fn gutter_depth(&self) -> f64 {
+        self.gutter_width * self.depressed_slope
+    }
+
+    /// Spread onto the roadway beyond the gutter
+    fn side_spread(&self, depth: f64) -> f64 {
+        (depth - self.gutter_depth()).max(0.0) / self.roadway_slope
+    }
+}
+
+impl CrossSection for CompositeGutterSection {
+    fn area(&self, depth: f64) -> f64 {
+        let depth = depth.max(0.0);
+        if depth <= self.gutter_depth() {
+            return depth * depth / (2.0 * self.depressed_slope);
+        }
+        let side_spread = self.side_spread(depth);
+        depth * self.gutter_width - 0.5 * self.depressed_slope * self.gutter_width.powi(2)
+            + 0.5 * self.roadway_slope * side_spread.powi(2)
+    }
+
+    fn wetted_perimeter(&self, depth: f64) -> f64 {
+        let depth = depth.max(0.0);
+        let gutter_spread = (depth / self.depressed_slope).min(self.gutter_width);
+        depth
+            + gutter_spread * (1.0 + self.depressed_slope.powi(2)).sqrt()
+            + self.side_spread(depth) * (1.0 + self.roadway_slope.powi(2)).sqrt()
+    }
+
+    fn top_width(&self, depth: f64) -> f64 {
+        let depth = depth.max(0.0);
+        if depth <= self.gutter_depth() {
+            depth / self.depressed_slope
+        } else {
+            self.gutter_width + self.side_spread(depth)
+        }
+    }
+
+    fn max_depth(&self) -> Option<f64> {
+        None
+    }
 }
 
 /// Parabolic crown section calculator
 ///
 /// For roadways with parabolic cross-section
+#[derive(Debug, Clone, PartialEq)]
 pub struct ParabolicCrown {
     /// Manning's roughness coefficient
     pub manning_n: f64,
@@ -412,8 +510,7 @@ impl ParabolicCrown {
         let sx_equiv = self.equivalent_slope_at_spread(spread);
         let depth_at_curb = spread * sx_equiv / 2.0; // Approximate
 
-        // Parabolic area: A = (2/3) × T × d
-        let area = (2.0 / 3.0) * spread * depth_at_curb;
+        let area = self.area(depth_at_curb);
 
         let velocity = if area > 0.0 { flow / area } else { 0.0 };
 
@@ -435,6 +532,158 @@ impl ParabolicCrown {
     }
 }
 
+/// Parabolic roadway surface d = h_c (T/T_c)² rising from the curb
+impl CrossSection for ParabolicCrown {
+    /// A = (2/3) × T × d
+    fn area(&self, depth: f64) -> f64 {
+        (2.0 / 3.0) * self.top_width(depth) * depth.max(0.0)
+    }
+
+    fn wetted_perimeter(&self, depth: f64) -> f64 {
+        // Curb face plus the arc length of the parabola out to the spread
+        let spread = self.top_width(depth);
+        let a = 2.0 * self.crown_height / self.width_to_crown.powi(2);
+        let arc = if a > 0.0 {
+            0.5 * spread * (1.0 + (a * spread).powi(2)).sqrt() + (a * spread).asinh() / (2.0 * a)
+        } else {
+            spread
+        };
+        depth.max(0.0) + arc
+    }
+
+    fn top_width(&self, depth: f64) -> f64 {
+        self.width_to_crown * (depth.max(0.0) / self.crown_height).sqrt()
+    }
+
+    fn max_depth(&self) -> Option<f64> {
+        None
+    }
+}
+
+/// Gutter section of any supported type
+///
+/// Dispatches spread and velocity calculations to the matching calculator,
+/// so callers can work with whichever section a gutter actually has.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GutterSection {
+    /// Uniform cross slope
+    Uniform(UniformGutter),
+    /// Depressed gutter with roadway
+    Composite(CompositeGutter),
+    /// Parabolic crown
+    ParabolicCrown(ParabolicCrown),
+}
+
+impl GutterSection {
+    /// Type of this section
+    pub fn section_type(&self) -> GutterSectionType {
+        match self {
+            GutterSection::Uniform(_) => GutterSectionType::UniformSlope,
+            GutterSection::Composite(_) => GutterSectionType::Composite,
+            GutterSection::ParabolicCrown(_) => GutterSectionType::ParabolicCrown,
+        }
+    }
+
+    /// Longitudinal slope S_L (ft/ft or m/m)
+    pub fn longitudinal_slope(&self) -> f64 {
+        match self {
+            GutterSection::Uniform(g) => g.longitudinal_slope,
+            GutterSection::Composite(g) => g.longitudinal_slope,
+            GutterSection::ParabolicCrown(g) => g.longitudinal_slope,
+        }
+    }
+
+    /// Manning's roughness coefficient
+    pub fn manning_n(&self) -> f64 {
+        match self {
+            GutterSection::Uniform(g) => g.manning_n,
+            GutterSection::Composite(g) => g.manning_n,
+            GutterSection::ParabolicCrown(g) => g.manning_n,
+        }
+    }
+
+    /// Equivalent cross slope S_e at a spread (HEC-22 Equation 7.11)
+    ///
+    /// S_e = S_x + S'_w × E_o
+    ///
+    /// where S'_w = a/W is the gutter depression slope. Uniform sections
+    /// return their cross slope, and parabolic sections the average slope
+    /// across the spread.
+    pub fn equivalent_cross_slope(&self, spread: f64, k: f64) -> f64 {
+        match self {
+            GutterSection::Uniform(g) => g.cross_slope,
+            GutterSection::Composite(g) => {
+                let depression = g.local_depression_ft;
+                let e0 = g.flow_efficiency_ratio(g.depressed_slope(depression), spread);
+                g.roadway_slope + depression / g.gutter_width * e0
+            }
+            GutterSection::ParabolicCrown(g) => {
+                if spread > 0.0 {
+                    g.flow_result(spread, k).depth_at_curb / spread
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    /// Calculate flow capacity for a given spread
+    pub fn flow_capacity(&self, spread: f64, k: f64) -> f64 {
+        match self {
+            GutterSection::Uniform(g) => g.flow_capacity(spread, k),
+            GutterSection::Composite(g) => g.flow_capacity(spread, k),
+            GutterSection::ParabolicCrown(g) => g.flow_capacity(spread, k),
+        }
+    }
+
+    /// Calculate complete flow result for a given flow
+    pub fn result_for_flow(&self, flow: f64, k: f64) -> GutterFlowResult {
+        match self {
+            GutterSection::Uniform(g) => g.result_for_flow(flow, k),
+            GutterSection::Composite(g) => g.result_for_flow(flow, k),
+            GutterSection::ParabolicCrown(g) => g.result_for_flow(flow, k),
+        }
+    }
+
+    /// Calculate complete flow result for a given spread
+    pub fn flow_result(&self, spread: f64, k: f64) -> GutterFlowResult {
+        match self {
+            GutterSection::Uniform(g) => g.flow_result(spread, k),
+            GutterSection::Composite(g) => g.flow_result(spread, k),
+            GutterSection::ParabolicCrown(g) => g.flow_result(spread, k),
+        }
+    }
+
+    /// Calculate the spread at which the depth at the curb reaches `depth`
+    ///
+    /// Used where the depth is set by ponding rather than flow, as at sag
+    /// inlets.
+    pub fn spread_for_depth(&self, depth: f64, k: f64) -> f64 {
+        if depth <= 0.0 {
+            return 0.0;
+        }
+
+        let depth_at = |spread: f64| self.flow_result(spread, k).depth_at_curb;
+        let mut t_low = 0.0;
+        let mut t_high = 1.0;
+        while depth_at(t_high) < depth && t_high < 1.0e6 {
+            t_low = t_high;
+            t_high *= 2.0;
+        }
+
+        for _ in 0..60 {
+            let t_mid = (t_low + t_high) / 2.0;
+            if depth_at(t_mid) < depth {
+                t_low = t_mid;
+            } else {
+                t_high = t_mid;
+            }
+        }
+
+        (t_low + t_high) / 2.0
+    }
+}
+
 /// Unit constants for gutter equations
 pub const GUTTER_K_US: f64 = 0.56;  // US customary units
 pub const GUTTER_K_SI: f64 = 0.376; // SI metric units
@@ -490,7 +739,7 @@ mod tests {
             0.02,   // 2% roadway slope
             0.01,   // 1% longitudinal slope
             2.0,    // 2 ft gutter width
-            2.0,    // 2 inch local depression
+            2.0 / 12.0, // 2 inch local depression
         );
 
         let result = gutter.flow_result(10.0, GUTTER_K_US);
@@ -532,7 +781,7 @@ mod tests {
             0.02,
             0.01,
             2.0,
-            2.0,
+            2.0 / 12.0,
         );
 
         // Calculate spread for given flow
@@ -544,4 +793,65 @@ mod tests {
         assert!((check - target_flow).abs() < 0.1,
             "Expected flow {}, got {}", target_flow, check);
     }
+
+    #[test]
+    fn test_composite_gutter_example_5_2() {
+        // HEC-22 Example 5.2: W = 2 ft, a = 2 in, Sx = 0.02, SL = 0.01
+        let gutter = CompositeGutter::new(0.016, 0.02, 0.02, 0.01, 2.0, 2.0 / 12.0);
+
+        // A) T = 8.2 ft gives Eo = 0.7 and Q = 2.3 cfs
+        let result = gutter.flow_result(8.2, GUTTER_K_US);
+        let eo = result.frontal_flow.unwrap() / result.flow;
+        assert!((eo - 0.71).abs() < 0.01, "Eo = {}", eo);
+        assert!((result.flow - 2.3).abs() < 0.1, "Q = {}", result.flow);
+
+        // B) Q = 4.2 cfs gives T = 11.1 ft
+        let result = gutter.result_for_flow(4.2, GUTTER_K_US);
+        assert!((result.spread - 11.1).abs() < 0.1, "T = {}", result.spread);
+    }
+
+    #[test]
+    fn test_gutter_cross_sections() {
+        // Spread follows from the depth at the curb for each section
+        let uniform = UniformGutter::new(0.016, 0.02, 0.01, None);
+        assert!((uniform.top_width(0.16) - 8.0).abs() < 1e-9);
+        assert!((uniform.area(0.16) - 0.64).abs() < 1e-9);
+
+        let composite = CompositeGutter::new(0.016, 0.02, 0.02, 0.01, 2.0, 2.0 / 12.0);
+        let section = composite.cross_section();
+        let result = composite.flow_result(8.2, GUTTER_K_US);
+        assert!((section.top_width(result.depth_at_curb) - 8.2).abs() < 1e-9);
+        assert!((section.area(result.depth_at_curb) - result.area).abs() < 1e-12);
+
+        // Area and width are continuous at the edge of the gutter
+        let edge = 2.0 * section.depressed_slope;
+        assert!((section.top_width(edge + 1e-9) - 2.0).abs() < 1e-6);
+        assert!((section.area(edge + 1e-9) - section.area(edge)).abs() < 1e-6);
+
+        let crown = ParabolicCrown::new(0.016, 0.10, 12.0, 0.01);
+        let result = crown.flow_result(8.0, GUTTER_K_US);
+        assert!((crown.top_width(result.depth_at_curb) - 8.0).abs() < 1e-9);
+        assert!(crown.wetted_perimeter(result.depth_at_curb) > 8.0);
+    }
+
+    #[test]
+    fn test_gutter_section_dispatch() {
+        let uniform = UniformGutter::new(0.016, 0.02, 0.01, None);
+        let section = GutterSection::Uniform(uniform.clone());
+
+        assert_eq!(section.section_type(), GutterSectionType::UniformSlope);
+        assert_eq!(section.result_for_flow(1.8, GUTTER_K_US), uniform.result_for_flow(1.8, GUTTER_K_US));
+
+        let composite = GutterSection::Composite(CompositeGutter::new(0.016, 0.02, 0.02, 0.01, 2.0, 2.0 / 12.0));
+        assert_eq!(composite.section_type(), GutterSectionType::Composite);
+
+        // The depressed gutter carries the same flow at a narrower spread
+        let spread = composite.result_for_flow(1.8, GUTTER_K_US).spread;
+        assert!(spread < uniform.spread_for_flow(1.8, GUTTER_K_US));
+
+        // S_e = 0.02 + (2/12 / 2)(0.71) at the Example 5.2 spread
+        assert_eq!(section.equivalent_cross_slope(8.2, GUTTER_K_US), 0.02);
+        let se = composite.equivalent_cross_slope(8.2, GUTTER_K_US);
+        assert!((se - 0.079).abs() < 0.001, "Se = {}", se);
+    }
 }
@@ -0,0 +1,732 @@
+//! Gradually varied flow water surface profiles
+//!
+//! Between structures the water surface in a partly full conduit follows a
+//! gradually varied flow (GVF) profile rather than a straight line: backwater
+//! from a submerged outlet (M1, S1), drawdown toward a free outfall (M2) or
+//! supercritical flow leaving a steep inlet (S2, S3, M3).
+//!
+//! Profiles are computed with the direct-step method. Depth is stepped toward
+//! the depth the profile approaches, and the distance for each step follows
+//! from the energy equation
+//!
+//! ```text
+//! Δx = (E₂ - E₁) / (S₀ - S̄f),    E = y + V²/2g
+//! ```
+//!
+//! where S̄f is the mean Manning friction slope over the step. Subcritical
+//! profiles are marched upstream from a downstream control and supercritical
+//! profiles downstream from an upstream control. When both exist a hydraulic
+//! jump forms where the specific forces of the two branches match.
+//!
+//! Closed conduits pressurize once the depth reaches the crown; the pressure
+//! head then changes linearly with the full-flow friction slope. As in the HGL
+//! solver, conveyance is capped at its full-pipe value.
+//!
+//! Reaches may have any [`CrossSection`], including natural channels whose
+//! conveyance is summed over roughness subsections.
+//!
+//! ## References
+//!
+//! FHWA HEC-22 (4th Edition, 2024), Chapter 9: Storm Drain Conduits;
+//! Chow, V.T. (1959), *Open-Channel Hydraulics*, Chapter 10
+
+use crate::hydraulics::ManningsEquation;
+use crate::section::CrossSection;
+use serde::{Deserialize, Serialize};
+
+/// Fraction of the remaining depth change taken in each step
+const STEP_FRACTION: f64 = 0.1;
+
+/// Growth of depth per step for profiles with no limiting depth
+const STEP_GROWTH: f64 = 0.05;
+
+/// Relative depth at which a profile is taken to have reached its limit
+const DEPTH_TOLERANCE: f64 = 1e-4;
+
+/// Most steps in a single profile branch
+const MAX_STEPS: usize = 500;
+
+/// A uniform reach of a conduit
+#[derive(Debug)]
+pub struct Reach {
+    /// Cross section
+    pub section: Box<dyn CrossSection>,
+    /// Length (ft or m)
+    pub length: f64,
+    /// Bed slope, positive downhill (ft/ft or m/m)
+    pub slope: f64,
+    /// Manning's roughness coefficient; natural sections carry their own
+    pub manning_n: f64,
+    /// Invert at the downstream end (ft or m)
+    pub downstream_invert: f64,
+}
+
+/// Classification of a gradually varied flow profile
+///
+/// The letter gives the bed slope (Mild, Steep, Critical, Horizontal,
+/// Adverse) and the number the zone the depth lies in: above both normal and
+/// critical depth (1), between them (2), or below both (3).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ProfileType {
+    M1,
+    M2,
+    M3,
+    S1,
+    S2,
+    S3,
+    C1,
+    C3,
+    H2,
+    H3,
+    A2,
+    A3,
+}
+
+/// One computed point on a water surface profile
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ProfilePoint {
+    /// Distance from the upstream end (ft or m)
+    pub station: f64,
+
+    /// Flow depth, or pressure head above the invert in a full pipe (ft or m)
+    pub depth: f64,
+
+    /// Water surface (HGL) elevation (ft or m)
+    #[serde(rename = "waterSurface")]
+    pub water_surface: f64,
+
+    /// Energy grade line elevation (ft or m)
+    pub energy: f64,
+}
+
+/// Water surface profile along a reach
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WaterSurfaceProfile {
+    /// Profile curves from upstream to downstream; empty for uniform flow
+    #[serde(rename = "profileTypes")]
+    pub profile_types: Vec<ProfileType>,
+
+    /// Points ordered from the upstream end
+    pub points: Vec<ProfilePoint>,
+
+    /// Distance from the upstream end to a hydraulic jump (ft or m)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "jumpLocation")]
+    pub jump_location: Option<f64>,
+
+    /// Friction loss over the reach (ft or m)
+    #[serde(rename = "frictionLoss")]
+    pub friction_loss: f64,
+}
+
+impl WaterSurfaceProfile {
+    /// Point at the upstream end
+    pub fn upstream(&self) -> &ProfilePoint {
+        &self.points[0]
+    }
+
+    /// Point at the downstream end
+    pub fn downstream(&self) -> &ProfilePoint {
+        &self.points[self.points.len() - 1]
+    }
+}
+
+/// Depth the direct step marches toward
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Approach {
+    /// Normal depth, reached asymptotically and held to the end of the reach
+    Normal(f64),
+    /// Critical depth, where the branch ends
+    Critical(f64),
+    /// No limit; depth keeps rising
+    Rising,
+}
+
+/// One step of a profile branch, measured from where the march began
+#[derive(Debug, Clone, Copy)]
+struct Step {
+    distance: f64,
+    depth: f64,
+    friction: f64,
+}
+
+/// Depths along a whole reach, from the upstream end
+struct Joined {
+    points: Vec<(f64, f64)>,
+    types: Vec<ProfileType>,
+    jump: Option<f64>,
+    friction: f64,
+}
+
+/// A profile marched from one control
+struct Branch {
+    steps: Vec<Step>,
+    profile_type: Option<ProfileType>,
+    /// Whether the branch covers the whole reach
+    complete: bool,
+}
+
+/// Direct-step water surface profile calculator
+pub struct DirectStep {
+    /// Manning's equation for conveyance
+    pub mannings: ManningsEquation,
+    /// Gravitational constant (32.17 ft/s² or 9.81 m/s²)
+    pub gravity: f64,
+}
+
+impl DirectStep {
+    /// Create for US customary units
+    pub fn us_customary() -> Self {
+        Self {
+            mannings: ManningsEquation::us_customary(),
+            gravity: 32.17,
+        }
+    }
+
+    /// Create for SI metric units
+    pub fn si_metric() -> Self {
+        Self {
+            mannings: ManningsEquation::si_metric(),
+            gravity: 9.81,
+        }
+    }
+
+    /// Compute the water surface profile along a reach
+    ///
+    /// # Arguments
+    /// * `reach` - The reach
+    /// * `flow` - Flow rate (cfs or cms)
+    /// * `upstream_depth` - Supercritical control depth at the upstream end,
+    ///   if flow enters supercritical
+    /// * `downstream_depth` - Depth, or pressure head, at the downstream end
+    ///   (ft or m); depths below critical leave the outlet under upstream or
+    ///   critical control
+    ///
+    /// # Returns
+    /// The profile, with any hydraulic jump between the two controls
+    pub fn profile(
+        &self,
+        reach: &Reach,
+        flow: f64,
+        upstream_depth: Option<f64>,
+        downstream_depth: f64,
+    ) -> Result<WaterSurfaceProfile, String> {
+        if flow <= 0.0 {
+            return Err("Profile flow must be positive".to_string());
+        }
+        if reach.length <= 0.0 {
+            return Err("Profile reach must have positive length".to_string());
+        }
+
+        let critical = self.critical_depth(reach, flow);
+        let supercritical = upstream_depth
+            .filter(|&y| y < critical)
+            .map(|y| self.march(reach, flow, y, false));
+
+        // Without an upstream control the outlet cannot fall below critical
+        let subcritical = match supercritical {
+            Some(_) if downstream_depth <= critical => None,
+            Some(_) => Some(self.march(reach, flow, downstream_depth, true)),
+            None => Some(self.march(reach, flow, downstream_depth.max(critical), true)),
+        };
+
+        let joined = match (supercritical, subcritical) {
+            (Some(sup), None) => self.single(reach, sup, critical, false),
+            (None, Some(sub)) => self.single(reach, sub, critical, true),
+            (Some(sup), Some(sub)) => self.join(reach, flow, sup, sub, critical),
+            (None, None) => unreachable!("a profile always has one control"),
+        };
+
+        let points = joined
+            .points
+            .into_iter()
+            .map(|(station, depth)| self.point(reach, flow, station, depth))
+            .collect();
+
+        Ok(WaterSurfaceProfile {
+            profile_types: joined.types,
+            points,
+            jump_location: joined.jump,
+            friction_loss: joined.friction,
+        })
+    }
+
+    /// Normal depth, if the bed slopes downhill; a closed conduit is full
+    /// once the flow exceeds its capacity
+    pub fn normal_depth(&self, reach: &Reach, flow: f64) -> Option<f64> {
+        reach
+            .section
+            .normal_depth(flow, reach.slope, reach.manning_n, self.mannings.k)
+    }
+
+    /// Full-flow capacity of a closed conduit on a downhill grade
+    pub fn full_capacity(&self, reach: &Reach) -> Option<f64> {
+        let conveyance = self.full_conveyance(reach)?;
+        (reach.slope > 0.0).then(|| conveyance * reach.slope.sqrt())
+    }
+
+    /// Manning conveyance of a closed conduit running full
+    pub fn full_conveyance(&self, reach: &Reach) -> Option<f64> {
+        reach.section.crown().map(|crown| self.conveyance(reach, crown))
+    }
+
+    /// Critical depth, at most the crown of a closed conduit
+    pub fn critical_depth(&self, reach: &Reach, flow: f64) -> f64 {
+        reach.section.critical_depth(flow, self.gravity)
+    }
+
+    /// Specific force Q²/(gA) + Aȳ at a depth
+    pub fn specific_force(&self, reach: &Reach, flow: f64, depth: f64) -> f64 {
+        reach.section.specific_force(flow, depth, self.gravity)
+    }
+
+    /// Manning conveyance, capped at the full-pipe value for closed conduits
+    fn conveyance(&self, reach: &Reach, depth: f64) -> f64 {
+        reach.section.conveyance(depth, reach.manning_n, self.mannings.k)
+    }
+
+    /// Friction slope at a depth
+    fn friction_slope(&self, reach: &Reach, flow: f64, depth: f64) -> f64 {
+        (flow / self.conveyance(reach, depth)).powi(2)
+    }
+
+    /// Specific energy at a depth, counting pressure head in a full pipe
+    fn specific_energy(&self, reach: &Reach, flow: f64, depth: f64) -> f64 {
+        let velocity = flow / reach.section.area(depth);
+        depth + velocity * velocity / (2.0 * self.gravity)
+    }
+
+    /// Classify the profile starting at `depth`
+    fn classify(&self, reach: &Reach, flow: f64, depth: f64) -> (Option<ProfileType>, Approach) {
+        let critical = self.critical_depth(reach, flow);
+        let normal = self.normal_depth(reach, flow);
+        let tolerance = DEPTH_TOLERANCE * critical.max(normal.unwrap_or(0.0));
+
+        let Some(normal) = normal else {
+            let (zone_2, zone_3) = if reach.slope < 0.0 {
+                (ProfileType::A2, ProfileType::A3)
+            } else {
+                (ProfileType::H2, ProfileType::H3)
+            };
+            return if depth >= critical {
+                (Some(zone_2), Approach::Rising)
+            } else {
+                (Some(zone_3), Approach::Critical(critical))
+            };
+        };
+
+        if (depth - normal).abs() <= tolerance {
+            return (None, Approach::Normal(normal));
+        }
+
+        if (normal - critical).abs() <= tolerance {
+            return if depth > critical {
+                (Some(ProfileType::C1), Approach::Critical(critical))
+            } else {
+                (Some(ProfileType::C3), Approach::Critical(critical))
+            };
+        }
+
+        if normal > critical {
+            if depth > normal {
+                (Some(ProfileType::M1), Approach::Normal(normal))
+            } else if depth >= critical {
+                (Some(ProfileType::M2), Approach::Normal(normal))
+            } else {
+                (Some(ProfileType::M3), Approach::Critical(critical))
+            }
+        } else if depth >= critical {
+            (Some(ProfileType::S1), Approach::Critical(critical))
+        } else if depth > normal {
+            (Some(ProfileType::S2), Approach::Normal(normal))
+        } else {
+            (Some(ProfileType::S3), Approach::Normal(normal))
+        }
+    }
+
+    /// March a profile branch from a control depth
+    ///
+    /// Subcritical branches march upstream from the downstream end, and
+    /// supercritical branches downstream from the upstream end. Distances are
+    /// measured from the control.
+    fn march(&self, reach: &Reach, flow: f64, start: f64, upstream: bool) -> Branch {
+        let (profile_type, approach) = self.classify(reach, flow, start);
+        let crown = reach.section.crown();
+        let tolerance = DEPTH_TOLERANCE * start.max(self.critical_depth(reach, flow));
+
+        let mut steps = vec![Step { distance: 0.0, depth: start, friction: 0.0 }];
+        let mut depth = start;
+        let mut distance = 0.0;
+        let mut friction = 0.0;
+        let mut complete = false;
+
+        for _ in 0..MAX_STEPS {
+            let remaining = reach.length - distance;
+
+            // Pressure flow: the head changes linearly with the full-pipe
+            // friction slope. A rising profile that reaches the crown runs
+            // full from there on.
+            let full = |c: f64| depth > c || (depth >= c && approach == Approach::Rising);
+            if let Some(crown) = crown.filter(|&c| upstream && full(c)) {
+                let sf = self.friction_slope(reach, flow, crown);
+                let fall = reach.slope - sf;
+                if fall <= 0.0 || (depth - crown) / fall >= remaining {
+                    friction += sf * remaining;
+                    steps.push(Step { distance: reach.length, depth: depth - fall * remaining, friction });
+                    complete = true;
+                    break;
+                }
+                let dx = (depth - crown) / fall;
+                distance += dx;
+                friction += sf * dx;
+                depth = crown;
+                steps.push(Step { distance, depth, friction });
+                continue;
+            }
+
+            let next = match approach {
+                Approach::Normal(limit) | Approach::Critical(limit) => {
+                    if (depth - limit).abs() <= tolerance {
+                        if let Approach::Normal(normal) = approach {
+                            friction += self.friction_slope(reach, flow, normal) * remaining;
+                            steps.push(Step { distance: reach.length, depth: normal, friction });
+                            complete = true;
+                        }
+                        break;
+                    }
+                    depth + (limit - depth) * STEP_FRACTION
+                }
+                Approach::Rising => {
+                    let next = depth * (1.0 + STEP_GROWTH);
+                    crown.map_or(next, |c| next.min(c))
+                }
+            };
+
+            let sf = 0.5
+                * (self.friction_slope(reach, flow, depth) + self.friction_slope(reach, flow, next));
+            let energy_change = self.specific_energy(reach, flow, next)
+                - self.specific_energy(reach, flow, depth);
+            let dx = if upstream {
+                -energy_change / (reach.slope - sf)
+            } else {
+                energy_change / (reach.slope - sf)
+            };
+
+            if !dx.is_finite() || dx <= 0.0 {
+                break;
+            }
+            if dx >= remaining {
+                let fraction = remaining / dx;
+                friction += sf * remaining;
+                steps.push(Step {
+                    distance: reach.length,
+                    depth: depth + (next - depth) * fraction,
+                    friction,
+                });
+                complete = true;
+                break;
+            }
+
+            distance += dx;
+            friction += sf * dx;
+            depth = next;
+            steps.push(Step { distance, depth, friction });
+        }
+
+        Branch { steps, profile_type, complete }
+    }
+
+    /// Profile from a single branch; a branch that stopped at critical depth
+    /// is held there to the far end of the reach
+    fn single(&self, reach: &Reach, branch: Branch, critical: f64, upstream: bool) -> Joined {
+        let mut points: Vec<(f64, f64)> = branch.steps.iter().map(|s| (s.distance, s.depth)).collect();
+        if points.last().is_some_and(|&(distance, _)| distance < reach.length) {
+            points.push((reach.length, critical));
+        }
+        if upstream {
+            points = points.into_iter().rev().map(|(d, y)| (reach.length - d, y)).collect();
+        }
+
+        Joined {
+            points,
+            types: branch.profile_type.into_iter().collect(),
+            jump: None,
+            friction: branch.steps.last().map_or(0.0, |s| s.friction),
+        }
+    }
+
+    /// Join a supercritical branch from upstream and a subcritical branch from
+    /// downstream at the hydraulic jump between them
+    fn join(&self, reach: &Reach, flow: f64, sup: Branch, sub: Branch, critical: f64) -> Joined {
+        let length = reach.length;
+
+        // Walk the subcritical branch upstream until its specific force no
+        // longer exceeds that of the supercritical flow beside it
+        let excess = |step: &Step| {
+            let (sup_depth, _) = interpolate(&sup.steps, length - step.distance, critical);
+            self.specific_force(reach, flow, step.depth) - self.specific_force(reach, flow, sup_depth)
+        };
+
+        let mut crossing = None;
+        let mut previous: Option<(&Step, f64)> = None;
+        for step in &sub.steps {
+            let f = excess(step);
+            if f <= 0.0 {
+                crossing = Some(previous.map(|(prev, f_prev)| {
+                    let t = f_prev / (f_prev - f);
+                    prev.distance + (step.distance - prev.distance) * t
+                }));
+                break;
+            }
+            previous = Some((step, f));
+        }
+
+        let jump_distance = match crossing {
+            // The tailwater cannot hold a jump in the reach
+            Some(None) => return self.single(reach, sup, critical, false),
+            Some(Some(distance)) => distance,
+            // The jump is drowned out of the upstream end
+            None if sub.complete => return self.single(reach, sub, critical, true),
+            // The subcritical branch ran out at critical depth
+            None => sub.steps.last().map_or(0.0, |s| s.distance),
+        };
+
+        let jump_station = length - jump_distance;
+        let (sup_depth, sup_friction) = interpolate(&sup.steps, jump_station, critical);
+        let (sub_depth, sub_friction) = interpolate(&sub.steps, jump_distance, critical);
+
+        let mut points: Vec<(f64, f64)> = sup
+            .steps
+            .iter()
+            .filter(|s| s.distance < jump_station)
+            .map(|s| (s.distance, s.depth))
+            .collect();
+        points.push((jump_station, sup_depth));
+        points.push((jump_station, sub_depth));
+        points.extend(
+            sub.steps
+                .iter()
+                .rev()
+                .filter(|s| s.distance < jump_distance)
+                .map(|s| (length - s.distance, s.depth)),
+        );
+
+        Joined {
+            points,
+            types: sup.profile_type.into_iter().chain(sub.profile_type).collect(),
+            jump: Some(jump_station),
+            friction: sup_friction + sub_friction,
+        }
+    }
+
+    /// Profile point at a station from the upstream end
+    fn point(&self, reach: &Reach, flow: f64, station: f64, depth: f64) -> ProfilePoint {
+        let invert = reach.downstream_invert + reach.slope * (reach.length - station);
+        ProfilePoint {
+            station,
+            depth,
+            water_surface: invert + depth,
+            energy: invert + self.specific_energy(reach, flow, depth),
+        }
+    }
+}
+
+/// Depth and friction along a branch at a distance from its control; past
+/// the end of the branch the flow is at critical depth
+fn interpolate(steps: &[Step], distance: f64, critical: f64) -> (f64, f64) {
+    let last = steps[steps.len() - 1];
+    if distance >= last.distance {
+        return if distance > last.distance { (critical, last.friction) } else { (last.depth, last.friction) };
+    }
+    for pair in steps.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        if distance <= b.distance {
+            let t = if b.distance > a.distance {
+                (distance - a.distance) / (b.distance - a.distance)
+            } else {
+                0.0
+            };
+            return (a.depth + (b.depth - a.depth) * t, a.friction + (b.friction - a.friction) * t);
+        }
+    }
+    (last.depth, last.friction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::section::{Circular, Rectangular, Trapezoidal};
+    use approx::assert_relative_eq;
+
+    /// Trapezoidal channel of Chow (1959) Example 10.1, carrying 400 cfs
+    fn chow_channel() -> Reach {
+        Reach {
+            section: Box::new(Trapezoidal { bottom_width: 20.0, side_slope: 2.0 }),
+            length: 5000.0,
+            slope: 0.0016,
+            manning_n: 0.025,
+            downstream_invert: 100.0,
+        }
+    }
+
+    fn depths(profile: &WaterSurfaceProfile) -> Vec<f64> {
+        profile.points.iter().map(|p| p.depth).collect()
+    }
+
+    #[test]
+    fn test_normal_and_critical_depth() {
+        let gvf = DirectStep::us_customary();
+        let reach = chow_channel();
+        assert_relative_eq!(gvf.normal_depth(&reach, 400.0).unwrap(), 3.36, epsilon = 0.01);
+        assert_relative_eq!(gvf.critical_depth(&reach, 400.0), 2.15, epsilon = 0.01);
+        assert_eq!(gvf.normal_depth(&Reach { slope: 0.0, ..reach }, 400.0), None);
+    }
+
+    #[test]
+    fn test_box_normal_depth() {
+        let gvf = DirectStep::us_customary();
+        let reach = Reach {
+            section: Box::new(Rectangular { width: 4.0, height: Some(3.0) }),
+            length: 200.0,
+            slope: 0.0025,
+            manning_n: 0.013,
+            downstream_invert: 100.0,
+        };
+
+        // Q = (1.486/n) A R^(2/3) √S at a 1.5 ft depth
+        let flow = 1.486 / 0.013 * 6.0 * (6.0_f64 / 7.0).powf(2.0 / 3.0) * 0.0025_f64.sqrt();
+        assert_relative_eq!(gvf.normal_depth(&reach, flow).unwrap(), 1.5, epsilon = 1e-3);
+
+        let full = gvf.full_capacity(&reach).unwrap();
+        let expected = 1.486 / 0.013 * 12.0 * (12.0_f64 / 14.0).powf(2.0 / 3.0) * 0.0025_f64.sqrt();
+        assert_relative_eq!(full, expected, epsilon = 1e-6);
+        assert_eq!(gvf.normal_depth(&reach, full * 1.2), Some(3.0));
+
+        // Rectangular critical depth (q²/g)^(1/3)
+        let q = flow / 4.0;
+        assert_relative_eq!(gvf.critical_depth(&reach, flow), (q * q / 32.2).cbrt(), epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_m1_backwater() {
+        let gvf = DirectStep::us_customary();
+        let reach = chow_channel();
+        let normal = gvf.normal_depth(&reach, 400.0).unwrap();
+        let profile = gvf.profile(&reach, 400.0, None, 5.0).unwrap();
+
+        assert_eq!(profile.profile_types, vec![ProfileType::M1]);
+        assert_eq!(profile.jump_location, None);
+        assert_relative_eq!(profile.downstream().depth, 5.0);
+        assert_relative_eq!(profile.downstream().station, 5000.0);
+        assert_relative_eq!(profile.upstream().station, 0.0);
+
+        // Depth falls toward normal depth going upstream
+        let depths = depths(&profile);
+        assert!(depths.windows(2).all(|w| w[0] <= w[1]));
+        assert_relative_eq!(profile.upstream().depth, normal, epsilon = 1e-3);
+
+        // Water surface and energy line both rise upstream
+        assert!(profile.upstream().water_surface > profile.downstream().water_surface);
+        assert!(profile.upstream().energy > profile.downstream().energy);
+        assert!(profile.friction_loss > 0.0);
+    }
+
+    #[test]
+    fn test_m2_drawdown_to_free_overfall() {
+        let gvf = DirectStep::us_customary();
+        let reach = chow_channel();
+        let critical = gvf.critical_depth(&reach, 400.0);
+        let normal = gvf.normal_depth(&reach, 400.0).unwrap();
+
+        // The outlet cannot fall below critical depth
+        let profile = gvf.profile(&reach, 400.0, None, 0.5).unwrap();
+        assert_eq!(profile.profile_types, vec![ProfileType::M2]);
+        assert_relative_eq!(profile.downstream().depth, critical);
+        assert_relative_eq!(profile.upstream().depth, normal, epsilon = 1e-3);
+
+        // Uniform flow needs no curve
+        let uniform = gvf.profile(&reach, 400.0, None, normal).unwrap();
+        assert!(uniform.profile_types.is_empty());
+        assert!(depths(&uniform).iter().all(|&y| (y - normal).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_steep_channel_jump() {
+        let gvf = DirectStep::us_customary();
+        let reach = Reach { slope: 0.02, length: 1000.0, ..chow_channel() };
+        let normal = gvf.normal_depth(&reach, 400.0).unwrap();
+        let critical = gvf.critical_depth(&reach, 400.0);
+        assert!(normal < critical);
+
+        // Low tailwater: supercritical throughout
+        let profile = gvf.profile(&reach, 400.0, Some(critical * 0.99), 1.0).unwrap();
+        assert_eq!(profile.profile_types, vec![ProfileType::S2]);
+        assert_eq!(profile.jump_location, None);
+        assert!(profile.downstream().depth < critical);
+
+        // A high tailwater forces a jump with equal specific force either side
+        let profile = gvf.profile(&reach, 400.0, Some(normal), 12.0).unwrap();
+        assert_eq!(profile.profile_types, vec![ProfileType::S1]);
+        let jump = profile.jump_location.unwrap();
+        assert!(jump > 0.0 && jump < 1000.0);
+        let at_jump: Vec<&ProfilePoint> = profile.points.iter().filter(|p| p.station == jump).collect();
+        assert_eq!(at_jump.len(), 2);
+        assert_relative_eq!(
+            gvf.specific_force(&reach, 400.0, at_jump[0].depth),
+            gvf.specific_force(&reach, 400.0, at_jump[1].depth),
+            max_relative = 0.01
+        );
+
+        // Deep enough tailwater drowns the jump out of the reach
+        let profile = gvf.profile(&reach, 400.0, Some(normal), 30.0).unwrap();
+        assert_eq!(profile.jump_location, None);
+        assert!(profile.upstream().depth > critical);
+    }
+
+    #[test]
+    fn test_surcharged_pipe_profile() {
+        let gvf = DirectStep::us_customary();
+        let reach = Reach {
+            section: Box::new(Circular { diameter: 2.0 }),
+            length: 400.0,
+            slope: 0.002,
+            manning_n: 0.013,
+            downstream_invert: 100.0,
+        };
+
+        // Pressure head falls linearly upstream until the pipe unsubmerges
+        let profile = gvf.profile(&reach, 6.75, None, 2.3).unwrap();
+        let friction_slope = (6.75 * 0.013 / (0.4632 * 2.0_f64.powf(8.0 / 3.0))).powi(2);
+        let crown_station = 400.0 - 0.3 / (0.002 - friction_slope);
+        let crown = profile.points.iter().find(|p| p.depth == 2.0).unwrap();
+        assert_relative_eq!(crown.station, crown_station, epsilon = 0.5);
+        assert_eq!(profile.profile_types, vec![ProfileType::M1]);
+        assert!(profile.upstream().depth < 2.0);
+    }
+
+    #[test]
+    fn test_rising_profile_surcharges_pipe() {
+        let gvf = DirectStep::us_customary();
+        let reach = Reach {
+            section: Box::new(Circular { diameter: 1.5 }),
+            length: 600.0,
+            slope: 0.0,
+            manning_n: 0.013,
+            downstream_invert: 100.0,
+        };
+
+        // The H2 profile rises to the crown partway up the reach, then runs full
+        let profile = gvf.profile(&reach, 5.0, None, 1.2).unwrap();
+        assert_eq!(profile.profile_types, vec![ProfileType::H2]);
+        let crown = profile.points.iter().find(|p| p.depth == 1.5).unwrap();
+        assert!(crown.station > 0.0 && crown.station < 600.0);
+
+        let friction_slope = (5.0 / gvf.full_conveyance(&reach).unwrap()).powi(2);
+        assert_relative_eq!(
+            profile.upstream().depth,
+            1.5 + friction_slope * crown.station,
+            epsilon = 1e-6
+        );
+        assert!(profile.points.iter().all(|p| p.depth >= 1.2));
+    }
+}
@@ -15,7 +15,7 @@
 //!
 //! FHWA HEC-22 (4th Edition, 2024), Chapter 9: Storm Drain Conduits
 
-use std::f64::consts::PI;
+use crate::section::{Circular, CrossSection};
 
 /// Gravitational acceleration constant
 pub const GRAVITY_US: f64 = 32.17; // ft/s²
@@ -107,11 +107,7 @@ impl ManningsEquation {
         slope: f64,
         manning_n: f64,
     ) -> f64 {
-        let area = PI * diameter.powi(2) / 4.0;
-        let perimeter = PI * diameter;
-        let hydraulic_radius = area / perimeter; // For circle: D/4
-
-        (self.k / manning_n) * area * hydraulic_radius.powf(2.0 / 3.0) * slope.sqrt()
+        Circular { diameter }.conveyance(diameter, manning_n, self.k) * slope.sqrt()
     }
 
     /// Calculate velocity in full pipe
@@ -138,8 +134,7 @@ impl ManningsEquation {
     /// # Returns
     /// Velocity (ft/s or m/s)
     pub fn full_pipe_velocity(&self, diameter: f64, flow: f64) -> f64 {
-        let area = PI * diameter.powi(2) / 4.0;
-        flow / area
+        flow / Circular { diameter }.area(diameter)
     }
 
     /// Calculate partial flow in circular pipe given depth
@@ -160,7 +155,7 @@ impl ManningsEquation {
         manning_n: f64,
         gravity: f64,
     ) -> PipeFlowResult {
-        let radius = diameter / 2.0;
+        let section = Circular { diameter };
         let depth_ratio = depth / diameter;
 
         // Handle edge cases
@@ -171,16 +166,8 @@ impl ManningsEquation {
             return self.full_pipe_flow_result(diameter, slope, manning_n, gravity);
         }
 
-        // Central angle (radians)
-        let theta = 2.0 * ((radius - depth) / radius).acos();
-
-        // Flow area
-        let area = (radius.powi(2) / 2.0) * (theta - theta.sin());
-
-        // Wetted perimeter
-        let perimeter = radius * theta;
-
-        // Hydraulic radius
+        let area = section.area(depth);
+        let perimeter = section.wetted_perimeter(depth);
         let hydraulic_radius = area / perimeter;
 
         // Flow rate using Manning's equation
@@ -228,9 +215,10 @@ impl ManningsEquation {
         manning_n: f64,
         gravity: f64,
     ) -> PipeFlowResult {
-        let area = PI * diameter.powi(2) / 4.0;
-        let perimeter = PI * diameter;
-        let hydraulic_radius = diameter / 4.0;
+        let section = Circular { diameter };
+        let area = section.area(diameter);
+        let perimeter = section.wetted_perimeter(diameter);
+        let hydraulic_radius = area / perimeter;
         let flow = self.full_pipe_capacity(diameter, slope, manning_n);
         let velocity = flow / area;
         let velocity_head = velocity.powi(2) / (2.0 * gravity);
@@ -250,15 +238,15 @@ impl ManningsEquation {
 
     /// Calculate normal depth for given flow in circular pipe
     ///
-    /// Iteratively solves Manning's equation for depth that produces the given flow.
-    /// Uses bisection method.
+    /// Solves Manning's equation for the depth that carries the given flow.
+    /// See [`CrossSection::normal_depth`] for other shapes.
     ///
     /// # Arguments
     /// * `flow` - Target flow rate (cfs or cms)
     /// * `diameter` - Pipe diameter (ft or m)
     /// * `slope` - Pipe slope (ft/ft or m/m)
     /// * `manning_n` - Manning's roughness coefficient
-    /// * `gravity` - Gravitational constant
+    /// * `_gravity` - Gravitational constant (unused by Manning's equation)
     ///
     /// # Returns
     /// Normal depth (ft or m), or None if no solution exists
@@ -268,41 +256,10 @@ impl ManningsEquation {
         diameter: f64,
         slope: f64,
         manning_n: f64,
-        gravity: f64,
+        _gravity: f64,
     ) -> Option<f64> {
-        // Check if flow exceeds full pipe capacity
-        let q_full = self.full_pipe_capacity(diameter, slope, manning_n);
-        if flow > q_full {
-            return Some(diameter); // Pressurized flow
-        }
-
-        // Bisection method
-        let mut y_low = 0.0001 * diameter;
-        let mut y_high = diameter;
-        let tolerance = 0.0001;
-        let max_iterations = 50;
-
-        for _ in 0..max_iterations {
-            let y_mid = (y_low + y_high) / 2.0;
-            let result = self.partial_pipe_flow(diameter, y_mid, slope, manning_n, gravity);
-            let q_mid = result.flow;
-
-            if (q_mid - flow).abs() < tolerance {
-                return Some(y_mid);
-            }
-
-            if q_mid < flow {
-                y_low = y_mid;
-            } else {
-                y_high = y_mid;
-            }
-
-            if (y_high - y_low) < tolerance {
-                return Some(y_mid);
-            }
-        }
-
-        Some((y_low + y_high) / 2.0)
+        // Pressurized once the flow exceeds full pipe capacity
+        Circular { diameter }.normal_depth(flow, slope, manning_n, self.k)
     }
 
     /// Calculate critical depth for circular pipe
@@ -323,37 +280,7 @@ impl ManningsEquation {
         diameter: f64,
         gravity: f64,
     ) -> Option<f64> {
-        // Iteratively solve for yc where Fr = 1
-        let radius = diameter / 2.0;
-        let mut y_low = 0.0001 * diameter;
-        let mut y_high = diameter;
-        let tolerance = 0.0001;
-        let max_iterations = 50;
-
-        for _ in 0..max_iterations {
-            let y_mid = (y_low + y_high) / 2.0;
-
-            // Calculate area and top width
-            let theta = 2.0 * ((radius - y_mid) / radius).acos();
-            let area = (radius.powi(2) / 2.0) * (theta - theta.sin());
-            let top_width = 2.0 * (radius.powi(2) - (radius - y_mid).powi(2)).sqrt();
-
-            // Critical flow condition: Q² = g * A³ / T
-            let lhs = flow.powi(2);
-            let rhs = gravity * area.powi(3) / top_width;
-
-            if (lhs - rhs).abs() < tolerance * lhs {
-                return Some(y_mid);
-            }
-
-            if lhs > rhs {
-                y_low = y_mid;
-            } else {
-                y_high = y_mid;
-            }
-        }
-
-        Some((y_low + y_high) / 2.0)
+        Some(Circular { diameter }.critical_depth(flow, gravity))
     }
 
     /// Calculate Froude number
@@ -1822,7 +1749,7 @@ mod tests {
         let fhwa = FhwaAccessHoleMethod::us_customary();
 
         let flow = 10.0; // cfs
-        let diameter = 2.0; // ft (24 inches)
+        let diameter: f64 = 2.0; // ft (24 inches)
         let area = std::f64::consts::PI * diameter.powi(2) / 4.0;
 
         let di = fhwa.discharge_intensity(flow, area, diameter);
@@ -1887,7 +1814,7 @@ mod tests {
         let fhwa = FhwaAccessHoleMethod::us_customary();
 
         // Simple test case: single straight-through inflow
-        let outflow_diameter = 2.0; // ft (24 inches)
+        let outflow_diameter: f64 = 2.0; // ft (24 inches)
         let outflow_area = std::f64::consts::PI * outflow_diameter.powi(2) / 4.0;
         let outflow_flow = 10.0; // cfs
         let outflow_velocity = outflow_flow / outflow_area;
@@ -0,0 +1,174 @@
+//! Flow hydrographs
+//!
+//! A hydrograph is a time series of flow rate at a point in the drainage system.
+//! Hydrographs are used as inflow boundary conditions for unsteady routing
+//! (see [`crate::routing`]) and as the output of runoff calculations.
+//!
+//! Times are measured in minutes from the start of the storm; flows are in
+//! cfs (US) or cms (SI).
+
+use serde::{Deserialize, Serialize};
+
+/// Single ordinate of a hydrograph
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct HydrographPoint {
+    /// Time from start of storm (minutes)
+    pub time: f64,
+
+    /// Flow rate (cfs or cms)
+    pub flow: f64,
+}
+
+/// Flow hydrograph (time series of flow rate)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Hydrograph {
+    /// Hydrograph ordinates, sorted by time
+    pub points: Vec<HydrographPoint>,
+}
+
+impl Hydrograph {
+    /// Create a hydrograph from a list of points
+    ///
+    /// Points are sorted by time.
+    pub fn new(mut points: Vec<HydrographPoint>) -> Self {
+        points.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap_or(std::cmp::Ordering::Equal));
+        Self { points }
+    }
+
+    /// Create a hydrograph from flows at a uniform time step
+    ///
+    /// # Arguments
+    /// * `time_step` - Interval between ordinates (minutes)
+    /// * `flows` - Flow ordinates starting at t = 0
+    pub fn from_ordinates(time_step: f64, flows: &[f64]) -> Self {
+        let points = flows
+            .iter()
+            .enumerate()
+            .map(|(i, &flow)| HydrographPoint {
+                time: i as f64 * time_step,
+                flow,
+            })
+            .collect();
+        Self { points }
+    }
+
+    /// Create a constant-flow hydrograph lasting `duration` minutes
+    pub fn constant(flow: f64, duration: f64) -> Self {
+        Self {
+            points: vec![
+                HydrographPoint { time: 0.0, flow },
+                HydrographPoint { time: duration, flow },
+            ],
+        }
+    }
+
+    /// Flow at time `t` (minutes), linearly interpolated
+    ///
+    /// Returns zero outside the range of the hydrograph.
+    pub fn flow_at(&self, t: f64) -> f64 {
+        let first = match self.points.first() {
+            Some(p) => p,
+            None => return 0.0,
+        };
+        let last = self.points[self.points.len() - 1];
+
+        if t < first.time || t > last.time {
+            return 0.0;
+        }
+
+        for pair in self.points.windows(2) {
+            let (p1, p2) = (pair[0], pair[1]);
+            if t >= p1.time && t <= p2.time {
+                let dt = p2.time - p1.time;
+                if dt <= 0.0 {
+                    return p2.flow;
+                }
+                return p1.flow + (p2.flow - p1.flow) * (t - p1.time) / dt;
+            }
+        }
+
+        last.flow
+    }
+
+    /// Peak ordinate of the hydrograph
+    pub fn peak(&self) -> Option<HydrographPoint> {
+        self.points
+            .iter()
+            .copied()
+            .fold(None, |best: Option<HydrographPoint>, p| match best {
+                Some(b) if b.flow >= p.flow => Some(b),
+                _ => Some(p),
+            })
+    }
+
+    /// Peak flow rate (cfs or cms), zero for an empty hydrograph
+    pub fn peak_flow(&self) -> f64 {
+        self.peak().map(|p| p.flow).unwrap_or(0.0)
+    }
+
+    /// Time of the last ordinate (minutes)
+    pub fn end_time(&self) -> f64 {
+        self.points.last().map(|p| p.time).unwrap_or(0.0)
+    }
+
+    /// Total runoff volume by trapezoidal integration (ft³ or m³)
+    pub fn volume(&self) -> f64 {
+        self.points
+            .windows(2)
+            .map(|pair| 0.5 * (pair[0].flow + pair[1].flow) * (pair[1].time - pair[0].time) * 60.0)
+            .sum()
+    }
+
+    /// Sum of two hydrographs, sampled at `time_step` minutes
+    pub fn combine(&self, other: &Hydrograph, time_step: f64) -> Hydrograph {
+        let end = self.end_time().max(other.end_time());
+        let steps = (end / time_step).ceil() as usize;
+        let flows: Vec<f64> = (0..=steps)
+            .map(|i| {
+                let t = i as f64 * time_step;
+                self.flow_at(t) + other.flow_at(t)
+            })
+            .collect();
+        Hydrograph::from_ordinates(time_step, &flows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn triangle() -> Hydrograph {
+        Hydrograph::new(vec![
+            HydrographPoint { time: 0.0, flow: 0.0 },
+            HydrographPoint { time: 10.0, flow: 6.0 },
+            HydrographPoint { time: 30.0, flow: 0.0 },
+        ])
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let hydrograph = triangle();
+        assert_relative_eq!(hydrograph.flow_at(5.0), 3.0);
+        assert_relative_eq!(hydrograph.flow_at(20.0), 3.0);
+        assert_eq!(hydrograph.flow_at(45.0), 0.0);
+    }
+
+    #[test]
+    fn test_peak_and_volume() {
+        let hydrograph = triangle();
+        let peak = hydrograph.peak().unwrap();
+        assert_eq!(peak.time, 10.0);
+        assert_eq!(peak.flow, 6.0);
+
+        // Triangle: 0.5 × 6 cfs × 30 min × 60 s/min
+        assert_relative_eq!(hydrograph.volume(), 5400.0);
+    }
+
+    #[test]
+    fn test_combine() {
+        let combined = triangle().combine(&Hydrograph::constant(1.0, 20.0), 5.0);
+        assert_relative_eq!(combined.flow_at(10.0), 7.0);
+        assert_relative_eq!(combined.flow_at(25.0), 1.5);
+    }
+}
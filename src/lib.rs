@@ -13,11 +13,20 @@
 //! - [`conduit`] - Conduit types (pipes, gutters, channels)
 //! - [`drainage`] - Drainage areas and subcatchments
 //! - [`rainfall`] - Rainfall events and IDF curves
+//! - [`hydrograph`] - Flow hydrographs (time series of flow)
+//! - [`runoff`] - Runoff hydrographs (modified rational, NRCS unit hydrograph)
 //! - [`analysis`] - Analysis results and violations
 //! - [`hydraulics`] - Hydraulic calculations (Manning's equation, HGL/EGL)
 //! - [`gutter`] - Gutter spread calculations (Chapter 5)
+//! - [`section`] - Cross-section geometry and depth hydraulics for any conduit shape
+//! - [`gvf`] - Gradually varied flow water surface profiles (direct step)
+//! - [`culvert`] - Culvert inlet and outlet control headwater (HDS-5)
 //! - [`inlet`] - Inlet capacity calculations (Chapter 7)
 //! - [`solver`] - HGL/EGL solver (9-step procedure from Chapter 9)
+//! - [`looped`] - Simultaneous head and flow solution for looped networks
+//! - [`design`] - Storm drain design (pipe sizing)
+//! - [`rational`] - Network rational method design flows (Tc and ΣCA accumulation)
+//! - [`routing`] - Unsteady kinematic and dynamic wave routing
 //! - [`csv`] - CSV input/output for tabular data
 //! - [`visualization`] - SVG and HTML visualization tools (network plan and profile views)
 //!
@@ -40,17 +49,29 @@
 pub mod analysis;
 pub mod conduit;
 pub mod csv;
+pub mod culvert;
+pub mod design;
 pub mod drainage;
 pub mod gutter;
+pub mod gvf;
 pub mod hydraulics;
+pub mod hydrograph;
 pub mod inlet;
+pub mod looped;
 pub mod network;
 pub mod node;
 pub mod project;
 pub mod rainfall;
+pub mod rational;
+pub mod routing;
+pub mod runoff;
+pub mod section;
 pub mod solver;
 pub mod visualization;
 
+#[cfg(test)]
+pub(crate) mod test_support;
+
 use serde::{Deserialize, Serialize};
 
 /// Root-level drainage network model
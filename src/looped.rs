@@ -0,0 +1,862 @@
+//! Steady-state solution of looped drainage networks
+//!
+//! [`crate::solver::route_flows`] and [`crate::solver::HglSolver`] assume a
+//! dendritic network: flow is accumulated from the top of the tree down and the
+//! HGL is then worked up from the outfall. Older systems with loops,
+//! cross-connections and relief sewers discharging to more than one outfall
+//! have no single downstream path, and the split between branches depends on
+//! the heads along them.
+//!
+//! This module solves node heads and pipe flows simultaneously. Each pipe
+//! carries
+//!
+//! ```text
+//! Q = sign(H₁ - H₂) √(|H₁ - H₂| / r(y)),    r = L / K² + ΣKₘ / (2gA²)
+//! ```
+//!
+//! where K is the Manning conveyance and A the flow area at the depth y where
+//! flow enters the pipe, and ΣKₘ the entrance, exit and bend loss coefficients
+//! of the pipe. Conveyance is limited to its full-pipe value, so flow always
+//! rises with the upstream head and falls with the downstream head. Continuity
+//! at every node gives one equation per unknown head, which is solved by
+//! Newton-Raphson iteration (the nodal form of the Hardy Cross method). Flow
+//! may run either way through a pipe.
+//!
+//! Outfall heads follow their boundary condition; free outfalls take the lesser
+//! of critical and normal depth for the flow they receive. Only pipes take part,
+//! each with its own cross section from [`crate::section::from_conduit`]:
+//! gutter and channel conduits are ignored, so node inflows should be the flows
+//! entering the pipe system.
+//!
+//! ## References
+//!
+//! FHWA HEC-22 (4th Edition, 2024), Chapter 9: Storm Drain Conduits;
+//! Todini, E. and Pilati, S. (1988), *A gradient algorithm for the analysis of
+//! pipe networks*
+
+use crate::analysis::{
+    Analysis, AnalysisMethod, ConduitResult, NodeResult, Severity, TailwaterControl, Violation,
+};
+use crate::network::Network;
+use crate::node::{BoundaryCondition, Node, TailwaterRule};
+use crate::section::{self, CrossSection};
+use crate::solver::SolverConfig;
+use std::collections::HashMap;
+
+/// Smallest slope used for full-flow capacity (ft/ft or m/m)
+const MIN_SLOPE: f64 = 0.0001;
+
+/// Smallest relative depth used for conveyance, so dry pipes stay in the system
+const MIN_DEPTH_RATIO: f64 = 0.001;
+
+/// Head difference below which pipe flow is taken as linear in head (ft or m)
+const LINEAR_HEAD: f64 = 1e-4;
+
+/// A pipe in the looped solution
+struct Link {
+    id: String,
+    from: usize,
+    to: usize,
+    length: f64,
+    /// Pipe cross section
+    section: Box<dyn CrossSection>,
+    /// Crown height of the pipe (ft or m)
+    rise: f64,
+    manning_n: f64,
+    /// Sum of entrance, exit and bend loss coefficients
+    minor_loss: f64,
+    upstream_invert: f64,
+    downstream_invert: f64,
+    slope: f64,
+}
+
+impl Link {
+    /// Depth at each end for the given node heads, limited to the crown
+    fn end_depths(&self, heads: &[f64]) -> (f64, f64) {
+        (
+            (heads[self.from] - self.upstream_invert).clamp(0.0, self.rise),
+            (heads[self.to] - self.downstream_invert).clamp(0.0, self.rise),
+        )
+    }
+
+    /// Mean depth used for conveyance
+    fn mean_depth(&self, heads: &[f64]) -> f64 {
+        let (y1, y2) = self.end_depths(heads);
+        (0.5 * (y1 + y2)).max(MIN_DEPTH_RATIO * self.rise)
+    }
+}
+
+/// Result of a looped network solution
+#[derive(Debug, Clone)]
+pub struct LoopedSolution {
+    /// Solved head at each node (ft or m)
+    pub node_heads: HashMap<String, f64>,
+
+    /// Flow in each pipe, negative when running from `toNode` to `fromNode` (cfs or cms)
+    pub conduit_flows: HashMap<String, f64>,
+
+    /// Flow discharged at each outfall, negative where an outfall feeds the system (cfs or cms)
+    pub outfall_flows: HashMap<String, f64>,
+
+    /// Newton iterations taken
+    pub iterations: usize,
+
+    /// Node HGLs, pipe flows and depths, and HGL violations
+    pub analysis: Analysis,
+}
+
+impl LoopedSolution {
+    /// Fraction of the total outfall discharge leaving through an outfall
+    pub fn outfall_share(&self, outfall_id: &str) -> Option<f64> {
+        let flow = self.outfall_flows.get(outfall_id)?;
+        let total: f64 = self.outfall_flows.values().filter(|q| **q > 0.0).sum();
+        (total > 0.0).then(|| flow / total)
+    }
+}
+
+/// Simultaneous head and flow solver for looped networks
+pub struct LoopedSolver {
+    config: SolverConfig,
+}
+
+impl LoopedSolver {
+    /// Create a new solver with the given configuration
+    pub fn new(config: SolverConfig) -> Self {
+        Self { config }
+    }
+
+    /// Solve the network for node heads and pipe flows
+    ///
+    /// # Arguments
+    /// * `network` - The drainage network to solve
+    /// * `node_inflows` - Inflow entering the pipe system at each node (cfs or cms)
+    /// * `design_storm_id` - ID of the design storm being analyzed
+    ///
+    /// # Returns
+    /// Heads, flows and outfall discharges, or an error if a node with pipes
+    /// or inflow has no pipe path to an outfall or the iteration does not converge
+    pub fn solve(
+        &self,
+        network: &Network,
+        node_inflows: &HashMap<String, f64>,
+        design_storm_id: String,
+    ) -> Result<LoopedSolution, String> {
+        network.validate_connectivity()?;
+        if network.outfalls().is_empty() {
+            return Err("Network has no outfall nodes".to_string());
+        }
+
+        let nodes = &network.nodes;
+        let links = self.build_links(network)?;
+        let lateral: Vec<f64> = nodes
+            .iter()
+            .map(|n| node_inflows.get(&n.id).copied().unwrap_or(0.0))
+            .collect();
+
+        // Every node on a pipe or receiving inflow needs a pipe path to an outfall
+        let mut connected: Vec<bool> = nodes.iter().map(|n| n.is_outfall()).collect();
+        let mut stack: Vec<usize> = (0..nodes.len()).filter(|&i| connected[i]).collect();
+        while let Some(i) = stack.pop() {
+            for link in links.iter().filter(|l| l.from == i || l.to == i) {
+                let other = if link.from == i { link.to } else { link.from };
+                if !connected[other] {
+                    connected[other] = true;
+                    stack.push(other);
+                }
+            }
+        }
+        for (i, node) in nodes.iter().enumerate() {
+            let on_pipe = links.iter().any(|l| l.from == i || l.to == i);
+            if !connected[i] && (on_pipe || lateral[i] > 0.0) {
+                return Err(format!("Node {} has no pipe path to an outfall", node.id));
+            }
+        }
+
+        // Unknown heads are the connected nodes that are not outfalls
+        let mut unknown: Vec<Option<usize>> = vec![None; nodes.len()];
+        let mut count = 0;
+        for (i, node) in nodes.iter().enumerate() {
+            if connected[i] && !node.is_outfall() && links.iter().any(|l| l.from == i || l.to == i) {
+                unknown[i] = Some(count);
+                count += 1;
+            }
+        }
+
+        let max_rise = links.iter().map(|l| l.rise).fold(0.0, f64::max);
+        let mut heads: Vec<f64> = Vec::with_capacity(nodes.len());
+        for (i, node) in nodes.iter().enumerate() {
+            let head = if node.is_outfall() {
+                self.outfall_head(node, i, &links, &vec![0.0; links.len()])?.0
+            } else {
+                let rise = links
+                    .iter()
+                    .filter(|l| l.from == i || l.to == i)
+                    .map(|l| l.rise)
+                    .fold(0.0, f64::max);
+                node.invert_elevation + 0.5 * rise
+            };
+            heads.push(head);
+        }
+
+        let mut iterations = 0;
+        let mut converged = false;
+        while iterations < self.config.max_iterations {
+            iterations += 1;
+
+            // Jacobian of the continuity residual (net inflow) at each unknown node
+            let mut jacobian = vec![vec![0.0; count]; count];
+            for link in &links {
+                let (_, dq_dfrom, dq_dto) = self.link_flow(link, &heads);
+                if let Some(a) = unknown[link.from] {
+                    jacobian[a][a] -= dq_dfrom;
+                    if let Some(b) = unknown[link.to] {
+                        jacobian[a][b] -= dq_dto;
+                    }
+                }
+                if let Some(b) = unknown[link.to] {
+                    jacobian[b][b] += dq_dto;
+                    if let Some(a) = unknown[link.from] {
+                        jacobian[b][a] += dq_dfrom;
+                    }
+                }
+            }
+
+            let residual = self.residual(&links, &lateral, &unknown, count, &heads);
+            let rhs: Vec<f64> = residual.iter().map(|r| -r).collect();
+            let mut step = solve_linear(jacobian, rhs)
+                .ok_or_else(|| "Looped network equations are singular".to_string())?;
+
+            // Limit the step to one pipe rise to keep early iterations stable
+            let largest = step.iter().fold(0.0_f64, |m, s| m.max(s.abs()));
+            if largest > max_rise {
+                let scale = max_rise / largest;
+                step.iter_mut().for_each(|s| *s *= scale);
+            }
+
+            // Halve the step until continuity improves, for heads far from the solution
+            let current = max_abs(&residual);
+            let mut fraction = 1.0;
+            let mut trial = heads.clone();
+            for _ in 0..6 {
+                for (i, slot) in unknown.iter().enumerate() {
+                    if let Some(a) = *slot {
+                        trial[i] = heads[i] + fraction * step[a];
+                    }
+                }
+                if max_abs(&self.residual(&links, &lateral, &unknown, count, &trial)) <= current {
+                    break;
+                }
+                fraction *= 0.5;
+            }
+            let mut max_change = largest;
+            heads = trial;
+
+            // Outfall heads follow the flow they now receive
+            let flows: Vec<f64> = links.iter().map(|l| self.link_flow(l, &heads).0).collect();
+            for (i, node) in nodes.iter().enumerate() {
+                if node.is_outfall() {
+                    let (head, _) = self.outfall_head(node, i, &links, &flows)?;
+                    max_change = max_change.max((head - heads[i]).abs());
+                    heads[i] = head;
+                }
+            }
+
+            if max_change < self.config.tolerance {
+                converged = true;
+                break;
+            }
+        }
+
+        if !converged {
+            return Err(format!(
+                "Looped network solution did not converge in {} iterations",
+                iterations
+            ));
+        }
+
+        let flows: Vec<f64> = links.iter().map(|l| self.link_flow(l, &heads).0).collect();
+        let outfall_flows = nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| n.is_outfall())
+            .map(|(i, n)| (n.id.clone(), net_inflow(&links, &flows, i) + lateral[i]))
+            .collect();
+
+        Ok(LoopedSolution {
+            node_heads: nodes
+                .iter()
+                .zip(&heads)
+                .map(|(n, &h)| (n.id.clone(), h))
+                .collect(),
+            conduit_flows: links
+                .iter()
+                .zip(&flows)
+                .map(|(l, &q)| (l.id.clone(), q))
+                .collect(),
+            outfall_flows,
+            iterations,
+            analysis: self.summarize(network, &links, &heads, &flows, design_storm_id),
+        })
+    }
+
+    /// Collect the pipes of the network
+    fn build_links(&self, network: &Network) -> Result<Vec<Link>, String> {
+        let index: HashMap<&str, usize> = network
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.id.as_str(), i))
+            .collect();
+
+        let mut links = Vec::new();
+        for conduit in &network.conduits {
+            let Some(ref pipe) = conduit.pipe else {
+                continue;
+            };
+            let section = section::from_conduit(conduit, self.config.unit_system)?;
+            let rise = section
+                .crown()
+                .ok_or_else(|| format!("Pipe {} is not a closed section", conduit.id))?;
+            if conduit.length <= 0.0 {
+                return Err(format!("Pipe {} has no length", conduit.id));
+            }
+
+            let from = index[conduit.from_node.as_str()];
+            let to = index[conduit.to_node.as_str()];
+            let upstream_invert = conduit
+                .upstream_invert
+                .unwrap_or(network.nodes[from].invert_elevation);
+            let downstream_invert = conduit
+                .downstream_invert
+                .unwrap_or(network.nodes[to].invert_elevation);
+
+            links.push(Link {
+                id: conduit.id.clone(),
+                from,
+                to,
+                length: conduit.length,
+                section,
+                rise,
+                manning_n: pipe.manning_n,
+                minor_loss: pipe.entrance_loss.unwrap_or(0.0)
+                    + pipe.exit_loss.unwrap_or(0.0)
+                    + pipe.bend_loss.unwrap_or(0.0),
+                upstream_invert,
+                downstream_invert,
+                slope: network.conduit_slope(conduit).unwrap_or(0.0).max(MIN_SLOPE),
+            });
+        }
+        Ok(links)
+    }
+
+    /// Flow coefficient 1/√r at a depth, so that Q = √(ΔH) / √r
+    fn flow_coefficient(&self, link: &Link, depth: f64) -> f64 {
+        let depth = depth.clamp(MIN_DEPTH_RATIO * link.rise, link.rise);
+        let g = self.config.gravity;
+        // Conveyance is held at the full-pipe value near the crown so it
+        // never falls as the pipe fills
+        let conveyance = link.section.conveyance(depth, link.manning_n, self.config.manning_k);
+        let resistance = link.length / conveyance.powi(2)
+            + link.minor_loss / (2.0 * g * link.section.area(depth).powi(2));
+        1.0 / resistance.sqrt()
+    }
+
+    /// Pipe flow for the current heads with its derivatives with respect to
+    /// the `fromNode` and `toNode` heads
+    fn link_flow(&self, link: &Link, heads: &[f64]) -> (f64, f64, f64) {
+        // Conveyance is taken at the end the flow enters
+        let dh = heads[link.from] - heads[link.to];
+        let (y1, y2) = link.end_depths(heads);
+        let entering = if dh >= 0.0 { y1 } else { y2 };
+        let coefficient = self.flow_coefficient(link, entering);
+        let delta = 1e-4 * link.rise;
+        let dcoefficient = if entering > 0.0 && entering < link.rise {
+            (self.flow_coefficient(link, entering + delta)
+                - self.flow_coefficient(link, entering - delta))
+                / (2.0 * delta)
+        } else {
+            0.0
+        };
+
+        // √|ΔH| with sign, linear close to zero so the derivative stays finite
+        let (root, droot) = if dh.abs() >= LINEAR_HEAD {
+            (dh.signum() * dh.abs().sqrt(), 0.5 / dh.abs().sqrt())
+        } else {
+            (dh / LINEAR_HEAD.sqrt(), 1.0 / LINEAR_HEAD.sqrt())
+        };
+
+        let flow = coefficient * root;
+        let mut dq_dfrom = coefficient * droot;
+        let mut dq_dto = -coefficient * droot;
+        if dh >= 0.0 {
+            dq_dfrom += root * dcoefficient;
+        } else {
+            dq_dto += root * dcoefficient;
+        }
+        (flow, dq_dfrom, dq_dto)
+    }
+
+    /// Continuity residual, lateral plus net pipe inflow, at each unknown node
+    fn residual(
+        &self,
+        links: &[Link],
+        lateral: &[f64],
+        unknown: &[Option<usize>],
+        count: usize,
+        heads: &[f64],
+    ) -> Vec<f64> {
+        let mut residual = vec![0.0; count];
+        for (i, slot) in unknown.iter().enumerate() {
+            if let Some(a) = *slot {
+                residual[a] = lateral[i];
+            }
+        }
+        for link in links {
+            let flow = self.link_flow(link, heads).0;
+            if let Some(a) = unknown[link.from] {
+                residual[a] -= flow;
+            }
+            if let Some(b) = unknown[link.to] {
+                residual[b] += flow;
+            }
+        }
+        residual
+    }
+
+    /// Water surface elevation at an outfall for the pipe flows it receives
+    ///
+    /// Free outfalls take the smaller of critical and normal depth in each
+    /// discharging pipe; normal-depth outfalls use the greater of normal depth
+    /// and the specified tailwater; fixed-stage outfalls use the specified
+    /// tailwater and tidal outfalls the tide stage chosen for steady
+    /// analysis. The HEC-22
+    /// rule raises the tailwater to (dc + D)/2 where that is higher. With
+    /// several pipes the highest water surface governs.
+    fn outfall_head(
+        &self,
+        outfall: &Node,
+        index: usize,
+        links: &[Link],
+        flows: &[f64],
+    ) -> Result<(f64, TailwaterControl), String> {
+        let props = outfall
+            .outfall
+            .as_ref()
+            .ok_or_else(|| "Node is not an outfall".to_string())?;
+        let invert = outfall.invert_elevation;
+
+        let g = self.config.gravity;
+        let pipe_depths: Vec<(f64, f64, f64, f64)> = links
+            .iter()
+            .zip(flows)
+            .filter_map(|(link, &q)| {
+                let (inflow, pipe_invert) = if link.to == index {
+                    (q, link.downstream_invert)
+                } else if link.from == index {
+                    (-q, link.upstream_invert)
+                } else {
+                    return None;
+                };
+                if inflow <= 0.0 {
+                    return None;
+                }
+                let normal = link
+                    .section
+                    .normal_depth(inflow, link.slope, link.manning_n, self.config.manning_k)?;
+                let critical = link.section.critical_depth(inflow, g);
+                Some((critical, normal, pipe_invert, link.rise))
+            })
+            .collect();
+        let highest = |depth: fn(f64, f64, f64) -> f64| {
+            pipe_depths
+                .iter()
+                .map(|&(yc, yn, pipe_invert, rise)| pipe_invert + depth(yc, yn, rise))
+                .fold(invert, f64::max)
+        };
+
+        let (head, control) = match props.boundary_condition {
+            BoundaryCondition::Free => (highest(|yc, yn, _| yc.min(yn)), TailwaterControl::Free),
+            BoundaryCondition::NormalDepth => {
+                let normal = highest(|_, yn, _| yn);
+                match props.tailwater_elevation {
+                    Some(tailwater) if tailwater >= normal => (tailwater, TailwaterControl::ReceivingWater),
+                    _ => (normal, TailwaterControl::NormalDepth),
+                }
+            }
+            BoundaryCondition::FixedStage => (
+                props
+                    .tailwater_elevation
+                    .ok_or_else(|| "Fixed stage outfall missing tailwater elevation".to_string())?,
+                TailwaterControl::ReceivingWater,
+            ),
+            BoundaryCondition::Tidal => (
+                props.steady_tide(self.config.peak_times.get(&outfall.id).copied())?,
+                TailwaterControl::ReceivingWater,
+            ),
+        };
+
+        let critical = highest(|yc, _, rise| 0.5 * (yc + rise));
+        let hec22 = props.tailwater_rule == Some(TailwaterRule::Hec22);
+        if hec22 && !pipe_depths.is_empty() && critical > head {
+            return Ok((critical, TailwaterControl::CriticalDepth));
+        }
+        Ok((head, control))
+    }
+
+    /// Collect node and pipe results into an analysis
+    fn summarize(
+        &self,
+        network: &Network,
+        links: &[Link],
+        heads: &[f64],
+        flows: &[f64],
+        design_storm_id: String,
+    ) -> Analysis {
+        let mut analysis = Analysis::new(AnalysisMethod::Rational, design_storm_id);
+
+        let mut node_results = Vec::new();
+        for (i, (node, &head)) in network.nodes.iter().zip(heads).enumerate() {
+            let hgl = head.max(node.invert_elevation);
+            let flooding = node.rim_elevation.is_some_and(|rim| hgl > rim);
+
+            // Highest crown of the pipes meeting at the node
+            let crown = links
+                .iter()
+                .filter_map(|l| {
+                    if l.from == i {
+                        Some(l.upstream_invert + l.rise)
+                    } else if l.to == i {
+                        Some(l.downstream_invert + l.rise)
+                    } else {
+                        None
+                    }
+                })
+                .fold(None, |acc: Option<f64>, c| Some(acc.map_or(c, |a| a.max(c))))
+                .filter(|_| !node.is_outfall());
+
+            node_results.push(NodeResult {
+                node_id: node.id.clone(),
+                hgl: Some(hgl),
+                egl: None,
+                depth: Some(hgl - node.invert_elevation),
+                velocity: None,
+                flooding: Some(flooding),
+                pressure_head: Some(hgl - node.invert_elevation),
+                junction_loss: None,
+                time_of_peak: None,
+                surcharge_duration: None,
+                surcharge_depth: crown.map(|crown| (hgl - crown).max(0.0)),
+                depth_above_rim: node.rim_elevation.map(|rim| (hgl - rim).max(0.0)),
+                flood_volume: None,
+                tailwater_control: node
+                    .is_outfall()
+                    .then(|| self.outfall_head(node, i, links, flows).ok())
+                    .flatten()
+                    .map(|(_, control)| control),
+            });
+
+            if let Some(rim) = node.rim_elevation.filter(|_| flooding) {
+                analysis.add_violation(Violation::hgl_violation(
+                    node.id.clone(),
+                    hgl,
+                    rim,
+                    Severity::Error,
+                ));
+            } else if let Some(crown) = crown.filter(|&c| !self.config.allow_surcharge && hgl > c) {
+                analysis.add_violation(Violation::surcharge_violation(
+                    node.id.clone(),
+                    hgl,
+                    crown,
+                    Severity::Warning,
+                ));
+            }
+        }
+
+        let conduit_results = links
+            .iter()
+            .zip(flows)
+            .map(|(link, &flow)| {
+                let depth = link.mean_depth(heads);
+                let area = link.section.area(depth);
+                let full_flow = link.section.conveyance(link.rise, link.manning_n, self.config.manning_k)
+                    * link.slope.sqrt();
+                ConduitResult {
+                    conduit_id: link.id.clone(),
+                    flow: Some(flow),
+                    velocity: Some(flow / area),
+                    depth: Some(depth),
+                    capacity_used: Some(flow.abs() / full_flow),
+                    froude_number: None,
+                    flow_regime: None,
+                    jump_location: None,
+                    headloss: None,
+                    profile: None,
+                    culvert: None,
+                    time_of_peak: None,
+                    time_of_concentration: None,
+                    intensity: None,
+                    sum_ca: None,
+                    travel_time: None,
+                }
+            })
+            .collect();
+
+        analysis.node_results = Some(node_results);
+        analysis.conduit_results = Some(conduit_results);
+        analysis
+    }
+}
+
+/// Net pipe inflow to a node
+fn net_inflow(links: &[Link], flows: &[f64], node: usize) -> f64 {
+    links
+        .iter()
+        .zip(flows)
+        .map(|(link, &q)| {
+            if link.to == node {
+                q
+            } else if link.from == node {
+                -q
+            } else {
+                0.0
+            }
+        })
+        .sum()
+}
+
+/// Largest absolute value in a residual vector
+fn max_abs(values: &[f64]) -> f64 {
+    values.iter().fold(0.0, |m, v| m.max(v.abs()))
+}
+
+/// Solve a dense linear system by Gaussian elimination with partial pivoting
+fn solve_linear(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-300 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        let (upper, lower) = a.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for (offset, row) in lower.iter_mut().enumerate() {
+            let factor = row[col] / pivot_row[col];
+            if factor == 0.0 {
+                continue;
+            }
+            for (value, pivot) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *value -= factor * pivot;
+            }
+            b[col + 1 + offset] -= factor * b[col];
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conduit::PipeShape;
+    use crate::hydraulics::ManningsEquation;
+    use crate::project::UnitSystem;
+    use crate::solver::{route_flows, topological_sort_upstream_to_downstream};
+    use approx::assert_relative_eq;
+    use crate::test_support::{junction, outfall, pipe};
+
+    fn solve(network: &Network, inflows: &[(&str, f64)]) -> LoopedSolution {
+        let inflows = inflows.iter().map(|(id, q)| (id.to_string(), *q)).collect();
+        LoopedSolver::new(SolverConfig::us_customary())
+            .solve(network, &inflows, "Test".to_string())
+            .unwrap()
+    }
+
+    /// Continuity holds at every node that is not an outfall
+    fn assert_continuity(network: &Network, solution: &LoopedSolution, inflows: &[(&str, f64)]) {
+        for node in network.nodes.iter().filter(|n| !n.is_outfall()) {
+            let lateral: f64 = inflows.iter().filter(|(id, _)| *id == node.id).map(|(_, q)| q).sum();
+            let into: f64 = network
+                .upstream_conduits(&node.id)
+                .iter()
+                .map(|c| solution.conduit_flows[&c.id])
+                .sum();
+            let out: f64 = network
+                .downstream_conduits(&node.id)
+                .iter()
+                .map(|c| solution.conduit_flows[&c.id])
+                .sum();
+            assert_relative_eq!(lateral + into, out, epsilon = 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_single_pipe_runs_near_normal_depth() {
+        let mut network = Network::new();
+        network.add_node(junction("MH-1", 101.5, 108.0));
+        network.add_node(outfall("OUT-1", 100.0, BoundaryCondition::Free, None));
+        network.add_conduit(pipe("P-1", "MH-1", "OUT-1", 300.0, 18.0));
+
+        let solution = solve(&network, &[("MH-1", 5.0)]);
+        assert_relative_eq!(solution.conduit_flows["P-1"], 5.0, epsilon = 1e-3);
+        assert_relative_eq!(solution.outfall_share("OUT-1").unwrap(), 1.0, epsilon = 1e-6);
+
+        let normal = ManningsEquation::us_customary()
+            .normal_depth(5.0, 1.5, 0.005, 0.013, 32.17)
+            .unwrap();
+        let depth = solution.node_heads["MH-1"] - 101.5;
+        assert_relative_eq!(depth, normal, max_relative = 0.1);
+    }
+
+    #[test]
+    fn test_hec22_tailwater_rule() {
+        let mut network = Network::new();
+        network.add_node(junction("MH-1", 101.5, 108.0));
+        let mut out = outfall("OUT-1", 100.0, BoundaryCondition::FixedStage, Some(100.2));
+        out.outfall.as_mut().unwrap().tailwater_rule = Some(TailwaterRule::Hec22);
+        network.add_node(out);
+        network.add_conduit(pipe("P-1", "MH-1", "OUT-1", 300.0, 18.0));
+
+        // The outlet is not submerged, so (dc + D)/2 sets the tailwater
+        let solution = solve(&network, &[("MH-1", 5.0)]);
+        let critical = ManningsEquation::us_customary().critical_depth(5.0, 1.5, 32.17).unwrap();
+        assert_relative_eq!(solution.node_heads["OUT-1"], 100.0 + 0.5 * (critical + 1.5), epsilon = 1e-3);
+
+        let outfall = solution
+            .analysis
+            .node_results
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|n| n.node_id == "OUT-1")
+            .unwrap();
+        assert_eq!(outfall.tailwater_control, Some(TailwaterControl::CriticalDepth));
+    }
+
+    #[test]
+    fn test_parallel_pipes_share_flow_by_conveyance() {
+        let mut network = Network::new();
+        network.add_node(junction("MH-1", 101.0, 108.0));
+        network.add_node(outfall("OUT-1", 100.0, BoundaryCondition::Free, None));
+        network.add_conduit(pipe("P-A", "MH-1", "OUT-1", 200.0, 18.0));
+        network.add_conduit(pipe("P-B", "MH-1", "OUT-1", 200.0, 18.0));
+        network.add_conduit(pipe("P-C", "MH-1", "OUT-1", 200.0, 24.0));
+
+        let inflows = [("MH-1", 12.0)];
+        let solution = solve(&network, &inflows);
+        let flows = &solution.conduit_flows;
+        assert_relative_eq!(flows["P-A"], flows["P-B"], epsilon = 1e-6);
+        assert!(flows["P-C"] > flows["P-A"]);
+        assert_relative_eq!(flows["P-A"] + flows["P-B"] + flows["P-C"], 12.0, epsilon = 1e-3);
+        assert_continuity(&network, &solution, &inflows);
+    }
+
+    #[test]
+    fn test_relief_sewer_to_two_outfalls() {
+        // MH-1 -> MH-2 -> OUT-1 is the old trunk; MH-2 -> OUT-2 is a relief line,
+        // and MH-3 is a cross-connection closing a loop back into MH-1
+        let mut network = Network::new();
+        network.add_node(junction("MH-1", 104.0, 112.0));
+        network.add_node(junction("MH-2", 102.0, 110.0));
+        network.add_node(junction("MH-3", 103.0, 111.0));
+        network.add_node(outfall("OUT-1", 99.0, BoundaryCondition::Free, None));
+        network.add_node(outfall("OUT-2", 100.0, BoundaryCondition::FixedStage, Some(101.0)));
+        network.add_conduit(pipe("P-1", "MH-1", "MH-2", 300.0, 24.0));
+        network.add_conduit(pipe("P-2", "MH-2", "OUT-1", 400.0, 18.0));
+        network.add_conduit(pipe("P-3", "MH-2", "OUT-2", 250.0, 24.0));
+        network.add_conduit(pipe("P-4", "MH-1", "MH-3", 200.0, 15.0));
+        network.add_conduit(pipe("P-5", "MH-3", "MH-2", 200.0, 15.0));
+        network.add_conduit(pipe("P-6", "MH-2", "MH-3", 200.0, 15.0));
+
+        // The MH-2/MH-3 pair is a directed cycle the tree routines reject
+        assert!(topological_sort_upstream_to_downstream(&network).is_err());
+        assert!(route_flows(&network, &HashMap::new(), UnitSystem::US).is_err());
+
+        let inflows = [("MH-1", 20.0), ("MH-3", 4.0)];
+        let solution = solve(&network, &inflows);
+        assert_continuity(&network, &solution, &inflows);
+
+        let out_1 = solution.outfall_flows["OUT-1"];
+        let out_2 = solution.outfall_flows["OUT-2"];
+        assert!(out_1 > 0.0 && out_2 > 0.0);
+        assert_relative_eq!(out_1 + out_2, 24.0, epsilon = 1e-3);
+        assert_relative_eq!(
+            solution.outfall_share("OUT-1").unwrap() + solution.outfall_share("OUT-2").unwrap(),
+            1.0,
+            epsilon = 1e-9
+        );
+
+        // The two MH-2/MH-3 pipes are identical, so they carry equal and
+        // opposite flow relative to their drawn direction
+        let flows = &solution.conduit_flows;
+        assert_relative_eq!(flows["P-5"], -flows["P-6"], epsilon = 1e-6);
+        assert_eq!(solution.analysis.conduit_results.as_ref().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_surcharged_pipes_between_fixed_stages() {
+        // No inflow: water runs from the higher tailwater to the lower one
+        let mut network = Network::new();
+        network.add_node(junction("MH-1", 100.0, 110.0));
+        network.add_node(outfall("OUT-1", 100.0, BoundaryCondition::FixedStage, Some(105.0)));
+        network.add_node(outfall("OUT-2", 100.0, BoundaryCondition::FixedStage, Some(104.0)));
+        network.add_conduit(pipe("P-1", "MH-1", "OUT-1", 200.0, 18.0));
+        network.add_conduit(pipe("P-2", "MH-1", "OUT-2", 200.0, 18.0));
+
+        let solution = solve(&network, &[]);
+        assert_relative_eq!(solution.node_heads["MH-1"], 104.5, epsilon = 1e-4);
+
+        // Full-pipe conveyance across half a foot of head
+        let conveyance = ManningsEquation::us_customary().full_pipe_capacity(1.5, 1.0, 0.013);
+        let flow = conveyance * (0.5_f64 / 200.0).sqrt();
+        assert_relative_eq!(solution.conduit_flows["P-1"], -flow, max_relative = 1e-4);
+        assert_relative_eq!(solution.conduit_flows["P-2"], flow, max_relative = 1e-4);
+        assert_relative_eq!(solution.outfall_flows["OUT-1"], -flow, max_relative = 1e-4);
+    }
+
+    #[test]
+    fn test_box_pipes_use_their_own_geometry() {
+        // As above, with 24 in × 18 in boxes in place of the 18 in pipes
+        let mut network = Network::new();
+        network.add_node(junction("MH-1", 100.0, 110.0));
+        network.add_node(outfall("OUT-1", 100.0, BoundaryCondition::FixedStage, Some(105.0)));
+        network.add_node(outfall("OUT-2", 100.0, BoundaryCondition::FixedStage, Some(104.0)));
+        for (id, to) in [("P-1", "OUT-1"), ("P-2", "OUT-2")] {
+            let mut conduit = pipe(id, "MH-1", to, 200.0, 18.0);
+            let props = conduit.pipe.as_mut().unwrap();
+            props.shape = PipeShape::Rectangular;
+            props.diameter = None;
+            props.width = Some(24.0);
+            props.height = Some(18.0);
+            network.add_conduit(conduit);
+        }
+
+        let solution = solve(&network, &[]);
+        assert_relative_eq!(solution.node_heads["MH-1"], 104.5, epsilon = 1e-4);
+
+        // Full box: A = 3.0 sq ft, R = 3.0 / 7.0 ft
+        let conveyance = 1.486 / 0.013 * 3.0 * (3.0_f64 / 7.0).powf(2.0 / 3.0);
+        let flow = conveyance * (0.5_f64 / 200.0).sqrt();
+        assert_relative_eq!(solution.conduit_flows["P-2"], flow, max_relative = 1e-4);
+    }
+
+    #[test]
+    fn test_unconnected_node_is_reported() {
+        let mut network = Network::new();
+        network.add_node(junction("MH-1", 101.0, 108.0));
+        network.add_node(junction("MH-2", 100.0, 108.0));
+        network.add_node(outfall("OUT-1", 99.0, BoundaryCondition::Free, None));
+        network.add_conduit(pipe("P-1", "MH-2", "OUT-1", 200.0, 18.0));
+
+        let inflows = HashMap::from([("MH-1".to_string(), 2.0)]);
+        let result = LoopedSolver::new(SolverConfig::us_customary()).solve(&network, &inflows, "Test".to_string());
+        assert!(result.unwrap_err().contains("MH-1"));
+    }
+}
@@ -22,7 +22,8 @@ struct Cli {
     #[arg(short, long, value_name = "FILE")]
     conduits: PathBuf,
 
-    /// Path to drainage areas CSV file (required: id, area, runoff_coef, time_of_conc, outlet_node)
+    /// Path to drainage areas CSV file (required: id, area, runoff_coef, outlet_node, and
+    /// time_of_conc or Tc flow path columns)
     #[arg(short = 'a', long, value_name = "FILE")]
     drainage_areas: Option<PathBuf>,
 
@@ -51,6 +52,42 @@ struct Cli {
     #[arg(short = 'f', long, value_enum, default_value = "text")]
     format: OutputFormat,
 
+    /// Size pipes to the smallest standard diameter meeting the design criteria
+    #[arg(long)]
+    design: bool,
+
+    /// Solve heads and flows together, for networks with loops or several outfalls
+    #[arg(long, conflicts_with = "design")]
+    looped: bool,
+
+    /// Write sized conduits (.csv) or the sized network (.json)
+    #[arg(long, value_name = "FILE", requires = "design")]
+    design_output: Option<PathBuf>,
+
+    /// Minimum full-flow velocity for pipe sizing (ft/s or m/s)
+    #[arg(long)]
+    min_velocity: Option<f64>,
+
+    /// Maximum design velocity for pipe sizing (ft/s or m/s)
+    #[arg(long)]
+    max_velocity: Option<f64>,
+
+    /// Minimum Q_full/Q_design ratio for pipe sizing
+    #[arg(long, default_value = "1.0")]
+    min_capacity_ratio: f64,
+
+    /// Report an HGL above a pipe crown as a violation
+    #[arg(long)]
+    no_surcharge: bool,
+
+    /// Lay out pipe inverts below the rims with this minimum cover (ft or m)
+    #[arg(long, requires = "design")]
+    min_cover: Option<f64>,
+
+    /// Drop outlet inverts this far below inlet inverts instead of matching crowns (ft or m)
+    #[arg(long, requires = "min_cover")]
+    invert_drop: Option<f64>,
+
     /// Export network plan view as SVG
     #[arg(long, value_name = "FILE")]
     export_network_plan: Option<PathBuf>,
@@ -172,6 +209,11 @@ fn run_analysis(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         None
     };
 
+    let unit_system = match cli.units {
+        UnitSystemArg::Us => project::UnitSystem::US,
+        UnitSystemArg::Si => project::UnitSystem::SI,
+    };
+
     // Compute flows from drainage areas
     let node_inflows = if let Some(ref areas) = drainage_areas {
         println!("\nComputing rational method flows...");
@@ -181,9 +223,10 @@ fn run_analysis(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         for area in areas {
             // Determine intensity for this drainage area
             let intensity = if let Some(ref curve) = idf_curve {
-                // Use time of concentration to look up intensity from IDF curve
-                if let Some(tc) = area.time_of_concentration {
-                    match curve.get_intensity(tc) {
+                // Use time of concentration (given or computed from the flow
+                // path) to look up intensity from IDF curve
+                match area.design_tc(unit_system, Some(curve)) {
+                    Ok(tc) => match curve.get_intensity(tc) {
                         Some(i) => {
                             println!("  Area {}: Tc={:.1} min, i={:.2} in/hr (from IDF curve)",
                                      area.id, tc, i);
@@ -194,11 +237,11 @@ fn run_analysis(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
                                      area.id, tc);
                             cli.intensity
                         }
+                    },
+                    Err(e) => {
+                        println!("  Warning - {}, using fallback intensity", e);
+                        cli.intensity
                     }
-                } else {
-                    println!("  Area {}: Warning - no time of concentration, using fallback intensity",
-                             area.id);
-                    cli.intensity
                 }
             } else {
                 // No IDF curve provided, use fixed intensity
@@ -228,25 +271,198 @@ fn run_analysis(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         HashMap::new()
     };
 
-    // Route flows through network
-    println!("\nRouting flows through network...");
-    let conduit_flows = solver::route_flows(&network, &node_inflows)
-        .map_err(|e| format!("Flow routing failed: {}", e))?;
+    let criteria = analysis::DesignCriteria {
+        gutter_spread: None,
+        hgl_criteria: Some(analysis::HglCriteria {
+            max_hgl_below_rim: None,
+            allow_surcharge: Some(!cli.no_surcharge),
+        }),
+        velocity: None,
+        cover: None,
+        capacity: None,
+    };
+    let solver_config = || {
+        match cli.units {
+            UnitSystemArg::Us => solver::SolverConfig::us_customary(),
+            UnitSystemArg::Si => solver::SolverConfig::si_metric(),
+        }
+        .with_criteria(&criteria)
+    };
+
+    // With an IDF curve, accumulate Tc and ΣCA through the network so each
+    // pipe uses the intensity for its own time of concentration
+    let mut rational_design = match (&drainage_areas, &idf_curve) {
+        (Some(areas), Some(curve)) if !cli.looped => {
+            println!("\nAccumulating Tc and ΣCA through network...");
+            match rational::RationalSolver::new(solver_config()).solve(&network, areas, curve) {
+                Ok(design) => {
+                    for c in &design.conduits {
+                        println!("  Conduit {}: Tc={:.1} min, i={:.2}, ΣCA={:.2}, Q={:.2}",
+                                 c.conduit_id, c.time_of_concentration, c.intensity, c.sum_ca, c.flow);
+                    }
+                    println!("  (converged in {} iterations)", design.iterations);
+                    Some(design)
+                }
+                Err(e) => {
+                    println!("  Warning - {}, routing inlet flows instead", e);
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
+    // A looped network splits its flow by head, so flows and HGL come out of
+    // one simultaneous solution
+    let mut looped = None;
+    let mut conduit_flows = match rational_design {
+        Some(ref design) => design.conduit_flows(),
+        None if cli.looped => {
+            println!("\nSolving looped network...");
+            let solution = looped::LoopedSolver::new(solver_config())
+                .solve(&network, &node_inflows, "Design Storm".to_string())
+                .map_err(|e| format!("Looped network solution failed: {}", e))?;
+
+            for conduit in &network.conduits {
+                if let Some(flow) = solution.conduit_flows.get(&conduit.id) {
+                    println!("  Conduit {}: {:.2} cfs", conduit.id, flow);
+                }
+            }
+            for outfall in network.outfalls() {
+                println!("  Outfall {}: {:.2} cfs ({:.1}%)",
+                         outfall.id,
+                         solution.outfall_flows.get(&outfall.id).copied().unwrap_or(0.0),
+                         100.0 * solution.outfall_share(&outfall.id).unwrap_or(0.0));
+            }
+            println!("  (converged in {} iterations)", solution.iterations);
+
+            let flows = solution.conduit_flows.clone();
+            looped = Some(solution);
+            flows
+        }
+        None => {
+            // Route flows through network
+            println!("\nRouting flows through network...");
+            let conduit_flows = solver::route_flows(&network, &node_inflows, unit_system)
+                .map_err(|e| format!("Flow routing failed: {}", e))?;
+
+            for (conduit_id, flow) in &conduit_flows {
+                println!("  Conduit {}: {:.2} cfs", conduit_id, flow);
+            }
+            conduit_flows
+        }
+    };
+
+    // Size pipes for the design flows. New sizes change pipe travel times, so
+    // rational design flows are recomputed until the sizes settle.
+    let mut sizing_violations = Vec::new();
+    if cli.design {
+        println!("\nSizing pipes...");
+        let mut sizing_config = match cli.units {
+            UnitSystemArg::Us => design::PipeSizingConfig::us_customary(),
+            UnitSystemArg::Si => design::PipeSizingConfig::si_metric(),
+        };
+        sizing_config.min_capacity_ratio = cli.min_capacity_ratio;
+        sizing_config.min_velocity = cli.min_velocity;
+        sizing_config.max_velocity = cli.max_velocity;
+        let sizer = design::PipeSizer::new(sizing_config);
+
+        // Inverts depend on pipe sizes, and slopes feed back into sizing
+        let profiler = cli.min_cover.map(|min_cover| {
+            let mut profile_config = match cli.units {
+                UnitSystemArg::Us => design::ProfileConfig::us_customary(),
+                UnitSystemArg::Si => design::ProfileConfig::si_metric(),
+            };
+            profile_config.min_cover = min_cover;
+            if cli.min_velocity.is_some() {
+                profile_config.min_velocity = cli.min_velocity;
+            }
+            if let Some(drop) = cli.invert_drop {
+                profile_config.junction_rule = design::JunctionRule::InvertDrop(drop);
+            }
+            design::ProfileDesigner::new(profile_config)
+        });
+        let mut profile = None;
+
+        let mut sizing = sizer.size(&network, &conduit_flows)?;
+        for _ in 0..10 {
+            let mut changed = sizing.apply_to(&mut network);
+            if let Some(ref profiler) = profiler {
+                let result = profiler.design(&network)?;
+                changed |= result.apply_to(&mut network);
+                profile = Some(result);
+            }
+            if !changed {
+                break;
+            }
+            if let (Some(areas), Some(curve)) = (&drainage_areas, &idf_curve) {
+                let design = rational::RationalSolver::new(solver_config()).solve(&network, areas, curve)?;
+                conduit_flows = design.conduit_flows();
+                rational_design = Some(design);
+            }
+            sizing = sizer.resize(&network, &conduit_flows, &sizing)?;
+        }
+
+        for pipe in &sizing.pipes {
+            println!("  Conduit {}: {:?} {} (was {}), Q={:.2}, Qfull={:.2}{}",
+                     pipe.conduit_id,
+                     pipe.material,
+                     pipe.diameter,
+                     pipe.original_diameter.map(|d| d.to_string()).unwrap_or_else(|| "-".to_string()),
+                     pipe.design_flow,
+                     pipe.full_capacity,
+                     if pipe.meets_criteria { "" } else { "  ** criteria not met" });
+        }
+        sizing_violations = sizing.violations;
+
+        if let Some(profile) = profile {
+            println!("\nPipe profile:");
+            for pipe in &profile.pipes {
+                println!("  Conduit {}: inverts {:.2} -> {:.2}, S={:.4} (min {:.4}), cover {} / {}",
+                         pipe.conduit_id,
+                         pipe.upstream_invert,
+                         pipe.downstream_invert,
+                         pipe.slope,
+                         pipe.minimum_slope,
+                         pipe.upstream_cover.map(|c| format!("{:.2}", c)).unwrap_or_else(|| "-".to_string()),
+                         pipe.downstream_cover.map(|c| format!("{:.2}", c)).unwrap_or_else(|| "-".to_string()));
+            }
+            sizing_violations.extend(profile.violations);
+        }
 
-    for (conduit_id, flow) in &conduit_flows {
-        println!("  Conduit {}: {:.2} cfs", conduit_id, flow);
+        if let Some(ref path) = cli.design_output {
+            if path.extension().is_some_and(|ext| ext == "json") {
+                std::fs::write(path, serde_json::to_string_pretty(&network)?)?;
+            } else {
+                csv::write_conduits_csv(path, &network.conduits)?;
+            }
+            println!("  Sized network written to {}", path.display());
+        }
     }
 
     // Run HGL/EGL solver
-    println!("\nSolving for hydraulic grade line...");
-    let config = match cli.units {
-        UnitSystemArg::Us => solver::SolverConfig::us_customary(),
-        UnitSystemArg::Si => solver::SolverConfig::si_metric(),
+    let mut analysis = match looped {
+        Some(solution) => solution.analysis,
+        None => {
+            println!("\nSolving for hydraulic grade line...");
+            // Rational flows peak at the time of concentration, which sets
+            // the tide read at time-of-peak tidal outfalls
+            let mut config = solver_config();
+            if let Some(ref design) = rational_design {
+                config = config.with_peak_times(design.node_tc.clone());
+            }
+            let hgl_solver = solver::HglSolver::new(config);
+            hgl_solver.solve(&network, &conduit_flows, "Design Storm".to_string())
+                .map_err(|e| format!("HGL solver failed: {}", e))?
+        }
     };
 
-    let hgl_solver = solver::HglSolver::new(config);
-    let analysis = hgl_solver.solve(&network, &conduit_flows, "Design Storm".to_string())
-        .map_err(|e| format!("HGL solver failed: {}", e))?;
+    if let Some(ref design) = rational_design {
+        design.apply_to(&mut analysis);
+    }
+    for violation in sizing_violations {
+        analysis.add_violation(violation);
+    }
 
     // Generate output
     println!("\n{}", "=".repeat(80));
@@ -312,29 +528,40 @@ fn format_text_report(
 
     // Node Results
     report.push_str("NODE RESULTS\n");
-    report.push_str(&format!("{:-<80}\n", ""));
+    report.push_str(&format!("{:-<91}\n", ""));
     report.push_str(&format!(
-        "{:<12} {:<10} {:<10} {:<10} {:<10} {:<10}\n",
+        "{:<12} {:<10} {:<10} {:<10} {:<10} {:<10} {:<10}\n",
         "Node ID",
         format!("HGL ({})", unit_suffix),
         format!("EGL ({})", unit_suffix),
         format!("Depth ({})", unit_suffix),
         "Velocity",
+        "Surcharge",
         "Flooding"
     ));
-    report.push_str(&format!("{:-<80}\n", ""));
+    report.push_str(&format!("{:-<91}\n", ""));
 
     if let Some(ref node_results) = analysis.node_results {
         for result in node_results {
             report.push_str(&format!(
-                "{:<12} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10}\n",
+                "{:<12} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10}\n",
                 result.node_id,
                 result.hgl.unwrap_or(0.0),
                 result.egl.unwrap_or(0.0),
                 result.depth.unwrap_or(0.0),
                 result.velocity.unwrap_or(0.0),
+                result.surcharge_depth.unwrap_or(0.0),
                 if result.flooding.unwrap_or(false) { "YES" } else { "No" }
             ));
+            if let Some(control) = result.tailwater_control {
+                let rule = match control {
+                    analysis::TailwaterControl::Free => "free outfall",
+                    analysis::TailwaterControl::NormalDepth => "normal depth",
+                    analysis::TailwaterControl::CriticalDepth => "(dc + D)/2",
+                    analysis::TailwaterControl::ReceivingWater => "receiving water",
+                };
+                report.push_str(&format!("{:<12} tailwater set by {}\n", "", rule));
+            }
         }
     }
 
@@ -357,16 +584,11 @@ fn format_text_report(
 
     if let Some(ref conduit_results) = analysis.conduit_results {
         for result in conduit_results {
-            let regime = if let Some(froude) = result.froude_number {
-                if froude < 1.0 {
-                    "Subcritical"
-                } else if froude > 1.0 {
-                    "Supercritical"
-                } else {
-                    "Critical"
-                }
-            } else {
-                "N/A"
+            let regime = match result.flow_regime {
+                Some(analysis::FlowRegime::Subcritical) => "Subcritical",
+                Some(analysis::FlowRegime::Critical) => "Critical",
+                Some(analysis::FlowRegime::Supercritical) => "Supercritical",
+                None => "N/A",
             };
 
             report.push_str(&format!(
@@ -379,6 +601,28 @@ fn format_text_report(
                 result.froude_number.unwrap_or(0.0),
                 regime
             ));
+            if let Some(jump) = result.jump_location {
+                report.push_str(&format!(
+                    "{:<12} hydraulic jump {:.1} {} below the upstream end\n",
+                    "", jump, unit_suffix
+                ));
+            }
+            if let Some(ref culvert) = result.culvert {
+                let control = match culvert.control {
+                    culvert::CulvertControl::Inlet => "inlet",
+                    culvert::CulvertControl::Outlet => "outlet",
+                };
+                report.push_str(&format!(
+                    "{:<12} culvert headwater {:.2} {} under {} control\n",
+                    "", culvert.headwater, unit_suffix, control
+                ));
+                if let Some(overtopping) = culvert.overtopping_flow.filter(|&q| q > 0.0) {
+                    report.push_str(&format!(
+                        "{:<12} roadway overtopping flow {:.2}\n",
+                        "", overtopping
+                    ));
+                }
+            }
         }
     }
 
@@ -400,6 +644,7 @@ fn format_violation(violation: &analysis::Violation) -> String {
             analysis::ViolationType::Cover => "Cover violation",
             analysis::ViolationType::Capacity => "Capacity violation",
             analysis::ViolationType::Flooding => "Flooding",
+            analysis::ViolationType::Profile => "Profile violation",
         },
         violation.element_id,
         violation.message
@@ -416,19 +661,21 @@ fn write_csv_output(
     // Write node results
     let node_path = base_path.with_extension("nodes.csv");
     let mut node_file = File::create(node_path)?;
-    writeln!(node_file, "node_id,hgl,egl,depth,velocity,flooding")?;
+    writeln!(node_file, "node_id,hgl,egl,depth,velocity,flooding,surcharge_depth,depth_above_rim")?;
 
     if let Some(ref node_results) = analysis.node_results {
         for result in node_results {
             writeln!(
                 node_file,
-                "{},{:.2},{:.2},{:.2},{:.2},{}",
+                "{},{:.2},{:.2},{:.2},{:.2},{},{:.2},{:.2}",
                 result.node_id,
                 result.hgl.unwrap_or(0.0),
                 result.egl.unwrap_or(0.0),
                 result.depth.unwrap_or(0.0),
                 result.velocity.unwrap_or(0.0),
-                result.flooding.unwrap_or(false)
+                result.flooding.unwrap_or(false),
+                result.surcharge_depth.unwrap_or(0.0),
+                result.depth_above_rim.unwrap_or(0.0)
             )?;
         }
     }
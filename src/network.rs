@@ -56,6 +56,17 @@ impl Network {
             .collect()
     }
 
+    /// Slope of a conduit, from its own slope or inverts or else from the
+    /// inverts of its end nodes
+    pub fn conduit_slope(&self, conduit: &Conduit) -> Option<f64> {
+        conduit.effective_slope().or_else(|| {
+            let up = self.find_node(&conduit.from_node)?;
+            let down = self.find_node(&conduit.to_node)?;
+            (conduit.length > 0.0)
+                .then(|| (up.invert_elevation - down.invert_elevation) / conduit.length)
+        })
+    }
+
     /// Get all downstream conduits for a node
     pub fn downstream_conduits(&self, node_id: &str) -> Vec<&Conduit> {
         self.conduits
@@ -141,6 +152,10 @@ mod tests {
                 boundary_condition: BoundaryCondition::Free,
                 tailwater_elevation: None,
                 tidal_curve: None,
+                tidal_stage: None,
+                storm_start: None,
+                flap_gate: None,
+                tailwater_rule: None,
             },
         );
 
@@ -164,6 +179,7 @@ mod tests {
             junction: None,
             inlet: None,
             outfall: None,
+            divider: None,
         });
 
         network.add_node(Node {
@@ -176,6 +192,7 @@ mod tests {
             junction: None,
             inlet: None,
             outfall: None,
+            divider: None,
         });
 
         // Add valid conduit
@@ -199,6 +216,7 @@ mod tests {
                 entrance_loss: None,
                 exit_loss: None,
                 bend_loss: None,
+                culvert: None,
             }),
             gutter: None,
             channel: None,
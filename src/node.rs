@@ -45,6 +45,10 @@ pub struct Node {
     /// Outfall-specific properties
     #[serde(skip_serializing_if = "Option::is_none")]
     pub outfall: Option<OutfallProperties>,
+
+    /// Flow divider splitting this node's outflow (optional)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub divider: Option<FlowDivider>,
 }
 
 /// Node type classification
@@ -133,6 +137,10 @@ pub struct InletProperties {
     #[serde(rename = "curbOpening")]
     pub curb_opening: Option<CurbOpeningProperties>,
 
+    /// Slotted drain properties
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slotted: Option<SlottedProperties>,
+
     /// Local depression depth (in or mm)
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "localDepression")]
@@ -142,6 +150,11 @@ pub struct InletProperties {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "cloggingFactor")]
     pub clogging_factor: Option<f64>,
+
+    /// Node receiving this inlet's bypass flow, overriding the downstream gutter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "bypassTo")]
+    pub bypass_to: Option<String>,
 }
 
 /// Inlet type classification
@@ -154,6 +167,8 @@ pub enum InletType {
     CurbOpening,
     /// Combination grate and curb opening
     Combination,
+    /// Combination inlet with the curb opening extending upstream of the grate
+    Sweeper,
     /// Slotted drain inlet
     Slotted,
 }
@@ -183,6 +198,11 @@ pub struct GrateProperties {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "barConfiguration")]
     pub bar_configuration: Option<BarConfiguration>,
+
+    /// Tested grate type, which sets splash-over and clear opening
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "grateType")]
+    pub grate_type: Option<GrateType>,
 }
 
 /// Bar configuration for grate inlets
@@ -195,6 +215,35 @@ pub enum BarConfiguration {
     Perpendicular,
 }
 
+/// Grate type from the FHWA grate tests (HEC-22 Table 7.1)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum GrateType {
+    /// P-1-7/8 parallel bar
+    #[serde(rename = "P-50")]
+    P50,
+    /// P-1-7/8-4 parallel bar with transverse rods
+    #[serde(rename = "P-50x100")]
+    P50x100,
+    /// P-1-1/8 parallel bar
+    #[serde(rename = "P-30")]
+    P30,
+    /// Curved vane
+    #[serde(rename = "curved-vane")]
+    CurvedVane,
+    /// 45 degree tilt bar, 2-1/4 in spacing
+    #[serde(rename = "tilt-bar-45-60")]
+    TiltBar45x60,
+    /// 45 degree tilt bar, 3-1/4 in spacing
+    #[serde(rename = "tilt-bar-45-85")]
+    TiltBar45x85,
+    /// 30 degree tilt bar, 3-1/4 in spacing
+    #[serde(rename = "tilt-bar-30-85")]
+    TiltBar30x85,
+    /// Reticuline
+    #[serde(rename = "reticuline")]
+    Reticuline,
+}
+
 /// Curb opening inlet properties
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CurbOpeningProperties {
@@ -224,6 +273,85 @@ pub enum ThroatType {
     Vertical,
 }
 
+/// Slotted drain inlet properties
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SlottedProperties {
+    /// Slot length along the gutter (ft or m)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length: Option<f64>,
+
+    /// Slot width (ft or m)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<f64>,
+}
+
+/// Flow divider properties
+///
+/// A divider sends part of a node's outflow to one downstream conduit, the
+/// diverted conduit, and the rest to the node's other downstream conduit.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FlowDivider {
+    /// Conduit receiving the diverted flow
+    #[serde(rename = "divertedConduit")]
+    pub diverted_conduit: String,
+
+    /// How the diverted flow is determined
+    #[serde(flatten)]
+    pub method: DiversionMethod,
+}
+
+/// Diverted flow relation for a flow divider
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "method", rename_all = "kebab-case")]
+pub enum DiversionMethod {
+    /// Fixed fraction of the inflow
+    Fraction {
+        /// Fraction of inflow diverted (0.0-1.0)
+        fraction: f64,
+    },
+
+    /// Overflow weir to a relief line
+    ///
+    /// The stage at the node is the normal depth of the continuing flow in
+    /// the continuing pipe.
+    Weir {
+        /// Weir crest height above the node invert (ft or m)
+        #[serde(rename = "crestHeight")]
+        crest_height: f64,
+
+        /// Weir length (ft or m)
+        length: f64,
+
+        /// Weir coefficient, Q = C L H^1.5 (default 3.0 US, 1.66 SI)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        coefficient: Option<f64>,
+    },
+
+    /// Overflow orifice to a relief line
+    ///
+    /// The stage is found as for a weir, with head taken at the orifice
+    /// center.
+    Orifice {
+        /// Orifice center height above the node invert (ft or m)
+        #[serde(rename = "centerHeight")]
+        center_height: f64,
+
+        /// Orifice area (sq ft or sq m)
+        area: f64,
+
+        /// Discharge coefficient (default 0.6)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        coefficient: Option<f64>,
+    },
+
+    /// Diverted flow interpolated from a rating table
+    Rating {
+        /// (inflow, diverted flow) pairs in order of increasing inflow
+        /// (cfs or cms)
+        table: Vec<(f64, f64)>,
+    },
+}
+
 /// Outfall properties
 ///
 /// Outfalls represent discharge points to receiving waters or downstream systems.
@@ -243,6 +371,26 @@ pub struct OutfallProperties {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "tidalCurve")]
     pub tidal_curve: Option<Vec<TidalPoint>>,
+
+    /// Tide stage a steady analysis uses from the tidal curve (default: mean tide)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "tidalStage")]
+    pub tidal_stage: Option<TidalStage>,
+
+    /// Time on the tidal curve when the storm begins (minutes, default 0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "stormStart")]
+    pub storm_start: Option<f64>,
+
+    /// Flap gate keeping the receiving water out of the outfall pipe
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "flapGate")]
+    pub flap_gate: Option<bool>,
+
+    /// How the steady tailwater is chosen (default: the boundary condition alone)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "tailwaterRule")]
+    pub tailwater_rule: Option<TailwaterRule>,
 }
 
 /// Downstream boundary condition type
@@ -259,15 +407,135 @@ pub enum BoundaryCondition {
     Tidal,
 }
 
+/// Choice of tailwater for a steady analysis
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TailwaterRule {
+    /// Water level given by the boundary condition
+    Boundary,
+    /// HEC-22 Chapter 9: the greater of (dc + D)/2 in the outlet pipe and
+    /// the boundary water level
+    Hec22,
+}
+
 /// Tidal stage data point
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TidalPoint {
-    /// Time from start (minutes or hours)
+    /// Time from the start of the tidal curve (minutes)
     pub time: f64,
     /// Water surface elevation at this time
     pub elevation: f64,
 }
 
+/// Tide stage used by a steady analysis of a tidal outfall
+///
+/// Times are measured from the start of the storm, which falls at
+/// `stormStart` on the tidal curve.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "method", rename_all = "kebab-case")]
+pub enum TidalStage {
+    /// Mean tide: the tailwater elevation if given, or else the
+    /// time-weighted mean of the tidal curve
+    Mean,
+
+    /// Stage when the peak flow reaches the outfall
+    TimeOfPeak {
+        /// Time of peak after the storm begins (minutes); defaults to the
+        /// time of concentration at the outfall
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(rename = "peakTime")]
+        peak_time: Option<f64>,
+    },
+
+    /// Highest stage over a window of the storm
+    Maximum {
+        /// Window start after the storm begins (minutes)
+        start: f64,
+
+        /// Window end after the storm begins (minutes)
+        end: f64,
+    },
+}
+
+impl OutfallProperties {
+    /// Tide stage at a time after the storm begins
+    ///
+    /// Stages are interpolated linearly along the tidal curve and held at
+    /// the first and last points beyond its ends. Returns `None` without a
+    /// tidal curve.
+    pub fn tide_at(&self, time: f64) -> Option<f64> {
+        let curve = self.tidal_curve.as_ref().filter(|c| !c.is_empty())?;
+        let t = self.storm_start.unwrap_or(0.0) + time;
+
+        let first = &curve[0];
+        let last = &curve[curve.len() - 1];
+        if t <= first.time {
+            return Some(first.elevation);
+        }
+        if t >= last.time {
+            return Some(last.elevation);
+        }
+        curve.windows(2).find(|w| t <= w[1].time).map(|w| {
+            let span = w[1].time - w[0].time;
+            if span <= 0.0 {
+                w[1].elevation
+            } else {
+                w[0].elevation + (w[1].elevation - w[0].elevation) * (t - w[0].time) / span
+            }
+        })
+    }
+
+    /// Tailwater of a tidal outfall for a steady analysis
+    ///
+    /// # Arguments
+    /// * `peak_time` - Time of peak flow at the outfall after the storm
+    ///   begins (minutes), used when the stage does not give its own
+    pub fn steady_tide(&self, peak_time: Option<f64>) -> Result<f64, String> {
+        let missing_curve = || "Tidal outfall missing tidal curve".to_string();
+        match self.tidal_stage.as_ref().unwrap_or(&TidalStage::Mean) {
+            TidalStage::Mean => self
+                .tailwater_elevation
+                .or_else(|| self.mean_tide())
+                .ok_or_else(|| "Tidal outfall missing tailwater elevation".to_string()),
+            TidalStage::TimeOfPeak { peak_time: given } => {
+                let time = given
+                    .or(peak_time)
+                    .ok_or_else(|| "Tidal outfall has no time of peak".to_string())?;
+                self.tide_at(time).ok_or_else(missing_curve)
+            }
+            TidalStage::Maximum { start, end } => {
+                let curve = self.tidal_curve.as_deref().unwrap_or(&[]);
+                let offset = self.storm_start.unwrap_or(0.0);
+                let ends = [self.tide_at(*start), self.tide_at(*end)];
+                ends.into_iter()
+                    .flatten()
+                    .chain(
+                        curve
+                            .iter()
+                            .filter(|p| p.time >= offset + start && p.time <= offset + end)
+                            .map(|p| p.elevation),
+                    )
+                    .reduce(f64::max)
+                    .ok_or_else(missing_curve)
+            }
+        }
+    }
+
+    /// Time-weighted mean of the tidal curve
+    fn mean_tide(&self) -> Option<f64> {
+        let curve = self.tidal_curve.as_ref().filter(|c| !c.is_empty())?;
+        let duration = curve[curve.len() - 1].time - curve[0].time;
+        if duration <= 0.0 {
+            return Some(curve.iter().map(|p| p.elevation).sum::<f64>() / curve.len() as f64);
+        }
+        let area: f64 = curve
+            .windows(2)
+            .map(|w| 0.5 * (w[0].elevation + w[1].elevation) * (w[1].time - w[0].time))
+            .sum();
+        Some(area / duration)
+    }
+}
+
 impl Node {
     /// Create a new junction node
     pub fn new_junction(
@@ -286,6 +554,7 @@ impl Node {
             junction: Some(properties),
             inlet: None,
             outfall: None,
+            divider: None,
         }
     }
 
@@ -306,6 +575,7 @@ impl Node {
             junction: None,
             inlet: Some(properties),
             outfall: None,
+            divider: None,
         }
     }
 
@@ -325,6 +595,7 @@ impl Node {
             junction: None,
             inlet: None,
             outfall: Some(properties),
+            divider: None,
         }
     }
 
@@ -375,10 +646,13 @@ mod tests {
                 length: Some(2.0),
                 width: Some(1.5),
                 bar_configuration: Some(BarConfiguration::Perpendicular),
+                grate_type: None,
             }),
             curb_opening: None,
+            slotted: None,
             local_depression: Some(2.0),
             clogging_factor: Some(0.15),
+            bypass_to: None,
         };
 
         let node = Node::new_inlet("IN-001".to_string(), 124.5, 128.0, props);
@@ -394,6 +668,10 @@ mod tests {
             boundary_condition: BoundaryCondition::NormalDepth,
             tailwater_elevation: None,
             tidal_curve: None,
+            tidal_stage: None,
+            storm_start: None,
+            flap_gate: None,
+            tailwater_rule: None,
         };
 
         let node = Node::new_outfall("OUT-001".to_string(), 115.0, props);
@@ -401,4 +679,49 @@ mod tests {
         assert_eq!(node.id, "OUT-001");
         assert!(node.is_outfall());
     }
+
+    #[test]
+    fn test_tidal_stage() {
+        // Semidiurnal tide from 2.0 to 6.0 ft, storm starting at 2 hours
+        let mut props = OutfallProperties {
+            boundary_condition: BoundaryCondition::Tidal,
+            tailwater_elevation: None,
+            tidal_curve: Some(vec![
+                TidalPoint { time: 0.0, elevation: 2.0 },
+                TidalPoint { time: 180.0, elevation: 6.0 },
+                TidalPoint { time: 540.0, elevation: 2.0 },
+            ]),
+            tidal_stage: None,
+            storm_start: Some(120.0),
+            flap_gate: None,
+            tailwater_rule: None,
+        };
+
+        assert_eq!(props.tide_at(-180.0), Some(2.0));
+        assert!((props.tide_at(15.0).unwrap() - 5.0).abs() < 1e-9);
+        assert_eq!(props.tide_at(600.0), Some(2.0));
+
+        // Mean tide: 4.0 ft over the curve, or the given tailwater
+        assert!((props.steady_tide(None).unwrap() - 4.0).abs() < 1e-9);
+        props.tailwater_elevation = Some(3.5);
+        assert_eq!(props.steady_tide(None), Ok(3.5));
+
+        // Time of peak from the solver, or from the stage itself
+        props.tidal_stage = Some(TidalStage::TimeOfPeak { peak_time: None });
+        assert!(props.steady_tide(None).is_err());
+        assert!((props.steady_tide(Some(15.0)).unwrap() - 5.0).abs() < 1e-9);
+        props.tidal_stage = Some(TidalStage::TimeOfPeak { peak_time: Some(150.0) });
+        assert!((props.steady_tide(Some(15.0)).unwrap() - 5.0).abs() < 1e-9);
+
+        // The high tide inside the window, or the higher end outside it
+        props.tidal_stage = Some(TidalStage::Maximum { start: 0.0, end: 120.0 });
+        assert_eq!(props.steady_tide(None), Ok(6.0));
+        props.tidal_stage = Some(TidalStage::Maximum { start: 150.0, end: 240.0 });
+        assert!((props.steady_tide(None).unwrap() - 5.0).abs() < 1e-9);
+
+        let json = r#"{"boundaryCondition": "tidal", "tidalStage": {"method": "time-of-peak"}, "flapGate": true}"#;
+        let parsed: OutfallProperties = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.tidal_stage, Some(TidalStage::TimeOfPeak { peak_time: None }));
+        assert_eq!(parsed.flap_gate, Some(true));
+    }
 }
@@ -53,6 +53,12 @@ pub struct DesignStorm {
     /// Time-series rainfall data for custom distributions (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hyetograph: Option<Vec<HyetographPoint>>,
+
+    /// Dimensionless cumulative rainfall curve for Atlas 14 quartile or custom
+    /// distributions (optional)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "temporalCurve")]
+    pub temporal_curve: Option<Vec<CumulativeRainfallPoint>>,
 }
 
 /// Temporal rainfall distribution type
@@ -70,12 +76,128 @@ pub enum DistributionType {
     /// SCS Type III (Gulf of Mexico, Atlantic coastal areas)
     #[serde(rename = "SCS Type III")]
     ScsTypeIII,
+    /// NOAA Atlas 14 first-quartile storm (most rain in the first quarter)
+    #[serde(rename = "NOAA Atlas 14 First Quartile")]
+    Atlas14FirstQuartile,
+    /// NOAA Atlas 14 second-quartile storm
+    #[serde(rename = "NOAA Atlas 14 Second Quartile")]
+    Atlas14SecondQuartile,
+    /// NOAA Atlas 14 third-quartile storm
+    #[serde(rename = "NOAA Atlas 14 Third Quartile")]
+    Atlas14ThirdQuartile,
+    /// NOAA Atlas 14 fourth-quartile storm
+    #[serde(rename = "NOAA Atlas 14 Fourth Quartile")]
+    Atlas14FourthQuartile,
     /// Uniform distribution (constant intensity)
     Uniform,
     /// Custom distribution (use hyetograph)
     Custom,
 }
 
+/// Point on a dimensionless cumulative rainfall curve
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CumulativeRainfallPoint {
+    /// Fraction of storm duration elapsed (0.0-1.0)
+    #[serde(rename = "timeFraction")]
+    pub time_fraction: f64,
+
+    /// Fraction of total depth fallen (0.0-1.0)
+    #[serde(rename = "depthFraction")]
+    pub depth_fraction: f64,
+}
+
+/// SCS Type I 24-hour cumulative distribution (hour, fraction of depth)
+///
+/// Source: NRCS TR-55 (1986) and SCS Technical Paper 149.
+const SCS_TYPE_I: [(f64, f64); 20] = [
+    (0.0, 0.000), (2.0, 0.035), (4.0, 0.076), (6.0, 0.125), (7.0, 0.156),
+    (8.0, 0.194), (8.5, 0.219), (9.0, 0.254), (9.5, 0.303), (9.75, 0.362),
+    (10.0, 0.515), (10.5, 0.583), (11.0, 0.624), (11.5, 0.654), (12.0, 0.682),
+    (13.0, 0.727), (14.0, 0.767), (16.0, 0.830), (20.0, 0.926), (24.0, 1.000),
+];
+
+/// SCS Type IA 24-hour cumulative distribution (hour, fraction of depth)
+const SCS_TYPE_IA: [(f64, f64); 19] = [
+    (0.0, 0.000), (2.0, 0.050), (4.0, 0.116), (6.0, 0.206), (7.0, 0.268),
+    (8.0, 0.425), (8.5, 0.480), (9.0, 0.520), (9.5, 0.550), (10.0, 0.577),
+    (10.5, 0.601), (11.0, 0.624), (11.5, 0.645), (12.0, 0.664), (13.0, 0.701),
+    (14.0, 0.736), (16.0, 0.800), (20.0, 0.906), (24.0, 1.000),
+];
+
+/// SCS Type II 24-hour cumulative distribution (hour, fraction of depth)
+const SCS_TYPE_II: [(f64, f64); 22] = [
+    (0.0, 0.000), (2.0, 0.022), (4.0, 0.048), (6.0, 0.080), (7.0, 0.098),
+    (8.0, 0.120), (8.5, 0.133), (9.0, 0.147), (9.5, 0.163), (10.0, 0.181),
+    (10.5, 0.204), (11.0, 0.235), (11.5, 0.283), (11.75, 0.357), (12.0, 0.663),
+    (12.5, 0.735), (13.0, 0.772), (13.5, 0.799), (14.0, 0.820), (16.0, 0.880),
+    (20.0, 0.952), (24.0, 1.000),
+];
+
+/// SCS Type III 24-hour cumulative distribution (hour, fraction of depth)
+const SCS_TYPE_III: [(f64, f64); 21] = [
+    (0.0, 0.000), (2.0, 0.020), (4.0, 0.043), (6.0, 0.072), (7.0, 0.089),
+    (8.0, 0.115), (8.5, 0.130), (9.0, 0.148), (9.5, 0.167), (10.0, 0.189),
+    (10.5, 0.216), (11.0, 0.250), (11.5, 0.298), (12.0, 0.500), (12.5, 0.702),
+    (13.0, 0.751), (13.5, 0.785), (14.0, 0.811), (16.0, 0.886), (20.0, 0.957),
+    (24.0, 1.000),
+];
+
+impl DistributionType {
+    /// Built-in 24-hour cumulative table (hour, fraction of depth), if any
+    fn scs_table(&self) -> Option<&'static [(f64, f64)]> {
+        match self {
+            DistributionType::ScsTypeI => Some(&SCS_TYPE_I),
+            DistributionType::ScsTypeIA => Some(&SCS_TYPE_IA),
+            DistributionType::ScsTypeII => Some(&SCS_TYPE_II),
+            DistributionType::ScsTypeIII => Some(&SCS_TYPE_III),
+            _ => None,
+        }
+    }
+
+    /// Whether this is an NOAA Atlas 14 quartile distribution
+    pub fn is_atlas14_quartile(&self) -> bool {
+        matches!(
+            self,
+            DistributionType::Atlas14FirstQuartile
+                | DistributionType::Atlas14SecondQuartile
+                | DistributionType::Atlas14ThirdQuartile
+                | DistributionType::Atlas14FourthQuartile
+        )
+    }
+
+    /// Cumulative fraction of total depth at a fraction of storm duration
+    ///
+    /// SCS distributions are the standard 24-hour tables with the time axis
+    /// scaled to the storm duration; uniform is linear. Atlas 14 quartile and
+    /// custom distributions need a tabulated curve and return `None` here.
+    pub fn cumulative_fraction(&self, time_fraction: f64) -> Option<f64> {
+        let t = time_fraction.clamp(0.0, 1.0);
+        if *self == DistributionType::Uniform {
+            return Some(t);
+        }
+        let table = self.scs_table()?;
+        Some(interpolate_table(table, t * 24.0))
+    }
+}
+
+/// Linear interpolation in an ascending (x, y) table, clamped at the ends
+fn interpolate_table(table: &[(f64, f64)], x: f64) -> f64 {
+    let (first, last) = (table[0], table[table.len() - 1]);
+    if x <= first.0 {
+        return first.1;
+    }
+    if x >= last.0 {
+        return last.1;
+    }
+    for pair in table.windows(2) {
+        let ((x1, y1), (x2, y2)) = (pair[0], pair[1]);
+        if x <= x2 {
+            return y1 + (y2 - y1) * (x - x1) / (x2 - x1);
+        }
+    }
+    last.1
+}
+
 /// Hyetograph data point (time-series rainfall)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct HyetographPoint {
@@ -244,8 +366,126 @@ impl DesignStorm {
             distribution: Some(DistributionType::Uniform),
             peak_intensity: Some(intensity),
             hyetograph: None,
+            temporal_curve: None,
         }
     }
+
+    /// Create a storm of known depth and duration with a temporal distribution
+    ///
+    /// # Arguments
+    /// * `total_depth` - Storm depth (inches or mm)
+    /// * `duration` - Storm duration (minutes)
+    /// * `distribution` - Temporal distribution
+    pub fn with_distribution(
+        id: String,
+        name: String,
+        return_period: f64,
+        total_depth: f64,
+        duration: f64,
+        distribution: DistributionType,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            return_period,
+            duration: Some(duration),
+            total_depth: Some(total_depth),
+            distribution: Some(distribution),
+            peak_intensity: None,
+            hyetograph: None,
+            temporal_curve: None,
+        }
+    }
+
+    /// Expand the storm's depth, duration and distribution into a hyetograph
+    ///
+    /// Each point holds the average intensity over the following `time_step`
+    /// minutes. SCS Type I/IA/II/III use the built-in 24-hour tables rescaled to
+    /// the storm duration. NOAA Atlas 14 quartile and custom distributions use
+    /// `temporal_curve`; Atlas 14 curves vary by region and duration, so take
+    /// them from the NOAA temporal distribution tables for the project site.
+    ///
+    /// # Arguments
+    /// * `time_step` - Hyetograph interval (minutes)
+    ///
+    /// # Returns
+    /// Hyetograph points with intensity in in/hr or mm/hr
+    pub fn generate_hyetograph(&self, time_step: f64) -> Result<Vec<HyetographPoint>, String> {
+        if time_step <= 0.0 {
+            return Err("Hyetograph time step must be positive".to_string());
+        }
+        let duration = self
+            .duration
+            .ok_or_else(|| format!("Design storm {} has no duration", self.id))?;
+        let distribution = self.distribution.unwrap_or(DistributionType::Uniform);
+        let total_depth = match (self.total_depth, distribution) {
+            (Some(depth), _) => depth,
+            (None, DistributionType::Uniform) => self
+                .peak_intensity
+                .map(|i| i * duration / 60.0)
+                .ok_or_else(|| format!("Design storm {} has no depth or intensity", self.id))?,
+            (None, _) => return Err(format!("Design storm {} has no total depth", self.id)),
+        };
+
+        let fraction = |t: f64| -> Result<f64, String> {
+            match self.temporal_curve.as_ref() {
+                Some(curve) if distribution.is_atlas14_quartile()
+                    || distribution == DistributionType::Custom =>
+                {
+                    let table: Vec<(f64, f64)> = curve
+                        .iter()
+                        .map(|p| (p.time_fraction, p.depth_fraction))
+                        .collect();
+                    Ok(interpolate_table(&table, t))
+                }
+                _ => distribution.cumulative_fraction(t).ok_or_else(|| {
+                    format!(
+                        "Design storm {} uses {:?} but has no temporal curve",
+                        self.id, distribution
+                    )
+                }),
+            }
+        };
+
+        let steps = (duration / time_step).ceil() as usize;
+        let mut points = Vec::with_capacity(steps);
+        for k in 0..steps {
+            let start = k as f64 * time_step;
+            let end = (start + time_step).min(duration);
+            let depth = (fraction(end / duration)? - fraction(start / duration)?) * total_depth;
+            points.push(HyetographPoint {
+                time: start,
+                intensity: depth * 60.0 / time_step,
+            });
+        }
+
+        Ok(points)
+    }
+
+    /// Cumulative rainfall depth from storm start to `time` (inches or mm)
+    ///
+    /// Each hyetograph intensity is held constant until the next point; the last
+    /// point lasts as long as the interval before it. Returns `None` if the storm
+    /// has no hyetograph.
+    pub fn cumulative_depth(&self, time: f64) -> Option<f64> {
+        let points = self.hyetograph.as_ref()?;
+        let mut depth = 0.0;
+
+        for (i, point) in points.iter().enumerate() {
+            let end = match points.get(i + 1) {
+                Some(next) => next.time,
+                None if i > 0 => point.time + (point.time - points[i - 1].time),
+                None => point.time,
+            };
+            if time <= point.time {
+                break;
+            }
+            let elapsed = time.min(end) - point.time;
+            depth += point.intensity * elapsed / 60.0;
+        }
+
+        Some(depth)
+    }
 }
 
 #[cfg(test)]
@@ -310,4 +550,92 @@ mod tests {
         assert_eq!(storm.peak_intensity, Some(3.8));
         assert_eq!(storm.distribution, Some(DistributionType::Uniform));
     }
+
+    #[test]
+    fn test_scs_type_ii_hyetograph() {
+        let storm = DesignStorm::with_distribution(
+            "100yr-24hr".to_string(),
+            "100-Year, 24-Hour".to_string(),
+            100.0,
+            6.0,
+            1440.0,
+            DistributionType::ScsTypeII,
+        );
+
+        let hyetograph = storm.generate_hyetograph(6.0).unwrap();
+        assert_eq!(hyetograph.len(), 240);
+
+        let total: f64 = hyetograph.iter().map(|p| p.intensity * 6.0 / 60.0).sum();
+        assert!((total - 6.0).abs() < 1e-9);
+
+        // Peak intensity falls in the block ending at hour 12
+        let peak = hyetograph
+            .iter()
+            .max_by(|a, b| a.intensity.partial_cmp(&b.intensity).unwrap())
+            .unwrap();
+        assert!(peak.time >= 702.0 && peak.time < 720.0);
+
+        // 30.6% of the depth falls between 11.75 h and 12 h: 1.836 in over 15 min
+        assert!((peak.intensity - 1.836 * 4.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_distribution_rescaled_to_duration() {
+        // A 6-hour Type III storm reaches its midpoint at 3 hours
+        assert_eq!(DistributionType::ScsTypeIII.cumulative_fraction(0.5), Some(0.5));
+
+        let mut storm = DesignStorm::with_distribution(
+            "10yr-6hr".to_string(),
+            "10-Year, 6-Hour".to_string(),
+            10.0,
+            3.0,
+            360.0,
+            DistributionType::ScsTypeIII,
+        );
+        storm.hyetograph = Some(storm.generate_hyetograph(5.0).unwrap());
+        assert!((storm.cumulative_depth(180.0).unwrap() - 1.5).abs() < 1e-9);
+        assert!((storm.cumulative_depth(360.0).unwrap() - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_atlas14_quartile_requires_curve() {
+        let mut storm = DesignStorm::with_distribution(
+            "25yr-24hr".to_string(),
+            "25-Year, 24-Hour".to_string(),
+            25.0,
+            5.0,
+            1440.0,
+            DistributionType::Atlas14FirstQuartile,
+        );
+        assert!(storm.generate_hyetograph(15.0).is_err());
+
+        storm.temporal_curve = Some(vec![
+            CumulativeRainfallPoint { time_fraction: 0.0, depth_fraction: 0.0 },
+            CumulativeRainfallPoint { time_fraction: 0.25, depth_fraction: 0.6 },
+            CumulativeRainfallPoint { time_fraction: 1.0, depth_fraction: 1.0 },
+        ]);
+        let hyetograph = storm.generate_hyetograph(15.0).unwrap();
+
+        // 60% of 5 in during the first 6 hours
+        let first_quarter: f64 = hyetograph
+            .iter()
+            .filter(|p| p.time < 360.0)
+            .map(|p| p.intensity * 15.0 / 60.0)
+            .sum();
+        assert!((first_quarter - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cumulative_depth() {
+        let mut storm = DesignStorm::uniform("storm-1".to_string(), "Block".to_string(), 10.0, 2.0);
+        storm.hyetograph = Some(vec![
+            HyetographPoint { time: 0.0, intensity: 2.0 },
+            HyetographPoint { time: 30.0, intensity: 4.0 },
+        ]);
+
+        // 2 in/hr for 30 min, then 4 in/hr for the final 30 min block
+        assert!((storm.cumulative_depth(15.0).unwrap() - 0.5).abs() < 1e-9);
+        assert!((storm.cumulative_depth(45.0).unwrap() - 2.0).abs() < 1e-9);
+        assert!((storm.cumulative_depth(120.0).unwrap() - 3.0).abs() < 1e-9);
+    }
 }
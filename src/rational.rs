@@ -0,0 +1,444 @@
+//! Network rational method design flows
+//!
+//! Computes design discharges for each conduit following the storm drain
+//! design procedure of HEC-22 Section 9.3 (Step 3):
+//!
+//! 1. Accumulate the contributing ΣCA from every upstream inlet
+//! 2. Take the time of concentration at the upstream end of each pipe as the
+//!    larger of the local inlet Tc and the upstream system Tc plus pipe travel time
+//! 3. Read the rainfall intensity for that Tc from the IDF curve
+//! 4. Compute Q = i × ΣCA and the design flow velocity and travel time in the pipe
+//!
+//! Pipe travel times depend on the design velocities, which depend on the flows.
+//! The solver sweeps the network until the velocities stop changing.
+
+use crate::analysis::Analysis;
+use crate::conduit::Conduit;
+use crate::drainage::DrainageArea;
+use crate::gvf::{DirectStep, Reach};
+use crate::section;
+use crate::hydraulics::ManningsEquation;
+use crate::network::Network;
+use crate::project::UnitSystem;
+use crate::rainfall::IdfCurve;
+use crate::solver::{split_outflow, topological_sort_upstream_to_downstream, SolverConfig};
+use std::collections::HashMap;
+
+/// Rational method design values for one conduit
+#[derive(Debug, Clone, PartialEq)]
+pub struct RationalConduit {
+    /// Conduit ID
+    pub conduit_id: String,
+    /// Sum of C × A draining to the conduit (acres or hectares)
+    pub sum_ca: f64,
+    /// Time of concentration at the upstream end (minutes)
+    pub time_of_concentration: f64,
+    /// Rainfall intensity for the time of concentration (in/hr or mm/hr)
+    pub intensity: f64,
+    /// Design flow (cfs or cms)
+    pub flow: f64,
+    /// Design flow velocity (ft/s or m/s), `None` for non-pipe conduits
+    pub velocity: Option<f64>,
+    /// Travel time through the conduit (minutes)
+    pub travel_time: f64,
+}
+
+/// Result of a network rational design
+#[derive(Debug, Clone)]
+pub struct RationalDesign {
+    /// Design values per conduit, in upstream-to-downstream order
+    pub conduits: Vec<RationalConduit>,
+    /// Time of concentration at each node (minutes)
+    pub node_tc: HashMap<String, f64>,
+    /// Number of sweeps until the velocities converged
+    pub iterations: usize,
+}
+
+impl RationalDesign {
+    /// Design values for a conduit
+    pub fn conduit(&self, conduit_id: &str) -> Option<&RationalConduit> {
+        self.conduits.iter().find(|c| c.conduit_id == conduit_id)
+    }
+
+    /// Design flow per conduit, suitable for [`crate::solver::HglSolver::solve`]
+    pub fn conduit_flows(&self) -> HashMap<String, f64> {
+        self.conduits
+            .iter()
+            .map(|c| (c.conduit_id.clone(), c.flow))
+            .collect()
+    }
+
+    /// Record Tc, intensity, ΣCA and travel time on the analysis conduit results
+    pub fn apply_to(&self, analysis: &mut Analysis) {
+        if let Some(ref mut results) = analysis.conduit_results {
+            for result in results.iter_mut() {
+                if let Some(design) = self.conduit(&result.conduit_id) {
+                    result.time_of_concentration = Some(design.time_of_concentration);
+                    result.intensity = Some(design.intensity);
+                    result.sum_ca = Some(design.sum_ca);
+                    result.travel_time = Some(design.travel_time);
+                }
+            }
+        }
+    }
+}
+
+/// Network-aware rational method solver
+pub struct RationalSolver {
+    config: SolverConfig,
+    direct_step: DirectStep,
+    minimum_tc: f64,
+}
+
+impl RationalSolver {
+    /// Create a new solver with the given configuration
+    ///
+    /// The minimum inlet time of concentration defaults to 5 minutes.
+    pub fn new(config: SolverConfig) -> Self {
+        let direct_step = DirectStep {
+            mannings: ManningsEquation { k: config.manning_k },
+            gravity: config.gravity,
+        };
+        Self {
+            config,
+            direct_step,
+            minimum_tc: 5.0,
+        }
+    }
+
+    /// Set the minimum inlet time of concentration (minutes)
+    pub fn with_minimum_tc(mut self, minimum_tc: f64) -> Self {
+        self.minimum_tc = minimum_tc;
+        self
+    }
+
+    /// Compute design flows for every conduit in the network
+    ///
+    /// # Arguments
+    /// * `network` - The drainage network
+    /// * `drainage_areas` - Areas draining to inlets (each needs C and a Tc)
+    /// * `idf` - IDF curve for the design return period
+    ///
+    /// # Returns
+    /// Tc, intensity, ΣCA and flow for each conduit
+    pub fn solve(
+        &self,
+        network: &Network,
+        drainage_areas: &[DrainageArea],
+        idf: &IdfCurve,
+    ) -> Result<RationalDesign, String> {
+        // Inlet ΣCA and Tc at each node
+        let mut inlet_ca: HashMap<String, f64> = HashMap::new();
+        let mut inlet_tc: HashMap<String, f64> = HashMap::new();
+        for area in drainage_areas {
+            if network.find_node(&area.outlet).is_none() {
+                return Err(format!(
+                    "Drainage area {} drains to unknown node {}",
+                    area.id, area.outlet
+                ));
+            }
+            let c = area
+                .runoff_coefficient
+                .ok_or_else(|| format!("Drainage area {} has no runoff coefficient", area.id))?;
+            let tc = area
+                .design_tc(self.config.unit_system, Some(idf))?
+                .max(self.minimum_tc);
+
+            *inlet_ca.entry(area.outlet.clone()).or_insert(0.0) += c * area.area;
+            let node_tc = inlet_tc.entry(area.outlet.clone()).or_insert(tc);
+            *node_tc = node_tc.max(tc);
+        }
+
+        let order = topological_sort_upstream_to_downstream(network)?;
+
+        // First guess: pipes flowing full
+        let mut velocities: HashMap<String, f64> = network
+            .conduits
+            .iter()
+            .filter_map(|c| self.full_flow_velocity(network, c).map(|v| (c.id.clone(), v)))
+            .collect();
+
+        for iteration in 1..=self.config.max_iterations {
+            let (conduits, node_tc) = self.sweep(network, &order, &inlet_ca, &inlet_tc, idf, &velocities)?;
+
+            let mut max_change: f64 = 0.0;
+            for design in &conduits {
+                if let Some(v) = design.velocity {
+                    let previous = velocities.insert(design.conduit_id.clone(), v).unwrap_or(0.0);
+                    max_change = max_change.max((v - previous).abs());
+                }
+            }
+
+            if max_change < self.config.tolerance {
+                return Ok(RationalDesign {
+                    conduits,
+                    node_tc,
+                    iterations: iteration,
+                });
+            }
+        }
+
+        Err(format!(
+            "Rational design did not converge in {} iterations",
+            self.config.max_iterations
+        ))
+    }
+
+    /// One upstream-to-downstream pass using travel times from `velocities`
+    fn sweep(
+        &self,
+        network: &Network,
+        order: &[String],
+        inlet_ca: &HashMap<String, f64>,
+        inlet_tc: &HashMap<String, f64>,
+        idf: &IdfCurve,
+        velocities: &HashMap<String, f64>,
+    ) -> Result<(Vec<RationalConduit>, HashMap<String, f64>), String> {
+        let mut node_ca: HashMap<String, f64> = inlet_ca.clone();
+        let mut node_tc: HashMap<String, f64> = inlet_tc.clone();
+        let mut conduits = Vec::new();
+
+        for node_id in order {
+            let sum_ca = node_ca.get(node_id).copied().unwrap_or(0.0);
+            let tc = node_tc.get(node_id).copied().unwrap_or(self.minimum_tc);
+
+            let downstream = network.downstream_conduits(node_id);
+            if downstream.is_empty() {
+                continue;
+            }
+
+            let intensity = idf
+                .get_intensity(tc)
+                .ok_or_else(|| format!("IDF curve has no intensity for duration {:.1} min", tc))?;
+
+            // A flow divider splits ΣCA in the same proportion as the flow
+            let node = network
+                .find_node(node_id)
+                .ok_or_else(|| format!("Node {} not found", node_id))?;
+            let node_flow = self.rational_flow(sum_ca, intensity);
+            let split = split_outflow(network, node, &downstream, node_flow, self.config.unit_system)?;
+
+            for (conduit, flow) in split {
+                let ca = if node_flow > 0.0 {
+                    sum_ca * flow / node_flow
+                } else {
+                    sum_ca / downstream.len() as f64
+                };
+
+                let velocity = if flow > 0.0 {
+                    self.design_velocity(network, conduit, flow)
+                } else {
+                    velocities.get(&conduit.id).copied()
+                };
+                let travel_time = match velocities.get(&conduit.id) {
+                    Some(&v) if v > 0.0 => conduit.length / (60.0 * v),
+                    _ => 0.0,
+                };
+
+                *node_ca.entry(conduit.to_node.clone()).or_insert(0.0) += ca;
+                let downstream_tc = node_tc.entry(conduit.to_node.clone()).or_insert(0.0);
+                *downstream_tc = downstream_tc.max(tc + travel_time);
+
+                conduits.push(RationalConduit {
+                    conduit_id: conduit.id.clone(),
+                    sum_ca: ca,
+                    time_of_concentration: tc,
+                    intensity,
+                    flow,
+                    velocity,
+                    travel_time,
+                });
+            }
+        }
+
+        Ok((conduits, node_tc))
+    }
+
+    /// Q = C × i × A (US) or Q = C × i × A / 360 (SI)
+    fn rational_flow(&self, sum_ca: f64, intensity: f64) -> f64 {
+        match self.config.unit_system {
+            UnitSystem::US => sum_ca * intensity,
+            UnitSystem::SI => sum_ca * intensity / 360.0,
+        }
+    }
+
+    /// Velocity of the design flow at normal depth, or Q/A when surcharged
+    fn design_velocity(&self, network: &Network, conduit: &Conduit, flow: f64) -> Option<f64> {
+        let reach = self.pipe_reach(network, conduit)?;
+        let full_area = reach.section.full_area()?;
+
+        if flow >= self.direct_step.full_capacity(&reach)? {
+            return Some(flow / full_area);
+        }
+
+        let depth = self.direct_step.normal_depth(&reach, flow)?;
+        let area = reach.section.area(depth);
+        (area > 0.0).then(|| flow / area)
+    }
+
+    fn full_flow_velocity(&self, network: &Network, conduit: &Conduit) -> Option<f64> {
+        let reach = self.pipe_reach(network, conduit)?;
+        Some(self.direct_step.full_capacity(&reach)? / reach.section.full_area()?)
+    }
+
+    /// Section, slope and n of a pipe with positive slope
+    fn pipe_reach(&self, network: &Network, conduit: &Conduit) -> Option<Reach> {
+        let pipe = conduit.pipe.as_ref()?;
+        let section = section::from_conduit(conduit, self.config.unit_system).ok()?;
+        let slope = network.conduit_slope(conduit)?;
+
+        (slope > 0.0).then_some(Reach {
+            section,
+            length: conduit.length,
+            slope,
+            manning_n: pipe.manning_n,
+            downstream_invert: 0.0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::{BoundaryCondition, DiversionMethod, FlowDivider};
+    use crate::rainfall::IdfPoint;
+    use approx::assert_relative_eq;
+    use crate::test_support::{junction, outfall, pipe};
+
+    fn area(id: &str, outlet: &str, acres: f64, c: f64, tc: f64) -> DrainageArea {
+        DrainageArea {
+            id: id.to_string(),
+            name: None,
+            area: acres,
+            outlet: outlet.to_string(),
+            land_use: None,
+            runoff_coefficient: Some(c),
+            time_of_concentration: Some(tc),
+            tc_calculation: None,
+            curve_number: None,
+            geometry: None,
+        }
+    }
+
+    /// MH-1 and MH-2 join at MH-3, which drains to OUT-1 (pipes at 1%)
+    fn network() -> Network {
+        let mut network = Network::new();
+        network.add_node(junction("MH-1", 104.0, 110.0));
+        network.add_node(junction("MH-2", 104.0, 110.0));
+        network.add_node(junction("MH-3", 100.0, 106.0));
+        network.add_node(outfall("OUT-1", 96.0, BoundaryCondition::Free, None));
+        network.add_conduit(pipe("P-1", "MH-1", "MH-3", 400.0, 15.0));
+        network.add_conduit(pipe("P-2", "MH-2", "MH-3", 400.0, 15.0));
+        network.add_conduit(pipe("P-3", "MH-3", "OUT-1", 400.0, 24.0));
+        network
+    }
+
+    fn idf() -> IdfCurve {
+        IdfCurve {
+            return_period: 10.0,
+            equation: None,
+            points: vec![
+                IdfPoint { duration: 5.0, intensity: 7.0 },
+                IdfPoint { duration: 15.0, intensity: 5.0 },
+                IdfPoint { duration: 30.0, intensity: 3.6 },
+                IdfPoint { duration: 60.0, intensity: 2.4 },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_tc_and_sum_ca_accumulate_downstream() {
+        let areas = vec![
+            area("DA-1", "MH-1", 2.0, 0.8, 10.0),
+            area("DA-2", "MH-2", 1.0, 0.9, 15.0),
+            area("DA-3", "MH-3", 0.5, 0.7, 5.0),
+        ];
+        let solver = RationalSolver::new(SolverConfig::us_customary());
+        let design = solver.solve(&network(), &areas, &idf()).unwrap();
+
+        let p1 = design.conduit("P-1").unwrap();
+        assert_relative_eq!(p1.time_of_concentration, 10.0);
+        assert_relative_eq!(p1.intensity, 6.0);
+        assert_relative_eq!(p1.flow, 1.6 * 6.0, epsilon = 1e-9);
+
+        // Longest path to MH-3 governs: max(10 + t1, 15 + t2, 5)
+        let p2 = design.conduit("P-2").unwrap();
+        let p3 = design.conduit("P-3").unwrap();
+        let expected_tc = (10.0 + p1.travel_time).max(15.0 + p2.travel_time);
+        assert_relative_eq!(p3.time_of_concentration, expected_tc, epsilon = 1e-9);
+        assert_relative_eq!(p3.sum_ca, 1.6 + 0.9 + 0.35, epsilon = 1e-9);
+        assert_relative_eq!(p3.flow, p3.sum_ca * idf().get_intensity(expected_tc).unwrap(), epsilon = 1e-9);
+
+        // Travel time consistent with the converged design velocity
+        assert_relative_eq!(p1.travel_time, 400.0 / (60.0 * p1.velocity.unwrap()), epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_minimum_tc_and_results() {
+        let areas = vec![area("DA-1", "MH-1", 1.0, 0.9, 2.0)];
+        let solver = RationalSolver::new(SolverConfig::us_customary()).with_minimum_tc(10.0);
+        let design = solver.solve(&network(), &areas, &idf()).unwrap();
+
+        let p1 = design.conduit("P-1").unwrap();
+        assert_relative_eq!(p1.time_of_concentration, 10.0);
+
+        let mut analysis = Analysis::new(crate::analysis::AnalysisMethod::Rational, "10yr".to_string());
+        analysis.conduit_results = Some(vec![crate::analysis::ConduitResult {
+            conduit_id: "P-1".to_string(),
+            flow: Some(p1.flow),
+            velocity: None,
+            depth: None,
+            capacity_used: None,
+            froude_number: None,
+            flow_regime: None,
+            jump_location: None,
+            headloss: None,
+            profile: None,
+            culvert: None,
+            time_of_peak: None,
+            time_of_concentration: None,
+            intensity: None,
+            sum_ca: None,
+            travel_time: None,
+        }]);
+        design.apply_to(&mut analysis);
+
+        let result = &analysis.conduit_results.unwrap()[0];
+        assert_eq!(result.time_of_concentration, Some(10.0));
+        assert_eq!(result.intensity, Some(6.0));
+        assert_relative_eq!(result.sum_ca.unwrap(), 0.9);
+    }
+
+    #[test]
+    fn test_unknown_outlet() {
+        let areas = vec![area("DA-1", "MH-9", 1.0, 0.9, 10.0)];
+        let solver = RationalSolver::new(SolverConfig::us_customary());
+        assert!(solver.solve(&network(), &areas, &idf()).is_err());
+    }
+
+    #[test]
+    fn test_flow_divider_splits_sum_ca() {
+        // A relief line takes a quarter of the flow leaving MH-3
+        let mut network = network();
+        network.add_node(outfall("OUT-2", 96.0, BoundaryCondition::Free, None));
+        network.add_conduit(pipe("P-R", "MH-3", "OUT-2", 400.0, 15.0));
+        network.nodes[2].divider = Some(FlowDivider {
+            diverted_conduit: "P-R".to_string(),
+            method: DiversionMethod::Fraction { fraction: 0.25 },
+        });
+
+        let areas = vec![
+            area("DA-1", "MH-1", 2.0, 0.8, 10.0),
+            area("DA-2", "MH-2", 1.0, 0.9, 15.0),
+        ];
+        let solver = RationalSolver::new(SolverConfig::us_customary());
+        let design = solver.solve(&network, &areas, &idf()).unwrap();
+
+        let p3 = design.conduit("P-3").unwrap();
+        let relief = design.conduit("P-R").unwrap();
+        assert_relative_eq!(relief.sum_ca, 0.25 * 2.5, epsilon = 1e-9);
+        assert_relative_eq!(p3.sum_ca, 0.75 * 2.5, epsilon = 1e-9);
+        assert_relative_eq!(relief.flow, p3.flow / 3.0, epsilon = 1e-9);
+        assert_relative_eq!(relief.flow, relief.sum_ca * relief.intensity, epsilon = 1e-9);
+    }
+}
@@ -0,0 +1,1341 @@
+//! Unsteady flow routing through drainage networks
+//!
+//! The steady-state [`crate::solver::HglSolver`] evaluates the network once at the
+//! peak rational flow. This module instead steps through time, routing inflow
+//! hydrographs through the conduits and nodes of a [`Network`], so that
+//! non-coincident peaks, pipe storage, surcharge duration and flood volume can be
+//! evaluated.
+//!
+//! Two routing methods are available:
+//!
+//! - **Kinematic wave**: each pipe is a nonlinear reservoir whose outflow is the
+//!   Manning normal flow for its stored volume. Flow only moves downstream and
+//!   inflow in excess of pipe capacity is lost as flooding at the upstream node.
+//!   Nodes have no storage and backwater is not represented.
+//! - **Dynamic wave**: a link-node scheme. Pipe flows are updated from the
+//!   momentum equation (local inertia, water surface slope and Manning friction,
+//!   convective acceleration neglected) and node heads from continuity using the
+//!   structure plan area plus half the water surface of each connected pipe.
+//!   Backwater, surcharge and reverse flow are represented.
+//!
+//! Tidal outfalls follow their tide curve through the storm, offset by
+//! `OutfallProperties::storm_start`. A pipe discharging to an outfall with a
+//! flap gate is closed whenever the outfall stage is at or above the water
+//! level in the pipe, so runoff is held in the system until the tide falls;
+//! [`RoutingResult::storage`] shows the volume building up. Kinematic wave
+//! routing has no backwater and ignores both.
+//!
+//! Node plan area comes from `JunctionProperties::diameter` (default 4 ft / 1.2 m).
+//! Pipes of every shape are routed with their own cross section from
+//! [`crate::section::from_conduit`]. Conduits without pipe geometry (gutters and
+//! channels) transfer their inflow downstream without attenuation.
+//!
+//! ## References
+//!
+//! FHWA HEC-22 (4th Edition, 2024), Chapter 9: Storm Drain Conduits;
+//! Rossman, L.A. (2017), *SWMM Reference Manual Volume II - Hydraulics*, EPA/600/R-17/111
+
+use crate::analysis::{
+    Analysis, AnalysisMethod, ConduitResult, NodeResult, Severity, Violation,
+};
+use crate::conduit::Conduit;
+use crate::hydrograph::Hydrograph;
+use crate::network::Network;
+use crate::node::{BoundaryCondition, Node};
+use crate::project::UnitSystem;
+use crate::section::{self, CrossSection};
+use crate::solver::{self, topological_sort_upstream_to_downstream};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// Smallest slope used in Manning's equation (ft/ft or m/m)
+const MIN_SLOPE: f64 = 0.0001;
+
+/// Golden-section iterations for the depth of maximum normal flow
+const MAX_FLOW_ITERATIONS: usize = 60;
+
+/// Under-relaxation factor for dynamic wave head iterations
+const RELAXATION: f64 = 0.5;
+
+/// Unsteady routing method
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoutingMethod {
+    /// Kinematic wave (nonlinear reservoir per conduit, no backwater)
+    KinematicWave,
+    /// Dynamic wave (link-node momentum and continuity)
+    DynamicWave,
+}
+
+impl RoutingMethod {
+    /// Analysis method recorded in the results
+    pub fn analysis_method(&self) -> AnalysisMethod {
+        match self {
+            RoutingMethod::KinematicWave => AnalysisMethod::KinematicWave,
+            RoutingMethod::DynamicWave => AnalysisMethod::DynamicWave,
+        }
+    }
+}
+
+/// Unsteady routing configuration
+pub struct RoutingConfig {
+    /// Unit system
+    pub unit_system: UnitSystem,
+    /// Gravitational constant (32.17 for US, 9.81 for SI)
+    pub gravity: f64,
+    /// Manning's constant (1.486 for US, 1.0 for SI)
+    pub manning_k: f64,
+    /// Routing method
+    pub method: RoutingMethod,
+    /// Routing time step (seconds); dynamic wave may take shorter steps for stability
+    pub time_step: f64,
+    /// Interval between reported time series values (seconds)
+    pub report_step: f64,
+    /// Simulation duration (minutes); `None` runs one hour past the last inflow ordinate
+    pub duration: Option<f64>,
+    /// Structure diameter used when a node does not specify one (ft or m)
+    pub default_node_diameter: f64,
+    /// Maximum head iterations per dynamic wave step
+    pub max_trials: usize,
+    /// Head convergence tolerance for dynamic wave (ft or m)
+    pub head_tolerance: f64,
+}
+
+impl RoutingConfig {
+    /// Create configuration for US customary units
+    pub fn us_customary() -> Self {
+        Self {
+            unit_system: UnitSystem::US,
+            gravity: 32.17,
+            manning_k: 1.486,
+            method: RoutingMethod::DynamicWave,
+            time_step: 5.0,
+            report_step: 60.0,
+            duration: None,
+            default_node_diameter: 4.0,
+            max_trials: 8,
+            head_tolerance: 0.005,
+        }
+    }
+
+    /// Create configuration for SI metric units
+    pub fn si_metric() -> Self {
+        Self {
+            unit_system: UnitSystem::SI,
+            gravity: 9.81,
+            manning_k: 1.0,
+            method: RoutingMethod::DynamicWave,
+            time_step: 5.0,
+            report_step: 60.0,
+            duration: None,
+            default_node_diameter: 1.2,
+            max_trials: 8,
+            head_tolerance: 0.0015,
+        }
+    }
+
+    /// Set the routing method
+    pub fn with_method(mut self, method: RoutingMethod) -> Self {
+        self.method = method;
+        self
+    }
+}
+
+/// Reported time series at a node
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NodeTimeSeries {
+    /// Node ID
+    #[serde(rename = "nodeId")]
+    pub node_id: String,
+
+    /// Hydraulic grade line elevation (ft or m)
+    pub hgl: Vec<f64>,
+
+    /// Water depth above the node invert (ft or m)
+    pub depth: Vec<f64>,
+
+    /// Total inflow, lateral plus upstream conduits (cfs or cms)
+    pub inflow: Vec<f64>,
+
+    /// Rate of overflow to the surface (cfs or cms)
+    pub flooding: Vec<f64>,
+}
+
+/// Reported time series for a conduit
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConduitTimeSeries {
+    /// Conduit ID
+    #[serde(rename = "conduitId")]
+    pub conduit_id: String,
+
+    /// Flow rate, negative for reverse flow (cfs or cms)
+    pub flow: Vec<f64>,
+
+    /// Average flow depth (ft or m)
+    pub depth: Vec<f64>,
+
+    /// Average velocity (ft/s or m/s)
+    pub velocity: Vec<f64>,
+}
+
+/// Result of an unsteady routing run
+#[derive(Debug, Clone)]
+pub struct RoutingResult {
+    /// Report times (minutes from storm start)
+    pub times: Vec<f64>,
+
+    /// Time series at each node
+    pub node_series: Vec<NodeTimeSeries>,
+
+    /// Time series for each conduit
+    pub conduit_series: Vec<ConduitTimeSeries>,
+
+    /// Total lateral inflow volume (ft³ or m³)
+    pub inflow_volume: f64,
+
+    /// Total volume discharged at outfalls (ft³ or m³)
+    pub outflow_volume: f64,
+
+    /// Total volume lost to flooding (ft³ or m³)
+    pub flood_volume: f64,
+
+    /// Volume held in structures and pipes at each report time (ft³ or m³)
+    pub storage: Vec<f64>,
+
+    /// Peak and summary values
+    pub analysis: Analysis,
+}
+
+impl RoutingResult {
+    /// Time series for a node
+    pub fn node(&self, node_id: &str) -> Option<&NodeTimeSeries> {
+        self.node_series.iter().find(|s| s.node_id == node_id)
+    }
+
+    /// Time series for a conduit
+    pub fn conduit(&self, conduit_id: &str) -> Option<&ConduitTimeSeries> {
+        self.conduit_series.iter().find(|s| s.conduit_id == conduit_id)
+    }
+}
+
+/// Routing state of a node
+struct NodeState<'a> {
+    node: &'a Node,
+    /// Structure plan area (sq ft or sq m)
+    plan_area: f64,
+    /// Highest crown elevation of connected pipes
+    crown: Option<f64>,
+    head: f64,
+    inflow: f64,
+    overflow: f64,
+    lateral: Option<&'a Hydrograph>,
+    upstream: Vec<usize>,
+    downstream: Vec<usize>,
+    // Summary statistics
+    max_head: f64,
+    time_of_max: f64,
+    surcharge_time: f64,
+    flood_volume: f64,
+}
+
+/// Routing state of a conduit
+struct LinkState {
+    id: String,
+    from: usize,
+    to: usize,
+    length: f64,
+    slope: f64,
+    manning_n: f64,
+    /// Pipe cross section; `None` for conduits routed without storage
+    section: Option<Box<dyn CrossSection>>,
+    /// Crown height of the pipe (ft or m); zero without a section
+    rise: f64,
+    upstream_invert: f64,
+    downstream_invert: f64,
+    /// Depth at which the pipe carries its largest normal flow (ft or m)
+    max_flow_depth: f64,
+    /// Largest normal flow the pipe can carry (cfs or cms)
+    max_flow: f64,
+    /// Full-flow capacity (cfs or cms)
+    full_flow: f64,
+    /// Discharges through a flap gate that closes against reverse head
+    flap_gate: bool,
+    flow: f64,
+    inflow: f64,
+    area: f64,
+    depth: f64,
+    /// Flow sensitivity to head difference, dQ/dH (dynamic wave)
+    dq_dh: f64,
+    // Summary statistics
+    max_flow_seen: f64,
+    time_of_max: f64,
+    depth_at_max: f64,
+    velocity_at_max: f64,
+}
+
+/// Unsteady hydrograph routing solver
+pub struct UnsteadySolver {
+    config: RoutingConfig,
+}
+
+impl UnsteadySolver {
+    /// Create a new solver with the given configuration
+    pub fn new(config: RoutingConfig) -> Self {
+        Self { config }
+    }
+
+    /// Route inflow hydrographs through the network
+    ///
+    /// # Arguments
+    /// * `network` - The drainage network to route through
+    /// * `inflows` - Lateral inflow hydrograph at each node, keyed by node ID
+    /// * `design_storm_id` - ID of the design storm being analyzed
+    ///
+    /// # Returns
+    /// Reported time series plus an [`Analysis`] holding the peak HGL, flow and
+    /// depth at each element, time of peak, surcharge duration and flood volume
+    pub fn route(
+        &self,
+        network: &Network,
+        inflows: &HashMap<String, Hydrograph>,
+        design_storm_id: String,
+    ) -> Result<RoutingResult, String> {
+        network.validate_connectivity()?;
+        if network.outfalls().is_empty() {
+            return Err("Network has no outfall nodes".to_string());
+        }
+
+        let (mut nodes, mut links) = self.build_state(network, inflows)?;
+        let order: Vec<usize> = match self.config.method {
+            RoutingMethod::KinematicWave => topological_sort_upstream_to_downstream(network)?
+                .iter()
+                .filter_map(|id| nodes.iter().position(|n| &n.node.id == id))
+                .collect(),
+            RoutingMethod::DynamicWave => (0..nodes.len()).collect(),
+        };
+
+        let end_time = match self.config.duration {
+            Some(minutes) => minutes * 60.0,
+            None => {
+                let last_inflow = inflows.values().map(|h| h.end_time()).fold(0.0, f64::max);
+                (last_inflow + 60.0) * 60.0
+            }
+        };
+
+        let mut times = Vec::new();
+        let mut node_series: Vec<NodeTimeSeries> = nodes
+            .iter()
+            .map(|n| NodeTimeSeries {
+                node_id: n.node.id.clone(),
+                hgl: Vec::new(),
+                depth: Vec::new(),
+                inflow: Vec::new(),
+                flooding: Vec::new(),
+            })
+            .collect();
+        let mut conduit_series: Vec<ConduitTimeSeries> = links
+            .iter()
+            .map(|l| ConduitTimeSeries {
+                conduit_id: l.id.clone(),
+                flow: Vec::new(),
+                depth: Vec::new(),
+                velocity: Vec::new(),
+            })
+            .collect();
+
+        let mut storage = Vec::new();
+        let mut inflow_volume = 0.0;
+        let mut outflow_volume = 0.0;
+        let mut t = 0.0;
+        let mut next_report = 0.0;
+
+        while t < end_time - 1e-9 {
+            if t >= next_report - 1e-9 {
+                times.push(t / 60.0);
+                record(&nodes, &links, &mut node_series, &mut conduit_series);
+                storage.push(stored_volume(&nodes, &links));
+                next_report += self.config.report_step;
+            }
+
+            let mut dt = self.config.time_step.min(end_time - t);
+            if self.config.method == RoutingMethod::DynamicWave {
+                dt = dt.min(self.courant_step(&links));
+            }
+
+            let laterals: Vec<f64> = nodes
+                .iter()
+                .map(|n| {
+                    n.lateral
+                        .map(|h| 0.5 * (h.flow_at(t / 60.0) + h.flow_at((t + dt) / 60.0)))
+                        .unwrap_or(0.0)
+                })
+                .collect();
+            inflow_volume += laterals.iter().sum::<f64>() * dt;
+
+            match self.config.method {
+                RoutingMethod::KinematicWave => {
+                    self.kinematic_step(network, &mut nodes, &mut links, &order, &laterals, t + dt, dt)?
+                }
+                RoutingMethod::DynamicWave => {
+                    self.dynamic_step(network, &mut nodes, &mut links, &laterals, t + dt, dt)?
+                }
+            }
+
+            t += dt;
+
+            for state in nodes.iter_mut() {
+                if state.node.is_outfall() {
+                    outflow_volume += state.inflow * dt;
+                }
+                state.flood_volume += state.overflow * dt;
+                if state.head > state.max_head {
+                    state.max_head = state.head;
+                    state.time_of_max = t;
+                }
+                if let Some(crown) = state.crown {
+                    if !state.node.is_outfall() && state.head > crown + 1e-6 {
+                        state.surcharge_time += dt;
+                    }
+                }
+            }
+            for link in links.iter_mut() {
+                if link.flow.abs() > link.max_flow_seen {
+                    link.max_flow_seen = link.flow.abs();
+                    link.time_of_max = t;
+                    link.depth_at_max = link.depth;
+                    link.velocity_at_max = if link.area > 0.0 { link.flow.abs() / link.area } else { 0.0 };
+                }
+            }
+        }
+        times.push(t / 60.0);
+        record(&nodes, &links, &mut node_series, &mut conduit_series);
+        storage.push(stored_volume(&nodes, &links));
+
+        let analysis = self.summarize(&nodes, &links, design_storm_id);
+        let flood_volume = nodes.iter().map(|n| n.flood_volume).sum();
+
+        Ok(RoutingResult {
+            times,
+            node_series,
+            conduit_series,
+            inflow_volume,
+            outflow_volume,
+            flood_volume,
+            storage,
+            analysis,
+        })
+    }
+
+    /// Build initial routing state from the network (dry pipes, tailwater at outfalls)
+    fn build_state<'a>(
+        &self,
+        network: &'a Network,
+        inflows: &'a HashMap<String, Hydrograph>,
+    ) -> Result<(Vec<NodeState<'a>>, Vec<LinkState>), String> {
+        let index: HashMap<&str, usize> = network
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.id.as_str(), i))
+            .collect();
+
+        let mut links = Vec::new();
+        for conduit in &network.conduits {
+            let from = index[conduit.from_node.as_str()];
+            let to = index[conduit.to_node.as_str()];
+            let upstream_invert = conduit
+                .upstream_invert
+                .unwrap_or(network.nodes[from].invert_elevation);
+            let downstream_invert = conduit
+                .downstream_invert
+                .unwrap_or(network.nodes[to].invert_elevation);
+            let slope = conduit
+                .effective_slope()
+                .unwrap_or((upstream_invert - downstream_invert) / conduit.length)
+                .max(MIN_SLOPE);
+
+            let (section, manning_n) = match conduit.pipe {
+                Some(ref pipe) => (Some(section::from_conduit(conduit, self.config.unit_system)?), pipe.manning_n),
+                None => (None, 0.0),
+            };
+            let rise = match section {
+                Some(ref section) => section
+                    .crown()
+                    .ok_or_else(|| format!("Pipe {} is not a closed section", conduit.id))?,
+                None => 0.0,
+            };
+            let (max_flow_depth, max_flow, full_flow) = match section {
+                Some(ref section) => {
+                    let depth = max_flow_depth(section.as_ref(), rise);
+                    (
+                        depth,
+                        self.normal_flow(section.as_ref(), depth, slope, manning_n),
+                        section.conveyance(rise, manning_n, self.config.manning_k) * slope.sqrt(),
+                    )
+                }
+                None => (0.0, f64::INFINITY, f64::INFINITY),
+            };
+            let flap_gate = network.nodes[to]
+                .outfall
+                .as_ref()
+                .is_some_and(|o| o.flap_gate == Some(true));
+
+            links.push(LinkState {
+                id: conduit.id.clone(),
+                from,
+                to,
+                length: conduit.length,
+                slope,
+                manning_n,
+                section,
+                rise,
+                upstream_invert,
+                downstream_invert,
+                max_flow_depth,
+                max_flow,
+                full_flow,
+                flap_gate,
+                flow: 0.0,
+                inflow: 0.0,
+                area: 0.0,
+                depth: 0.0,
+                dq_dh: 0.0,
+                max_flow_seen: 0.0,
+                time_of_max: 0.0,
+                depth_at_max: 0.0,
+                velocity_at_max: 0.0,
+            });
+        }
+
+        let mut nodes = Vec::new();
+        for (i, node) in network.nodes.iter().enumerate() {
+            let diameter = node
+                .junction
+                .as_ref()
+                .and_then(|j| j.diameter)
+                .unwrap_or(self.config.default_node_diameter);
+
+            let upstream: Vec<usize> = (0..links.len()).filter(|&l| links[l].to == i).collect();
+            let downstream: Vec<usize> = (0..links.len()).filter(|&l| links[l].from == i).collect();
+
+            let crown = upstream
+                .iter()
+                .filter(|&&l| links[l].section.is_some())
+                .map(|&l| links[l].downstream_invert + links[l].rise)
+                .chain(
+                    downstream
+                        .iter()
+                        .filter(|&&l| links[l].section.is_some())
+                        .map(|&l| links[l].upstream_invert + links[l].rise),
+                )
+                .fold(None, |acc: Option<f64>, c| Some(acc.map_or(c, |a| a.max(c))));
+
+            let head = if node.is_outfall() {
+                self.outfall_head(node, 0.0, None, 0.0)?
+            } else {
+                node.invert_elevation
+            };
+
+            nodes.push(NodeState {
+                node,
+                plan_area: PI * diameter * diameter / 4.0,
+                crown,
+                head,
+                inflow: 0.0,
+                overflow: 0.0,
+                lateral: inflows.get(&node.id),
+                upstream,
+                downstream,
+                max_head: head,
+                time_of_max: 0.0,
+                surcharge_time: 0.0,
+                flood_volume: 0.0,
+            });
+        }
+
+        Ok((nodes, links))
+    }
+
+    /// Advance one kinematic wave step to `time` (seconds), processing nodes
+    /// upstream to downstream
+    #[allow(clippy::too_many_arguments)]
+    fn kinematic_step(
+        &self,
+        network: &Network,
+        nodes: &mut [NodeState],
+        links: &mut [LinkState],
+        order: &[usize],
+        laterals: &[f64],
+        time: f64,
+        dt: f64,
+    ) -> Result<(), String> {
+        for &i in order {
+            let inflow = laterals[i] + nodes[i].upstream.iter().map(|&l| links[l].flow).sum::<f64>();
+            nodes[i].inflow = inflow;
+            nodes[i].overflow = 0.0;
+
+            if nodes[i].node.is_outfall() {
+                let outlet = nodes[i].upstream.first().map(|&l| &links[l]);
+                nodes[i].head = self
+                    .outfall_head(nodes[i].node, inflow, outlet, time)
+                    .unwrap_or(nodes[i].node.invert_elevation);
+                continue;
+            }
+
+            if nodes[i].downstream.is_empty() {
+                // Dead end: nothing can leave the node
+                nodes[i].overflow = inflow;
+                continue;
+            }
+
+            let mut overflow = 0.0;
+            let mut head = nodes[i].node.invert_elevation;
+
+            for (l, share) in self.split_outflow(network, &nodes[i], inflow)? {
+                let link = &mut links[l];
+                if link.section.is_some() {
+                    let accepted = share.min(link.max_flow);
+                    overflow += share - accepted;
+                    overflow += self.kinematic_link(link, accepted, dt) / dt;
+                    head = head.max(link.upstream_invert + link.depth);
+                } else {
+                    link.inflow = share;
+                    link.flow = share;
+                }
+            }
+
+            nodes[i].overflow = overflow;
+            if overflow > 0.0 {
+                let node = nodes[i].node;
+                head = node
+                    .rim_elevation
+                    .or(nodes[i].crown)
+                    .unwrap_or(head)
+                    .max(head);
+            }
+            nodes[i].head = head;
+        }
+        Ok(())
+    }
+
+    /// Share a node's outflow among its downstream links, by the node's flow
+    /// divider if it has one and equally otherwise
+    fn split_outflow(&self, network: &Network, state: &NodeState, outflow: f64) -> Result<Vec<(usize, f64)>, String> {
+        let conduits: Vec<&Conduit> = state.downstream.iter().map(|&l| &network.conduits[l]).collect();
+        let split = solver::split_outflow(network, state.node, &conduits, outflow, self.config.unit_system)?;
+        Ok(split
+            .into_iter()
+            .filter_map(|(conduit, flow)| {
+                let k = conduits.iter().position(|c| c.id == conduit.id)?;
+                Some((state.downstream[k], flow))
+            })
+            .collect())
+    }
+
+    /// Route one step through a kinematic wave pipe
+    ///
+    /// Solves the implicit storage equation for the new flow area:
+    ///
+    /// ```text
+    /// A₂L + (Δt/2)Q(A₂) = A₁L + (Δt/2)(I₁ + I₂) - (Δt/2)Q₁
+    /// ```
+    ///
+    /// Returns any volume that could not be stored in a full pipe (ft³ or m³).
+    fn kinematic_link(&self, link: &mut LinkState, inflow: f64, dt: f64) -> f64 {
+        let rhs = link.area * link.length + 0.5 * dt * (link.inflow + inflow) - 0.5 * dt * link.flow;
+        link.inflow = inflow;
+
+        let Some(section) = link.section.as_deref() else {
+            return 0.0;
+        };
+        let full_area = section.area(link.rise);
+        let storage = |a: f64| a * link.length + 0.5 * dt * self.kinematic_flow(link, section, a);
+
+        let mut excess = 0.0;
+        let area = if rhs <= 0.0 {
+            0.0
+        } else if storage(full_area) <= rhs {
+            excess = rhs - storage(full_area);
+            full_area
+        } else {
+            let (mut low, mut high) = (0.0, full_area);
+            for _ in 0..50 {
+                let mid = 0.5 * (low + high);
+                if storage(mid) < rhs {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            0.5 * (low + high)
+        };
+
+        link.area = area;
+        link.flow = self.kinematic_flow(link, section, area);
+        link.depth = section.depth_for_area(area);
+        excess
+    }
+
+    /// Normal flow for a given flow area, held at the maximum beyond the
+    /// depth of maximum flow (0.938D in a circular pipe)
+    fn kinematic_flow(&self, link: &LinkState, section: &dyn CrossSection, area: f64) -> f64 {
+        let depth = section.depth_for_area(area);
+        if depth >= link.max_flow_depth {
+            link.max_flow
+        } else {
+            self.normal_flow(section, depth, link.slope, link.manning_n)
+        }
+    }
+
+    /// Advance one dynamic wave step to `time` (seconds)
+    ///
+    /// Each trial updates every pipe flow from the momentum equation with
+    /// implicit friction,
+    ///
+    /// ```text
+    /// Q₂ = [Q₁ + (gAΔt/L)(H_up - H_dn)] / [1 + gΔt n²|Q₁| / (k²AR^(4/3))]
+    /// ```
+    ///
+    /// then solves node continuity A_s(H₂ - H₁) = Δt ΣQ with a Newton step that
+    /// uses the sensitivity dQ/dH of the connected pipes. Heads are
+    /// under-relaxed between trials until they change by less than the tolerance.
+    fn dynamic_step(
+        &self,
+        network: &Network,
+        nodes: &mut [NodeState],
+        links: &mut [LinkState],
+        laterals: &[f64],
+        time: f64,
+        dt: f64,
+    ) -> Result<(), String> {
+        let old_heads: Vec<f64> = nodes.iter().map(|n| n.head).collect();
+        let old_flows: Vec<f64> = links.iter().map(|l| l.flow).collect();
+
+        for trial in 0..self.config.max_trials.max(1) {
+            for (l, link) in links.iter_mut().enumerate() {
+                if link.section.is_some() {
+                    let upstream_head = nodes[link.from].head;
+                    let downstream_head = nodes[link.to].head;
+                    self.dynamic_link(link, old_flows[l], upstream_head, downstream_head, dt);
+                }
+            }
+
+            // Conduits without storage pass on whatever reaches their upstream node
+            for i in 0..nodes.len() {
+                if nodes[i].downstream.iter().all(|&l| links[l].section.is_some()) {
+                    continue;
+                }
+                let inflow = laterals[i] + nodes[i].upstream.iter().map(|&u| links[u].flow).sum::<f64>();
+                for (l, share) in self.split_outflow(network, &nodes[i], inflow.max(0.0))? {
+                    if links[l].section.is_none() {
+                        links[l].flow = share;
+                        links[l].inflow = share;
+                        links[l].dq_dh = 0.0;
+                    }
+                }
+            }
+
+            let mut max_change: f64 = 0.0;
+            for (i, state) in nodes.iter_mut().enumerate() {
+                let inflow = laterals[i] + state.upstream.iter().map(|&l| links[l].flow).sum::<f64>();
+                let outflow: f64 = state.downstream.iter().map(|&l| links[l].flow).sum();
+                state.inflow = inflow;
+
+                if state.node.is_outfall() {
+                    let outlet = state.upstream.first().map(|&l| &links[l]);
+                    let head = self
+                        .outfall_head(state.node, inflow - outflow, outlet, time)
+                        .unwrap_or(state.node.invert_elevation);
+                    max_change = max_change.max((head - state.head).abs());
+                    state.head = head;
+                    state.overflow = 0.0;
+                    continue;
+                }
+
+                let surface_area = self.surface_area(state, links);
+                let conductance: f64 = state
+                    .upstream
+                    .iter()
+                    .chain(state.downstream.iter())
+                    .map(|&l| links[l].dq_dh)
+                    .sum();
+
+                let mut head = state.head
+                    + (surface_area * (old_heads[i] - state.head) + (inflow - outflow) * dt)
+                        / (surface_area + dt * conductance);
+                if trial > 0 {
+                    head = RELAXATION * head + (1.0 - RELAXATION) * state.head;
+                }
+                head = head.max(state.node.invert_elevation);
+
+                state.overflow = 0.0;
+                if let Some(rim) = state.node.rim_elevation {
+                    if head > rim {
+                        // Whatever the structure cannot hold at the rim is lost
+                        let stored = surface_area * (rim - old_heads[i]) / dt;
+                        state.overflow = (inflow - outflow - stored).max(0.0);
+                        head = rim;
+                    }
+                }
+
+                max_change = max_change.max((head - state.head).abs());
+                state.head = head;
+            }
+
+            if trial > 0 && max_change < self.config.head_tolerance {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Update a single pipe for one dynamic wave trial
+    ///
+    /// A flap gate closed by the downstream stage holds the pipe as a level
+    /// pool at the upstream head with no flow.
+    fn dynamic_link(
+        &self,
+        link: &mut LinkState,
+        old_flow: f64,
+        upstream_head: f64,
+        downstream_head: f64,
+        dt: f64,
+    ) {
+        let Some(section) = link.section.as_deref() else {
+            return;
+        };
+        let g = self.config.gravity;
+        let rise = link.rise;
+        let h1 = upstream_head.max(link.upstream_invert);
+        let closed = link.flap_gate && downstream_head >= h1;
+        let h2 = if closed { h1 } else { downstream_head }.max(link.downstream_invert);
+        let y1 = (h1 - link.upstream_invert).min(rise);
+        let y2 = (h2 - link.downstream_invert).min(rise);
+        let depth = 0.5 * (y1 + y2);
+
+        link.depth = depth;
+        if depth <= 1e-6 * rise {
+            link.flow = 0.0;
+            link.area = 0.0;
+            link.dq_dh = 0.0;
+            return;
+        }
+
+        let area = section.area(depth);
+        let radius = section.hydraulic_radius(depth);
+
+        if closed {
+            link.flow = 0.0;
+            link.inflow = 0.0;
+            link.area = area;
+            link.dq_dh = 0.0;
+            return;
+        }
+
+        let friction = g * dt * link.manning_n.powi(2) * old_flow.abs()
+            / (self.config.manning_k.powi(2) * area * radius.powf(4.0 / 3.0));
+        let denominator = 1.0 + friction;
+        let mut flow = (old_flow + g * area * dt * (h1 - h2) / link.length) / denominator;
+        let mut dq_dh = g * area * dt / (link.length * denominator);
+
+        // A dry end cannot supply flow, and a flap gate never passes reverse flow
+        if (flow > 0.0 && y1 <= 0.0) || (flow < 0.0 && (y2 <= 0.0 || link.flap_gate)) {
+            flow = 0.0;
+            dq_dh = 0.0;
+        }
+
+        // Flow cannot exceed normal flow at the upstream depth unless surcharged
+        if flow > 0.0 && y1 < rise {
+            let normal = self.normal_flow(section, y1, link.slope, link.manning_n);
+            if flow > normal {
+                flow = normal;
+                dq_dh = 0.0;
+            }
+        }
+
+        link.flow = flow;
+        link.inflow = flow;
+        link.area = area;
+        link.dq_dh = dq_dh;
+    }
+
+    /// Node surface area: structure plan area plus half the water surface of
+    /// each connected pipe
+    fn surface_area(&self, state: &NodeState, links: &[LinkState]) -> f64 {
+        let pipe_area: f64 = state
+            .upstream
+            .iter()
+            .chain(state.downstream.iter())
+            .filter_map(|&l| {
+                let link = &links[l];
+                link.section
+                    .as_ref()
+                    .map(|section| 0.5 * link.length * section.top_width(link.depth))
+            })
+            .sum();
+        let d = self.config.default_node_diameter;
+        (state.plan_area + pipe_area).max(PI * d * d / 4.0)
+    }
+
+    /// Largest stable dynamic wave step from the Courant condition
+    fn courant_step(&self, links: &[LinkState]) -> f64 {
+        links
+            .iter()
+            .filter_map(|link| {
+                let section = link.section.as_ref()?;
+                if link.area <= 0.0 {
+                    return None;
+                }
+                let top_width = section.top_width(link.depth);
+                let hydraulic_depth = if top_width > 0.0 { link.area / top_width } else { link.rise };
+                let celerity = (link.flow / link.area).abs()
+                    + (self.config.gravity * hydraulic_depth).sqrt();
+                Some(0.75 * link.length / celerity)
+            })
+            .fold(self.config.time_step, f64::min)
+            .max(0.5)
+    }
+
+    /// Water surface elevation at an outfall
+    ///
+    /// Free outfalls take the smaller of critical and normal depth in the
+    /// connecting pipe; normal-depth outfalls use the specified tailwater or
+    /// normal depth; fixed-stage outfalls use the specified tailwater; tidal
+    /// outfalls read the tide curve at `time` (seconds), falling back to the
+    /// specified tailwater without one.
+    fn outfall_head(
+        &self,
+        outfall: &Node,
+        inflow: f64,
+        outlet: Option<&LinkState>,
+        time: f64,
+    ) -> Result<f64, String> {
+        let props = outfall
+            .outfall
+            .as_ref()
+            .ok_or_else(|| "Node is not an outfall".to_string())?;
+        let invert = outfall.invert_elevation;
+
+        let pipe_depths = outlet.and_then(|link| {
+            let section = link.section.as_ref()?;
+            if inflow <= 0.0 {
+                return None;
+            }
+            let normal = section.normal_depth(inflow, link.slope, link.manning_n, self.config.manning_k)?;
+            let critical = section.critical_depth(inflow, self.config.gravity);
+            Some((critical, normal, link.downstream_invert))
+        });
+
+        match props.boundary_condition {
+            BoundaryCondition::Free => Ok(pipe_depths
+                .map(|(yc, yn, pipe_invert)| pipe_invert + yc.min(yn))
+                .unwrap_or(invert)
+                .max(invert)),
+            BoundaryCondition::NormalDepth => Ok(props.tailwater_elevation.unwrap_or_else(|| {
+                pipe_depths
+                    .map(|(_, yn, pipe_invert)| pipe_invert + yn)
+                    .unwrap_or(invert)
+            })),
+            BoundaryCondition::FixedStage => props
+                .tailwater_elevation
+                .ok_or_else(|| "Fixed stage outfall missing tailwater elevation".to_string()),
+            BoundaryCondition::Tidal => props
+                .tide_at(time / 60.0)
+                .or(props.tailwater_elevation)
+                .ok_or_else(|| "Tidal outfall missing tidal curve or tailwater elevation".to_string()),
+        }
+    }
+
+    /// Manning normal flow at a given depth
+    fn normal_flow(&self, section: &dyn CrossSection, depth: f64, slope: f64, manning_n: f64) -> f64 {
+        manning_factor(section, depth) * self.config.manning_k / manning_n * slope.sqrt()
+    }
+
+    /// Collect peak values into an analysis
+    fn summarize(&self, nodes: &[NodeState], links: &[LinkState], design_storm_id: String) -> Analysis {
+        let mut analysis = Analysis::new(self.config.method.analysis_method(), design_storm_id);
+
+        let mut node_results = Vec::new();
+        for state in nodes {
+            let node = state.node;
+            let depth = state.max_head - node.invert_elevation;
+            let overtops_rim = node.rim_elevation.is_some_and(|rim| state.max_head > rim);
+
+            node_results.push(NodeResult {
+                node_id: node.id.clone(),
+                hgl: Some(state.max_head),
+                egl: None,
+                depth: Some(depth),
+                velocity: None,
+                flooding: Some(state.flood_volume > 0.0 || overtops_rim),
+                pressure_head: Some(depth),
+                junction_loss: None,
+                time_of_peak: Some(state.time_of_max / 60.0),
+                surcharge_duration: Some(state.surcharge_time / 60.0),
+                surcharge_depth: state
+                    .crown
+                    .filter(|_| !node.is_outfall())
+                    .map(|crown| (state.max_head - crown).max(0.0)),
+                depth_above_rim: node.rim_elevation.map(|rim| (state.max_head - rim).max(0.0)),
+                flood_volume: Some(state.flood_volume),
+                tailwater_control: None,
+            });
+
+            if state.flood_volume > 0.0 {
+                analysis.add_violation(Violation::flooding_violation(
+                    node.id.clone(),
+                    state.flood_volume,
+                    Severity::Error,
+                ));
+            } else if let Some(rim) = node.rim_elevation {
+                if overtops_rim {
+                    analysis.add_violation(Violation::hgl_violation(
+                        node.id.clone(),
+                        state.max_head,
+                        rim,
+                        Severity::Error,
+                    ));
+                }
+            }
+        }
+
+        let conduit_results = links
+            .iter()
+            .map(|link| ConduitResult {
+                conduit_id: link.id.clone(),
+                flow: Some(link.max_flow_seen),
+                velocity: Some(link.velocity_at_max),
+                depth: Some(link.depth_at_max),
+                capacity_used: link
+                    .section
+                    .as_ref()
+                    .map(|_| link.max_flow_seen / link.full_flow),
+                froude_number: None,
+                flow_regime: None,
+                jump_location: None,
+                headloss: None,
+                profile: None,
+                culvert: None,
+                time_of_peak: Some(link.time_of_max / 60.0),
+                time_of_concentration: None,
+                intensity: None,
+                sum_ca: None,
+                travel_time: None,
+            })
+            .collect();
+
+        analysis.node_results = Some(node_results);
+        analysis.conduit_results = Some(conduit_results);
+        analysis
+    }
+}
+
+/// Section factor A R^(2/3) at a depth, not capped at the full value
+fn manning_factor(section: &dyn CrossSection, depth: f64) -> f64 {
+    section.area(depth) * section.hydraulic_radius(depth).powf(2.0 / 3.0)
+}
+
+/// Depth at which a closed section carries its largest normal flow
+///
+/// The section factor A R^(2/3) of a closed conduit peaks just below the
+/// crown, where the wetted perimeter closes faster than the area grows; it
+/// is found by golden-section search over the upper half of the rise.
+fn max_flow_depth(section: &dyn CrossSection, rise: f64) -> f64 {
+    let ratio = (5.0_f64.sqrt() - 1.0) / 2.0;
+    let (mut low, mut high) = (0.5 * rise, rise);
+    for _ in 0..MAX_FLOW_ITERATIONS {
+        let lower = high - ratio * (high - low);
+        let upper = low + ratio * (high - low);
+        if manning_factor(section, lower) < manning_factor(section, upper) {
+            low = lower;
+        } else {
+            high = upper;
+        }
+    }
+    0.5 * (low + high)
+}
+
+/// Append current state to the reported time series
+fn record(
+    nodes: &[NodeState],
+    links: &[LinkState],
+    node_series: &mut [NodeTimeSeries],
+    conduit_series: &mut [ConduitTimeSeries],
+) {
+    for (state, series) in nodes.iter().zip(node_series.iter_mut()) {
+        series.hgl.push(state.head);
+        series.depth.push(state.head - state.node.invert_elevation);
+        series.inflow.push(state.inflow);
+        series.flooding.push(state.overflow);
+    }
+    for (link, series) in links.iter().zip(conduit_series.iter_mut()) {
+        series.flow.push(link.flow);
+        series.depth.push(link.depth);
+        series
+            .velocity
+            .push(if link.area > 0.0 { link.flow / link.area } else { 0.0 });
+    }
+}
+
+/// Water held in structures below their rims and in pipes (ft³ or m³)
+fn stored_volume(nodes: &[NodeState], links: &[LinkState]) -> f64 {
+    let structures: f64 = nodes
+        .iter()
+        .filter(|n| !n.node.is_outfall())
+        .map(|n| n.plan_area * (n.head - n.node.invert_elevation).max(0.0))
+        .sum();
+    let pipes: f64 = links.iter().map(|l| l.area * l.length).sum();
+    structures + pipes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conduit::PipeShape;
+    use crate::hydraulics::ManningsEquation;
+    use crate::hydrograph::HydrographPoint;
+    use crate::node::{DiversionMethod, FlowDivider, TidalPoint};
+    use crate::test_support::{junction, outfall, pipe};
+
+    /// MH-1 -> MH-2 -> MH-3 -> OUT-1, 18" pipes at 0.5%
+    fn chain(boundary: BoundaryCondition, tailwater: Option<f64>) -> Network {
+        let mut network = Network::new();
+        network.add_node(junction("MH-1", 101.5, 108.0));
+        network.add_node(junction("MH-2", 100.0, 107.0));
+        network.add_node(junction("MH-3", 98.5, 106.0));
+        network.add_node(outfall("OUT-1", 97.0, boundary, tailwater));
+        network.add_conduit(pipe("P-1", "MH-1", "MH-2", 300.0, 18.0));
+        network.add_conduit(pipe("P-2", "MH-2", "MH-3", 300.0, 18.0));
+        network.add_conduit(pipe("P-3", "MH-3", "OUT-1", 300.0, 18.0));
+        network
+    }
+
+    fn triangular_inflow(peak: f64) -> HashMap<String, Hydrograph> {
+        let mut inflows = HashMap::new();
+        inflows.insert(
+            "MH-1".to_string(),
+            Hydrograph::new(vec![
+                HydrographPoint { time: 0.0, flow: 0.0 },
+                HydrographPoint { time: 10.0, flow: peak },
+                HydrographPoint { time: 30.0, flow: 0.0 },
+            ]),
+        );
+        inflows
+    }
+
+    #[test]
+    fn test_routing_config() {
+        let config = RoutingConfig::us_customary().with_method(RoutingMethod::KinematicWave);
+        assert_eq!(config.method, RoutingMethod::KinematicWave);
+        assert_eq!(config.method.analysis_method(), AnalysisMethod::KinematicWave);
+        assert_eq!(config.default_node_diameter, 4.0);
+    }
+
+    #[test]
+    fn test_kinematic_wave_attenuates_and_conserves_volume() {
+        let network = chain(BoundaryCondition::Free, None);
+        let solver = UnsteadySolver::new(
+            RoutingConfig::us_customary().with_method(RoutingMethod::KinematicWave),
+        );
+        let result = solver
+            .route(&network, &triangular_inflow(6.0), "10-year".to_string())
+            .unwrap();
+
+        let analysis = &result.analysis;
+        assert_eq!(analysis.method, Some(AnalysisMethod::KinematicWave));
+
+        let conduits = analysis.conduit_results.as_ref().unwrap();
+        let p1 = conduits.iter().find(|c| c.conduit_id == "P-1").unwrap();
+        let p3 = conduits.iter().find(|c| c.conduit_id == "P-3").unwrap();
+
+        // Peak is attenuated and delayed as it moves downstream
+        assert!(p3.flow.unwrap() <= p1.flow.unwrap());
+        assert!(p1.flow.unwrap() < 6.0);
+        assert!(p3.time_of_peak.unwrap() > 10.0);
+        assert!(p3.time_of_peak.unwrap() >= p1.time_of_peak.unwrap());
+
+        // Everything drains to the outfall within the hour after the storm
+        assert_eq!(result.flood_volume, 0.0);
+        let error = (result.inflow_volume - result.outflow_volume).abs() / result.inflow_volume;
+        assert!(error < 0.01, "continuity error {:.3}", error);
+    }
+
+    #[test]
+    fn test_dynamic_wave_reaches_steady_normal_flow() {
+        let network = chain(BoundaryCondition::Free, None);
+        let mut config = RoutingConfig::us_customary();
+        config.duration = Some(60.0);
+        let solver = UnsteadySolver::new(config);
+
+        let mut inflows = HashMap::new();
+        inflows.insert("MH-1".to_string(), Hydrograph::constant(4.0, 60.0));
+        let result = solver.route(&network, &inflows, "steady".to_string()).unwrap();
+
+        let p2 = result.conduit("P-2").unwrap();
+        let final_flow = *p2.flow.last().unwrap();
+        assert!((final_flow - 4.0).abs() < 0.1, "flow {:.3}", final_flow);
+
+        // Middle pipe runs close to normal depth
+        let mannings = ManningsEquation::us_customary();
+        let yn = mannings.normal_depth(4.0, 1.5, 0.005, 0.013, 32.17).unwrap();
+        let final_depth = *p2.depth.last().unwrap();
+        assert!((final_depth - yn).abs() / yn < 0.15, "depth {:.3} vs {:.3}", final_depth, yn);
+    }
+
+    #[test]
+    fn test_kinematic_wave_flow_divider() {
+        // MH-2 diverts a quarter of its outflow to a relief line
+        let mut network = chain(BoundaryCondition::Free, None);
+        network.add_node(outfall("OUT-2", 98.5, BoundaryCondition::Free, None));
+        network.add_conduit(pipe("P-R", "MH-2", "OUT-2", 300.0, 18.0));
+        network.nodes[1].divider = Some(FlowDivider {
+            diverted_conduit: "P-R".to_string(),
+            method: DiversionMethod::Fraction { fraction: 0.25 },
+        });
+
+        let mut config = RoutingConfig::us_customary().with_method(RoutingMethod::KinematicWave);
+        config.duration = Some(60.0);
+        let mut inflows = HashMap::new();
+        inflows.insert("MH-1".to_string(), Hydrograph::constant(4.0, 60.0));
+        let result = UnsteadySolver::new(config)
+            .route(&network, &inflows, "steady".to_string())
+            .unwrap();
+
+        let relief = *result.conduit("P-R").unwrap().flow.last().unwrap();
+        let main = *result.conduit("P-2").unwrap().flow.last().unwrap();
+        assert!((relief - 1.0).abs() < 0.05, "relief flow {:.3}", relief);
+        assert!((main - 3.0).abs() < 0.05, "main flow {:.3}", main);
+    }
+
+    #[test]
+    fn test_box_pipes_route_with_their_own_geometry() {
+        // The circular section peaks at the familiar 0.938D
+        let circular = section::Circular { diameter: 1.5 };
+        assert!((max_flow_depth(&circular, 1.5) / 1.5 - 0.938).abs() < 1e-3);
+
+        // 24 in × 12 in boxes in place of the 18 in pipes
+        let mut network = chain(BoundaryCondition::Free, None);
+        for conduit in &mut network.conduits {
+            let pipe = conduit.pipe.as_mut().unwrap();
+            pipe.shape = PipeShape::Rectangular;
+            pipe.diameter = None;
+            pipe.width = Some(24.0);
+            pipe.height = Some(12.0);
+        }
+        let mut config = RoutingConfig::us_customary();
+        config.duration = Some(60.0);
+        let solver = UnsteadySolver::new(config);
+
+        let mut inflows = HashMap::new();
+        inflows.insert("MH-1".to_string(), Hydrograph::constant(4.0, 60.0));
+        let result = solver.route(&network, &inflows, "steady".to_string()).unwrap();
+
+        let p2 = result.conduit("P-2").unwrap();
+        let final_flow = *p2.flow.last().unwrap();
+        assert!((final_flow - 4.0).abs() < 0.1, "flow {:.3}", final_flow);
+
+        let box_section = section::Rectangular { width: 2.0, height: Some(1.0) };
+        let yn = box_section.normal_depth(4.0, 0.005, 0.013, 1.486).unwrap();
+        let final_depth = *p2.depth.last().unwrap();
+        assert!((final_depth - yn).abs() / yn < 0.15, "depth {:.3} vs {:.3}", final_depth, yn);
+    }
+
+    #[test]
+    fn test_dynamic_wave_backwater_from_tailwater() {
+        let network = chain(BoundaryCondition::FixedStage, Some(100.5));
+        let mut config = RoutingConfig::us_customary();
+        config.duration = Some(30.0);
+        let solver = UnsteadySolver::new(config);
+
+        let mut inflows = HashMap::new();
+        inflows.insert("MH-1".to_string(), Hydrograph::constant(2.0, 30.0));
+        let result = solver.route(&network, &inflows, "tailwater".to_string()).unwrap();
+
+        // MH-3 (invert 98.5, crown 100.0) is held above the tailwater and surcharged
+        let mh3 = result.node("MH-3").unwrap();
+        assert!(*mh3.hgl.last().unwrap() >= 100.5);
+
+        let node_results = result.analysis.node_results.as_ref().unwrap();
+        let mh3 = node_results.iter().find(|n| n.node_id == "MH-3").unwrap();
+        assert!(mh3.surcharge_duration.unwrap() > 20.0);
+    }
+
+    #[test]
+    fn test_dynamic_wave_flooding() {
+        let network = chain(BoundaryCondition::Free, None);
+        let solver = UnsteadySolver::new(RoutingConfig::us_customary());
+
+        // Well over the ~7.4 cfs capacity of an 18" pipe at 0.5%
+        let result = solver
+            .route(&network, &triangular_inflow(30.0), "100-year".to_string())
+            .unwrap();
+
+        assert!(result.flood_volume > 0.0);
+        let mh1 = result
+            .analysis
+            .node_results
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|n| n.node_id == "MH-1")
+            .unwrap();
+        assert_eq!(mh1.flooding, Some(true));
+        assert!(mh1.surcharge_duration.unwrap() > 0.0);
+        assert!(!result
+            .analysis
+            .get_violations_by_type(crate::analysis::ViolationType::Flooding)
+            .is_empty());
+
+        let balance = result.inflow_volume - result.outflow_volume - result.flood_volume;
+        assert!(balance.abs() / result.inflow_volume < 0.05);
+    }
+
+    /// Chain whose outfall sees the tide jump to 104.0 from 30 to 60 minutes
+    fn tidal_chain(flap_gate: bool) -> Network {
+        let mut network = chain(BoundaryCondition::Tidal, None);
+        let outfall = network.nodes.iter_mut().find(|n| n.id == "OUT-1").unwrap();
+        let props = outfall.outfall.as_mut().unwrap();
+        props.tidal_curve = Some(
+            [(0.0, 96.0), (29.0, 96.0), (30.0, 104.0), (60.0, 104.0), (61.0, 96.0), (120.0, 96.0)]
+                .iter()
+                .map(|&(time, elevation)| TidalPoint { time, elevation })
+                .collect(),
+        );
+        props.flap_gate = Some(flap_gate);
+        network
+    }
+
+    #[test]
+    fn test_dynamic_wave_tidal_flap_gate() {
+        let mut config = RoutingConfig::us_customary();
+        config.duration = Some(90.0);
+        let solver = UnsteadySolver::new(config);
+        let mut inflows = HashMap::new();
+        inflows.insert("MH-1".to_string(), Hydrograph::constant(0.5, 90.0));
+
+        let result = solver
+            .route(&tidal_chain(true), &inflows, "tide".to_string())
+            .unwrap();
+        let at = |minutes: f64| result.times.iter().position(|&t| (t - minutes).abs() < 1e-6).unwrap();
+
+        // The gate stays shut while the tide is up and runoff backs up behind it
+        let outfall = result.node("OUT-1").unwrap();
+        assert_eq!(outfall.hgl[at(45.0)], 104.0);
+        for i in at(31.0)..=at(59.0) {
+            assert!(outfall.inflow[i].abs() < 1e-9, "inflow {:.4}", outfall.inflow[i]);
+        }
+        let stored = result.storage[at(59.0)] - result.storage[at(29.0)];
+        assert!(stored > 0.8 * 0.5 * 30.0 * 60.0, "stored {:.0}", stored);
+
+        // Once the tide falls the stored water drains
+        assert!(result.storage[at(89.0)] < result.storage[at(60.0)]);
+        assert_eq!(result.flood_volume, 0.0);
+
+        // Without the gate the tide flows back up the pipe
+        let result = solver
+            .route(&tidal_chain(false), &inflows, "tide".to_string())
+            .unwrap();
+        let p3 = result.conduit("P-3").unwrap();
+        assert!(p3.flow.iter().any(|&q| q < 0.0));
+    }
+}
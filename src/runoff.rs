@@ -0,0 +1,568 @@
+//! Runoff hydrograph generation
+//!
+//! Converts drainage areas and design storms into runoff hydrographs for
+//! unsteady routing and detention design, where runoff volume matters as much
+//! as the peak.
+//!
+//! Three methods are provided:
+//!
+//! - **Modified rational method**: trapezoidal hydrograph from Q = CiA, rising
+//!   over Tc, holding for the storm duration and receding over Tc
+//! - **NRCS curve number with the dimensionless unit hydrograph**: rainfall excess
+//!   from the curve number runoff equation, convolved with the curvilinear NRCS
+//!   unit hydrograph (HEC-22 Section 4.3.1.1)
+//! - **Rational hyetograph**: Q(t) = C × A × (mean intensity over the preceding Tc),
+//!   which reduces to the modified rational method for a uniform storm
+//!
+//! Area is in acres (US) or hectares (SI); Tc is in minutes.
+//!
+//! ## References
+//!
+//! FHWA HEC-22 (4th Edition, 2024), Chapter 4: Urban Hydrologic Procedures;
+//! NRCS (1986), *Urban Hydrology for Small Watersheds*, TR-55
+
+use crate::analysis::DrainageAreaResult;
+use crate::drainage::DrainageArea;
+use crate::hydrograph::{Hydrograph, HydrographPoint};
+use crate::project::UnitSystem;
+use crate::rainfall::DesignStorm;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// NRCS dimensionless curvilinear unit hydrograph (t/tp, q/qp)
+///
+/// NRCS National Engineering Handbook Part 630, Chapter 16, Table 16-1.
+const NRCS_DIMENSIONLESS_UH: [(f64, f64); 33] = [
+    (0.0, 0.000),
+    (0.1, 0.030),
+    (0.2, 0.100),
+    (0.3, 0.190),
+    (0.4, 0.310),
+    (0.5, 0.470),
+    (0.6, 0.660),
+    (0.7, 0.820),
+    (0.8, 0.930),
+    (0.9, 0.990),
+    (1.0, 1.000),
+    (1.1, 0.990),
+    (1.2, 0.930),
+    (1.3, 0.860),
+    (1.4, 0.780),
+    (1.5, 0.680),
+    (1.6, 0.560),
+    (1.7, 0.460),
+    (1.8, 0.390),
+    (1.9, 0.330),
+    (2.0, 0.280),
+    (2.2, 0.207),
+    (2.4, 0.147),
+    (2.6, 0.107),
+    (2.8, 0.077),
+    (3.0, 0.055),
+    (3.2, 0.040),
+    (3.4, 0.029),
+    (3.6, 0.021),
+    (3.8, 0.015),
+    (4.0, 0.011),
+    (4.5, 0.005),
+    (5.0, 0.000),
+];
+
+/// Runoff hydrograph method
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunoffMethod {
+    /// Trapezoidal modified rational hydrograph (needs C, Tc and a storm intensity)
+    ModifiedRational,
+    /// NRCS curve number runoff with the dimensionless unit hydrograph (needs CN, Tc and a hyetograph)
+    ScsUnitHydrograph,
+    /// Rational runoff from a hyetograph, averaged over Tc (needs C, Tc and a hyetograph)
+    RationalHyetograph,
+}
+
+/// Runoff hydrograph generator
+pub struct HydrographGenerator {
+    /// Unit system
+    pub unit_system: UnitSystem,
+    /// Computation time step (minutes)
+    pub time_step: f64,
+}
+
+impl HydrographGenerator {
+    /// Create a generator with the given unit system and time step (minutes)
+    pub fn new(unit_system: UnitSystem, time_step: f64) -> Self {
+        Self { unit_system, time_step }
+    }
+
+    /// Create a generator for US customary units with a 1-minute time step
+    pub fn us_customary() -> Self {
+        Self::new(UnitSystem::US, 1.0)
+    }
+
+    /// Create a generator for SI metric units with a 1-minute time step
+    pub fn si_metric() -> Self {
+        Self::new(UnitSystem::SI, 1.0)
+    }
+
+    /// Generate a runoff hydrograph for a drainage area
+    ///
+    /// The modified rational method uses the storm's peak intensity and duration;
+    /// the other methods use its hyetograph, expanded from the storm's depth,
+    /// duration and distribution when none is given.
+    pub fn generate(
+        &self,
+        area: &DrainageArea,
+        storm: &DesignStorm,
+        method: RunoffMethod,
+    ) -> Result<Hydrograph, String> {
+        match method {
+            RunoffMethod::ModifiedRational => {
+                let intensity = storm.peak_intensity.ok_or_else(|| {
+                    format!("Design storm {} has no peak intensity", storm.id)
+                })?;
+                let duration = storm
+                    .duration
+                    .or(area.time_of_concentration)
+                    .ok_or_else(|| format!("Design storm {} has no duration", storm.id))?;
+                self.modified_rational(area, intensity, duration)
+            }
+            RunoffMethod::ScsUnitHydrograph => self.scs_unit_hydrograph(area, storm),
+            RunoffMethod::RationalHyetograph => self.rational_hyetograph(area, storm),
+        }
+    }
+
+    /// Modified rational method hydrograph
+    ///
+    /// For a storm at least as long as Tc the hydrograph rises to Q = CiA at Tc,
+    /// holds until the end of the storm and recedes over Tc. For a shorter storm
+    /// the peak is reduced to CiA × D/Tc and held from D until Tc.
+    ///
+    /// # Arguments
+    /// * `area` - Drainage area with runoff coefficient and Tc
+    /// * `intensity` - Rainfall intensity for the storm duration (in/hr or mm/hr)
+    /// * `duration` - Storm duration (minutes)
+    pub fn modified_rational(
+        &self,
+        area: &DrainageArea,
+        intensity: f64,
+        duration: f64,
+    ) -> Result<Hydrograph, String> {
+        let c = runoff_coefficient(area)?;
+        let tc = time_of_concentration(area, self.unit_system)?;
+        let q = self.rational_flow(c, intensity, area.area);
+
+        let points = if duration >= tc {
+            vec![(0.0, 0.0), (tc, q), (duration, q), (duration + tc, 0.0)]
+        } else {
+            let peak = q * duration / tc;
+            vec![(0.0, 0.0), (duration, peak), (tc, peak), (duration + tc, 0.0)]
+        };
+
+        Ok(Hydrograph::new(
+            points
+                .into_iter()
+                .map(|(time, flow)| HydrographPoint { time, flow })
+                .collect(),
+        ))
+    }
+
+    /// NRCS curve number runoff convolved with the dimensionless unit hydrograph
+    ///
+    /// Cumulative runoff follows the curve number equation applied to cumulative
+    /// rainfall; the increments over each time step are convolved with the unit
+    /// hydrograph for that step duration.
+    pub fn scs_unit_hydrograph(
+        &self,
+        area: &DrainageArea,
+        storm: &DesignStorm,
+    ) -> Result<Hydrograph, String> {
+        let cn = area
+            .curve_number
+            .ok_or_else(|| format!("Drainage area {} has no curve number", area.id))?;
+        let tc = time_of_concentration(area, self.unit_system)?;
+        let storm = self.with_hyetograph(storm)?;
+        let storm_end = hyetograph_end(&storm)?;
+
+        let steps = (storm_end / self.time_step).ceil() as usize;
+        let mut excess = Vec::with_capacity(steps);
+        let mut previous = 0.0;
+        for k in 1..=steps {
+            let rainfall = storm.cumulative_depth(k as f64 * self.time_step).unwrap_or(0.0);
+            let runoff = self.scs_runoff_depth(rainfall, cn);
+            excess.push(runoff - previous);
+            previous = runoff;
+        }
+
+        let unit = self.unit_hydrograph(area.area, tc);
+        let flows = convolve(&excess, &unit);
+        Ok(Hydrograph::from_ordinates(self.time_step, &flows))
+    }
+
+    /// Rational runoff from a hyetograph
+    ///
+    /// ```text
+    /// Q(t) = C × A × [P(t) - P(t - Tc)] / Tc
+    /// ```
+    ///
+    /// Where P is cumulative rainfall depth, so each ordinate is the rational
+    /// flow for the average intensity over the preceding Tc.
+    pub fn rational_hyetograph(
+        &self,
+        area: &DrainageArea,
+        storm: &DesignStorm,
+    ) -> Result<Hydrograph, String> {
+        let c = runoff_coefficient(area)?;
+        let tc = time_of_concentration(area, self.unit_system)?;
+        let storm = self.with_hyetograph(storm)?;
+        let storm_end = hyetograph_end(&storm)?;
+
+        let steps = ((storm_end + tc) / self.time_step).ceil() as usize;
+        let flows: Vec<f64> = (0..=steps)
+            .map(|k| {
+                let t = k as f64 * self.time_step;
+                let depth = storm.cumulative_depth(t).unwrap_or(0.0)
+                    - storm.cumulative_depth((t - tc).max(0.0)).unwrap_or(0.0);
+                self.rational_flow(c, depth * 60.0 / tc, area.area)
+            })
+            .collect();
+
+        Ok(Hydrograph::from_ordinates(self.time_step, &flows))
+    }
+
+    /// NRCS curve number runoff depth
+    ///
+    /// ```text
+    /// S = 1000/CN - 10        (in)     S = 25400/CN - 254   (mm)
+    /// Ia = 0.2 S
+    /// Q = (P - Ia)² / (P - Ia + S)   for P > Ia, otherwise 0
+    /// ```
+    ///
+    /// # Arguments
+    /// * `rainfall` - Cumulative rainfall depth P (in or mm)
+    /// * `curve_number` - Curve number CN
+    ///
+    /// # Returns
+    /// Cumulative runoff depth Q (in or mm)
+    pub fn scs_runoff_depth(&self, rainfall: f64, curve_number: f64) -> f64 {
+        let retention = match self.unit_system {
+            UnitSystem::US => 1000.0 / curve_number - 10.0,
+            UnitSystem::SI => 25400.0 / curve_number - 254.0,
+        };
+        let abstraction = 0.2 * retention;
+
+        if rainfall <= abstraction {
+            0.0
+        } else {
+            (rainfall - abstraction).powi(2) / (rainfall - abstraction + retention)
+        }
+    }
+
+    /// NRCS unit hydrograph for one unit of runoff depth over the time step
+    ///
+    /// Time to peak is tp = Δt/2 + 0.6 Tc. The curvilinear ordinates are scaled so
+    /// the hydrograph volume equals 1 in (1 mm) over the area; for a unit
+    /// duration near 0.13 Tc the peak matches HEC-22 Equation 4.10,
+    /// q_p = 484 A Q_D / t_p.
+    ///
+    /// # Arguments
+    /// * `area` - Drainage area (acres or hectares)
+    /// * `tc` - Time of concentration (minutes)
+    ///
+    /// # Returns
+    /// Ordinates at each time step starting at t = 0 (cfs/in or cms/mm)
+    pub fn unit_hydrograph(&self, area: f64, tc: f64) -> Vec<f64> {
+        let tp = self.time_step / 2.0 + 0.6 * tc;
+        let last = NRCS_DIMENSIONLESS_UH[NRCS_DIMENSIONLESS_UH.len() - 1].0;
+        let steps = (last * tp / self.time_step).ceil() as usize;
+
+        let mut ordinates: Vec<f64> = (0..=steps)
+            .map(|j| dimensionless_ordinate(j as f64 * self.time_step / tp))
+            .collect();
+
+        let unit_volume = match self.unit_system {
+            UnitSystem::US => area * 43560.0 / 12.0,
+            UnitSystem::SI => area * 10.0,
+        };
+        let shape_volume: f64 = ordinates.iter().sum::<f64>() * self.time_step * 60.0;
+        if shape_volume > 0.0 {
+            let scale = unit_volume / shape_volume;
+            for q in ordinates.iter_mut() {
+                *q *= scale;
+            }
+        }
+
+        ordinates
+    }
+
+    /// Generate hydrographs for every drainage area and combine them at outlet nodes
+    ///
+    /// # Returns
+    /// Tuple of (lateral inflow hydrograph per outlet node, per-area results)
+    pub fn node_inflows(
+        &self,
+        areas: &[DrainageArea],
+        storm: &DesignStorm,
+        method: RunoffMethod,
+    ) -> Result<(HashMap<String, Hydrograph>, Vec<DrainageAreaResult>), String> {
+        let mut inflows: HashMap<String, Hydrograph> = HashMap::new();
+        let mut results = Vec::new();
+
+        for area in areas {
+            let hydrograph = self.generate(area, storm, method)?;
+            let mut result = hydrograph.drainage_area_result(&area.id);
+            if method == RunoffMethod::ModifiedRational {
+                result.intensity = storm.peak_intensity;
+            }
+            results.push(result);
+
+            let combined = match inflows.remove(&area.outlet) {
+                Some(existing) => existing.combine(&hydrograph, self.time_step),
+                None => hydrograph,
+            };
+            inflows.insert(area.outlet.clone(), combined);
+        }
+
+        Ok((inflows, results))
+    }
+
+    /// Borrow the storm if it has a hyetograph, otherwise expand its distribution
+    fn with_hyetograph<'a>(&self, storm: &'a DesignStorm) -> Result<Cow<'a, DesignStorm>, String> {
+        if storm.hyetograph.is_some() {
+            return Ok(Cow::Borrowed(storm));
+        }
+        let mut expanded = storm.clone();
+        expanded.hyetograph = Some(storm.generate_hyetograph(self.time_step)?);
+        Ok(Cow::Owned(expanded))
+    }
+
+    /// Rational flow Q = CiA in cfs (acres, in/hr) or cms (hectares, mm/hr)
+    fn rational_flow(&self, c: f64, intensity: f64, area: f64) -> f64 {
+        match self.unit_system {
+            UnitSystem::US => c * intensity * area,
+            UnitSystem::SI => c * intensity * area / 360.0,
+        }
+    }
+}
+
+impl Hydrograph {
+    /// Summarize as a drainage area result (peak, time of peak and volume)
+    pub fn drainage_area_result(&self, drainage_area_id: &str) -> DrainageAreaResult {
+        let peak = self.peak();
+        DrainageAreaResult {
+            drainage_area_id: drainage_area_id.to_string(),
+            peak_flow: peak.map(|p| p.flow),
+            time_of_peak: peak.map(|p| p.time),
+            total_volume: Some(self.volume()),
+            intensity: None,
+        }
+    }
+}
+
+fn runoff_coefficient(area: &DrainageArea) -> Result<f64, String> {
+    area.runoff_coefficient
+        .ok_or_else(|| format!("Drainage area {} has no runoff coefficient", area.id))
+}
+
+fn time_of_concentration(area: &DrainageArea, unit_system: UnitSystem) -> Result<f64, String> {
+    Some(area.design_tc(unit_system, None)?)
+        .filter(|&tc| tc > 0.0)
+        .ok_or_else(|| format!("Drainage area {} has no time of concentration", area.id))
+}
+
+/// End time of the storm's hyetograph (minutes)
+fn hyetograph_end(storm: &DesignStorm) -> Result<f64, String> {
+    let points = storm
+        .hyetograph
+        .as_ref()
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| format!("Design storm {} has no hyetograph", storm.id))?;
+
+    let last = points[points.len() - 1].time;
+    let block = if points.len() > 1 { last - points[points.len() - 2].time } else { 0.0 };
+    Ok(storm.duration.unwrap_or(0.0).max(last + block))
+}
+
+/// Interpolate the dimensionless unit hydrograph at t/tp
+fn dimensionless_ordinate(ratio: f64) -> f64 {
+    for pair in NRCS_DIMENSIONLESS_UH.windows(2) {
+        let ((t1, q1), (t2, q2)) = (pair[0], pair[1]);
+        if ratio >= t1 && ratio <= t2 {
+            return q1 + (q2 - q1) * (ratio - t1) / (t2 - t1);
+        }
+    }
+    0.0
+}
+
+/// Discrete convolution of runoff increments with unit hydrograph ordinates
+///
+/// Increment k falls in the interval [kΔt, (k+1)Δt], so the response ordinate at
+/// time nΔt is Σ P_k U_(n-k).
+fn convolve(excess: &[f64], unit: &[f64]) -> Vec<f64> {
+    let mut flows = vec![0.0; excess.len() + unit.len()];
+    for (k, &depth) in excess.iter().enumerate() {
+        if depth <= 0.0 {
+            continue;
+        }
+        for (j, &u) in unit.iter().enumerate() {
+            flows[k + j] += depth * u;
+        }
+    }
+    flows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rainfall::HyetographPoint;
+    use approx::assert_relative_eq;
+
+    fn drainage_area(c: Option<f64>, cn: Option<f64>, tc: f64) -> DrainageArea {
+        DrainageArea {
+            id: "DA-001".to_string(),
+            name: None,
+            area: 10.0,
+            outlet: "IN-001".to_string(),
+            land_use: None,
+            runoff_coefficient: c,
+            time_of_concentration: Some(tc),
+            tc_calculation: None,
+            curve_number: cn,
+            geometry: None,
+        }
+    }
+
+    fn block_storm(intensity: f64, duration: f64) -> DesignStorm {
+        let mut storm = DesignStorm::uniform("10yr".to_string(), "10-Year".to_string(), 10.0, intensity);
+        storm.duration = Some(duration);
+        storm.hyetograph = Some(
+            (0..(duration as usize / 5))
+                .map(|k| HyetographPoint { time: k as f64 * 5.0, intensity })
+                .collect(),
+        );
+        storm
+    }
+
+    #[test]
+    fn test_modified_rational_long_storm() {
+        let generator = HydrographGenerator::us_customary();
+        let area = drainage_area(Some(0.5), None, 15.0);
+
+        let hydrograph = generator.modified_rational(&area, 4.0, 30.0).unwrap();
+
+        // Q = 0.5 × 4.0 × 10 = 20 cfs held from Tc to the end of the storm
+        assert_relative_eq!(hydrograph.peak_flow(), 20.0);
+        assert_relative_eq!(hydrograph.flow_at(20.0), 20.0);
+        assert_eq!(hydrograph.end_time(), 45.0);
+
+        // Trapezoid area equals Q × D: 20 cfs × 30 min
+        assert_relative_eq!(hydrograph.volume(), 20.0 * 30.0 * 60.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_modified_rational_short_storm() {
+        let generator = HydrographGenerator::us_customary();
+        let area = drainage_area(Some(0.5), None, 20.0);
+
+        let hydrograph = generator.modified_rational(&area, 4.0, 10.0).unwrap();
+        assert_relative_eq!(hydrograph.peak_flow(), 10.0);
+    }
+
+    #[test]
+    fn test_scs_runoff_depth() {
+        let generator = HydrographGenerator::us_customary();
+
+        // TR-55: P = 5 in, CN = 80 gives Q ≈ 2.89 in
+        assert_relative_eq!(generator.scs_runoff_depth(5.0, 80.0), 2.893, epsilon = 0.001);
+        assert_eq!(generator.scs_runoff_depth(0.4, 80.0), 0.0);
+    }
+
+    #[test]
+    fn test_unit_hydrograph_matches_hec22_example_4_4() {
+        // HEC-22 Example 4.4: A = 0.463 mi², Tc = 1.34 h, qp ≈ 251 cfs per inch
+        let tc = 1.34 * 60.0;
+        let generator = HydrographGenerator::new(UnitSystem::US, 2.0 * tc / 15.0);
+        let unit = generator.unit_hydrograph(0.463 * 640.0, tc);
+
+        let peak = unit.iter().cloned().fold(0.0, f64::max);
+        assert!((peak - 251.0).abs() / 251.0 < 0.03, "peak {:.1}", peak);
+    }
+
+    #[test]
+    fn test_scs_hydrograph_volume() {
+        let generator = HydrographGenerator::us_customary();
+        let area = drainage_area(None, Some(85.0), 20.0);
+        let storm = block_storm(2.0, 60.0);
+
+        let hydrograph = generator
+            .generate(&area, &storm, RunoffMethod::ScsUnitHydrograph)
+            .unwrap();
+
+        // 2 in of rain on CN 85 yields about 0.80 in of runoff
+        let runoff = generator.scs_runoff_depth(2.0, 85.0);
+        let expected = runoff / 12.0 * 10.0 * 43560.0;
+        assert_relative_eq!(hydrograph.volume(), expected, max_relative = 0.01);
+    }
+
+    #[test]
+    fn test_scs_hydrograph_from_type_ii_distribution() {
+        let generator = HydrographGenerator::new(UnitSystem::US, 6.0);
+        let area = drainage_area(None, Some(80.0), 30.0);
+        let storm = DesignStorm::with_distribution(
+            "10yr-24hr".to_string(),
+            "10-Year, 24-Hour".to_string(),
+            10.0,
+            5.0,
+            1440.0,
+            crate::rainfall::DistributionType::ScsTypeII,
+        );
+
+        let hydrograph = generator
+            .generate(&area, &storm, RunoffMethod::ScsUnitHydrograph)
+            .unwrap();
+
+        // Peak follows the Type II burst just before hour 12
+        let peak = hydrograph.peak().unwrap();
+        assert!(peak.time > 720.0 && peak.time < 780.0);
+        let expected = generator.scs_runoff_depth(5.0, 80.0) / 12.0 * 10.0 * 43560.0;
+        assert_relative_eq!(hydrograph.volume(), expected, max_relative = 0.01);
+    }
+
+    #[test]
+    fn test_rational_hyetograph_matches_modified_rational() {
+        let generator = HydrographGenerator::us_customary();
+        let area = drainage_area(Some(0.6), None, 10.0);
+        let storm = block_storm(3.0, 30.0);
+
+        let from_hyetograph = generator
+            .generate(&area, &storm, RunoffMethod::RationalHyetograph)
+            .unwrap();
+        let trapezoid = generator.modified_rational(&area, 3.0, 30.0).unwrap();
+
+        assert_relative_eq!(from_hyetograph.peak_flow(), trapezoid.peak_flow(), epsilon = 1e-9);
+        assert_relative_eq!(from_hyetograph.volume(), trapezoid.volume(), max_relative = 0.001);
+    }
+
+    #[test]
+    fn test_node_inflows_combine_at_outlet() {
+        let generator = HydrographGenerator::us_customary();
+        let storm = DesignStorm {
+            duration: Some(20.0),
+            ..DesignStorm::uniform("10yr".to_string(), "10-Year".to_string(), 10.0, 4.0)
+        };
+        let mut second = drainage_area(Some(0.9), None, 5.0);
+        second.id = "DA-002".to_string();
+        let areas = vec![drainage_area(Some(0.5), None, 10.0), second];
+
+        let (inflows, results) = generator
+            .node_inflows(&areas, &storm, RunoffMethod::ModifiedRational)
+            .unwrap();
+
+        assert_eq!(inflows.len(), 1);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].intensity, Some(4.0));
+
+        // Both areas are at full rate between 10 and 20 minutes: 20 + 36 cfs
+        assert_relative_eq!(inflows["IN-001"].flow_at(15.0), 56.0, epsilon = 1e-9);
+        let total: f64 = results.iter().map(|r| r.total_volume.unwrap()).sum();
+        assert_relative_eq!(inflows["IN-001"].volume(), total, max_relative = 0.001);
+    }
+}
@@ -0,0 +1,882 @@
+//! Hydraulic geometry of conduit cross sections
+//!
+//! Every cross section, closed or open, is described by how its flow area,
+//! wetted perimeter and top width vary with depth. The [`CrossSection`] trait
+//! captures that, and builds the depth-dependent hydraulics on top of it:
+//!
+//! ```text
+//! Normal depth:    Q = (k/n) A R^(2/3) S^(1/2)
+//! Critical depth:  Q²T = gA³
+//! Froude number:   Fr = V / √(g A/T)
+//! ```
+//!
+//! A custom shape only needs to implement the four geometry methods to be
+//! usable by the GVF profile and HGL solver.
+//!
+//! Closed conduits are full once the depth reaches their crown, where the
+//! top width closes to zero. Above the crown their area and conveyance stay
+//! at the full values, and any extra depth is read as pressure head.
+//!
+//! ## References
+//!
+//! FHWA HEC-22 (4th Edition, 2024), Chapter 9: Storm Drain Conduits;
+//! Chow, V.T. (1959), *Open-Channel Hydraulics*, Chapters 2 and 4
+
+use crate::conduit::{ChannelShape, ChannelSubsection, Conduit, ConduitType, PipeShape, StationElevation};
+use crate::project::UnitSystem;
+use std::fmt::Debug;
+
+/// Bisection iterations for depth solutions
+const BISECTION_ITERATIONS: usize = 60;
+
+/// Most doublings of the bracketing depth before giving up
+const MAX_DOUBLINGS: usize = 100;
+
+/// Top width, relative to the depth, below which a section is closed
+const CLOSURE_TOLERANCE: f64 = 1e-6;
+
+/// Geometry of a cross section as a function of flow depth
+///
+/// Depths are measured from the invert (ft or m).
+pub trait CrossSection: Debug {
+    /// Flow area at a depth; full above the crown of a closed section
+    fn area(&self, depth: f64) -> f64;
+
+    /// Wetted perimeter at a depth
+    fn wetted_perimeter(&self, depth: f64) -> f64;
+
+    /// Water surface width at a depth; zero once a closed section is full
+    fn top_width(&self, depth: f64) -> f64;
+
+    /// Deepest flow the section holds: the rise of a closed conduit or the
+    /// bank height of a surveyed channel. `None` for unbounded prismatic
+    /// channels.
+    fn max_depth(&self) -> Option<f64>;
+
+    /// Crown height of a closed section, where the top width closes to zero
+    fn crown(&self) -> Option<f64> {
+        self.max_depth()
+            .filter(|&depth| self.top_width(depth) <= CLOSURE_TOLERANCE * depth)
+    }
+
+    /// Hydraulic radius A/P at a depth, zero when dry
+    fn hydraulic_radius(&self, depth: f64) -> f64 {
+        let perimeter = self.wetted_perimeter(depth);
+        if perimeter <= 0.0 {
+            return 0.0;
+        }
+        self.area(depth) / perimeter
+    }
+
+    /// Flow area at the maximum depth
+    fn full_area(&self) -> Option<f64> {
+        self.max_depth().map(|depth| self.area(depth))
+    }
+
+    /// Manning conveyance K = (k/n) A R^(2/3) at a depth, capped at the
+    /// full value for closed sections
+    ///
+    /// # Arguments
+    /// * `depth` - Flow depth (ft or m)
+    /// * `manning_n` - Manning's roughness coefficient
+    /// * `k` - Manning's constant (1.486 for US, 1.0 for SI)
+    fn conveyance(&self, depth: f64, manning_n: f64, k: f64) -> f64 {
+        let conveyance = |y: f64| k / manning_n * self.area(y) * self.hydraulic_radius(y).powf(2.0 / 3.0);
+        match self.crown() {
+            Some(crown) => conveyance(depth.min(crown)).min(conveyance(crown)),
+            None => conveyance(depth),
+        }
+    }
+
+    /// First moment of the flow area about the water surface
+    ///
+    /// In a full closed section `depth` is the pressure head above the
+    /// invert, so the moment includes the pressure above the crown.
+    fn first_moment(&self, depth: f64) -> f64 {
+        let top = self.crown().map_or(depth, |crown| depth.min(crown));
+        simpson(|eta| (depth - eta) * self.top_width(eta), 0.0, top.max(0.0))
+    }
+
+    /// Depth at which the flow area reaches `area`
+    fn depth_for_area(&self, area: f64) -> f64 {
+        if area <= 0.0 {
+            return 0.0;
+        }
+        if let Some(crown) = self.crown() {
+            if self.area(crown) <= area {
+                return crown;
+            }
+        }
+        bisect(self.max_depth().unwrap_or(1.0), |y| self.area(y) < area).unwrap_or(0.0)
+    }
+
+    /// Normal depth for a flow on a downhill slope
+    ///
+    /// A closed section is full once the flow reaches its full capacity.
+    ///
+    /// # Arguments
+    /// * `flow` - Flow rate (cfs or cms)
+    /// * `slope` - Bed slope (ft/ft or m/m)
+    /// * `manning_n` - Manning's roughness coefficient
+    /// * `k` - Manning's constant (1.486 for US, 1.0 for SI)
+    ///
+    /// # Returns
+    /// Normal depth (ft or m), or `None` on a flat or adverse slope
+    fn normal_depth(&self, flow: f64, slope: f64, manning_n: f64, k: f64) -> Option<f64> {
+        if slope <= 0.0 {
+            return None;
+        }
+        let target = flow / slope.sqrt();
+        if let Some(crown) = self.crown() {
+            if self.conveyance(crown, manning_n, k) <= target {
+                return Some(crown);
+            }
+        }
+        bisect(self.max_depth().unwrap_or(1.0), |y| self.conveyance(y, manning_n, k) < target)
+    }
+
+    /// Critical depth, where Q²T = gA³; at most the crown of a closed section
+    ///
+    /// # Arguments
+    /// * `flow` - Flow rate (cfs or cms)
+    /// * `gravity` - Gravitational constant (32.17 ft/s² or 9.81 m/s²)
+    fn critical_depth(&self, flow: f64, gravity: f64) -> f64 {
+        let excess = |y: f64| flow * flow * self.top_width(y) - gravity * self.area(y).powi(3);
+        bisect(self.max_depth().unwrap_or(1.0), |y| excess(y) > 0.0).unwrap_or(0.0)
+    }
+
+    /// Froude number V/√(g A/T) at a depth; zero for a dry or full section
+    fn froude_number(&self, flow: f64, depth: f64, gravity: f64) -> f64 {
+        let area = self.area(depth);
+        let top_width = self.top_width(depth);
+        if area <= 0.0 || top_width <= 0.0 {
+            return 0.0;
+        }
+        flow / area / (gravity * area / top_width).sqrt()
+    }
+
+    /// Specific force Q²/(gA) + Aȳ at a depth
+    fn specific_force(&self, flow: f64, depth: f64, gravity: f64) -> f64 {
+        flow * flow / (gravity * self.area(depth)) + self.first_moment(depth)
+    }
+}
+
+/// Smallest depth for which `below` turns false, bracketed by doubling
+/// from `start`
+fn bisect(start: f64, below: impl Fn(f64) -> bool) -> Option<f64> {
+    let mut y_low = 0.0;
+    let mut y_high = start.max(1e-3);
+    let mut doublings = 0;
+    while below(y_high) {
+        if doublings == MAX_DOUBLINGS {
+            return None;
+        }
+        y_low = y_high;
+        y_high *= 2.0;
+        doublings += 1;
+    }
+    for _ in 0..BISECTION_ITERATIONS {
+        let y_mid = (y_low + y_high) / 2.0;
+        if below(y_mid) {
+            y_low = y_mid;
+        } else {
+            y_high = y_mid;
+        }
+    }
+    Some((y_low + y_high) / 2.0)
+}
+
+/// Simpson's rule integral of `f` from `a` to `b`
+fn simpson(f: impl Fn(f64) -> f64, a: f64, b: f64) -> f64 {
+    const INTERVALS: usize = 64;
+    let h = (b - a) / INTERVALS as f64;
+    let interior: f64 = (1..INTERVALS)
+        .map(|i| f(a + i as f64 * h) * if i % 2 == 1 { 4.0 } else { 2.0 })
+        .sum();
+    h / 3.0 * (f(a) + interior + f(b))
+}
+
+/// Cross section of a pipe or channel conduit
+///
+/// Pipes take their rise and span from [`PipeProperties::rise_and_span`],
+/// converted from in (mm) to ft (m). Natural channels need a surveyed cross
+/// section.
+///
+/// [`PipeProperties::rise_and_span`]: crate::conduit::PipeProperties::rise_and_span
+pub fn from_conduit(conduit: &Conduit, unit_system: UnitSystem) -> Result<Box<dyn CrossSection>, String> {
+    match conduit.conduit_type {
+        ConduitType::Pipe => {
+            let pipe = conduit
+                .pipe
+                .as_ref()
+                .ok_or_else(|| format!("Pipe {} has no pipe properties", conduit.id))?;
+            let (rise, span) = pipe.rise_and_span(unit_system).ok_or_else(|| match pipe.shape {
+                PipeShape::Circular => format!("Pipe {} has no diameter", conduit.id),
+                _ => format!("Pipe {} has no rise and span or standard size", conduit.id),
+            })?;
+            let (rise, span) = match unit_system {
+                UnitSystem::US => (rise / 12.0, span / 12.0),
+                UnitSystem::SI => (rise / 1000.0, span / 1000.0),
+            };
+            if rise <= 0.0 || span <= 0.0 {
+                return Err(format!("Pipe {} must have a positive size", conduit.id));
+            }
+            match pipe.shape {
+                PipeShape::Circular => Ok(Box::new(Circular { diameter: rise })),
+                PipeShape::Rectangular => Ok(Box::new(Rectangular { width: span, height: Some(rise) })),
+                PipeShape::Elliptical => Ok(Box::new(Elliptical { span, rise })),
+                PipeShape::Arch if rise > span / 2.0 => Ok(Box::new(Arch { span, rise })),
+                PipeShape::Arch => Err(format!("Arch pipe {} must rise more than half its span", conduit.id)),
+            }
+        }
+        ConduitType::Channel => {
+            let channel = conduit
+                .channel
+                .as_ref()
+                .ok_or_else(|| format!("Channel {} has no channel properties", conduit.id))?;
+            let bottom_width = channel.bottom_width.unwrap_or(0.0);
+            let side_slope = channel.side_slope.unwrap_or(0.0);
+            match channel.shape {
+                ChannelShape::Trapezoidal => Ok(Box::new(Trapezoidal { bottom_width, side_slope })),
+                ChannelShape::Rectangular => Ok(Box::new(Rectangular { width: bottom_width, height: None })),
+                ChannelShape::Triangular => Ok(Box::new(Triangular {
+                    left_slope: side_slope,
+                    right_slope: side_slope,
+                })),
+                ChannelShape::Natural => {
+                    let points = channel
+                        .cross_section
+                        .as_deref()
+                        .ok_or_else(|| format!("Natural channel {} has no cross section", conduit.id))?;
+                    let subsections = channel.subsections.as_deref().unwrap_or_default();
+                    Irregular::new(points, subsections, channel.manning_n)
+                        .map(|irregular| Box::new(irregular) as Box<dyn CrossSection>)
+                        .map_err(|e| format!("Channel {}: {}", conduit.id, e))
+                }
+            }
+        }
+        ConduitType::Gutter => Err(format!("Gutter {} has no conduit section", conduit.id)),
+    }
+}
+
+/// Circular pipe
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circular {
+    /// Diameter (ft or m)
+    pub diameter: f64,
+}
+
+impl Circular {
+    /// Central angle subtended by the water surface
+    fn angle(&self, depth: f64) -> f64 {
+        let r = self.diameter / 2.0;
+        2.0 * ((r - depth.clamp(0.0, self.diameter)) / r).acos()
+    }
+}
+
+impl CrossSection for Circular {
+    fn area(&self, depth: f64) -> f64 {
+        let r = self.diameter / 2.0;
+        let theta = self.angle(depth);
+        r * r / 2.0 * (theta - theta.sin())
+    }
+
+    fn wetted_perimeter(&self, depth: f64) -> f64 {
+        self.diameter / 2.0 * self.angle(depth)
+    }
+
+    fn top_width(&self, depth: f64) -> f64 {
+        let y = depth.clamp(0.0, self.diameter);
+        2.0 * (y * (self.diameter - y)).sqrt()
+    }
+
+    fn max_depth(&self) -> Option<f64> {
+        Some(self.diameter)
+    }
+
+    fn crown(&self) -> Option<f64> {
+        Some(self.diameter)
+    }
+
+    fn first_moment(&self, depth: f64) -> f64 {
+        let r = self.diameter / 2.0;
+        let y = depth.clamp(0.0, self.diameter);
+        let area = self.area(y);
+        (y - r) * area + (2.0 / 3.0) * (y * (self.diameter - y)).powf(1.5) + area * (depth - y)
+    }
+}
+
+/// Rectangular section: a box culvert when it has a height, otherwise an
+/// open rectangular channel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rectangular {
+    /// Inside width or span (ft or m)
+    pub width: f64,
+    /// Inside height or rise of a closed box (ft or m)
+    pub height: Option<f64>,
+}
+
+impl Rectangular {
+    fn wetted_depth(&self, depth: f64) -> f64 {
+        depth.clamp(0.0, self.height.unwrap_or(f64::INFINITY))
+    }
+}
+
+impl CrossSection for Rectangular {
+    fn area(&self, depth: f64) -> f64 {
+        self.width * self.wetted_depth(depth)
+    }
+
+    fn wetted_perimeter(&self, depth: f64) -> f64 {
+        match self.height {
+            Some(height) if depth >= height => 2.0 * (self.width + height),
+            _ => self.width + 2.0 * depth.max(0.0),
+        }
+    }
+
+    fn top_width(&self, depth: f64) -> f64 {
+        match self.height {
+            Some(height) if depth >= height => 0.0,
+            _ => self.width,
+        }
+    }
+
+    fn max_depth(&self) -> Option<f64> {
+        self.height
+    }
+
+    fn crown(&self) -> Option<f64> {
+        self.height
+    }
+
+    fn first_moment(&self, depth: f64) -> f64 {
+        let y = self.wetted_depth(depth);
+        self.width * y * (depth - y / 2.0)
+    }
+}
+
+/// Horizontal elliptical pipe
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Elliptical {
+    /// Inside width (ft or m)
+    pub span: f64,
+    /// Inside height (ft or m)
+    pub rise: f64,
+}
+
+impl Elliptical {
+    /// Half-angle subtended at the centre by the water surface
+    fn angle(&self, depth: f64) -> f64 {
+        let b = self.rise / 2.0;
+        ((b - depth.clamp(0.0, self.rise)) / b).acos()
+    }
+}
+
+impl CrossSection for Elliptical {
+    fn area(&self, depth: f64) -> f64 {
+        let phi = self.angle(depth);
+        self.span * self.rise / 4.0 * (phi - phi.sin() * phi.cos())
+    }
+
+    fn wetted_perimeter(&self, depth: f64) -> f64 {
+        let (a, b) = (self.span / 2.0, self.rise / 2.0);
+        2.0 * simpson(|t| (a * a * t.cos().powi(2) + b * b * t.sin().powi(2)).sqrt(), 0.0, self.angle(depth))
+    }
+
+    fn top_width(&self, depth: f64) -> f64 {
+        self.span * self.angle(depth).sin()
+    }
+
+    fn max_depth(&self) -> Option<f64> {
+        Some(self.rise)
+    }
+
+    fn crown(&self) -> Option<f64> {
+        Some(self.rise)
+    }
+}
+
+/// Arch pipe, taken as a semicircular crown of the full span over a
+/// circular invert arc; the corner radii of real arches are ignored
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Arch {
+    /// Inside width (ft or m)
+    pub span: f64,
+    /// Inside height (ft or m), more than half the span
+    pub rise: f64,
+}
+
+impl Arch {
+    /// Area, wetted perimeter and top width at a depth
+    ///
+    /// The invert arc spans the full width with a sagitta of the rise less
+    /// half the span; above it the section is a semicircle of half the span.
+    fn geometry(&self, depth: f64) -> (f64, f64, f64) {
+        let r = self.span / 2.0;
+        let sagitta = self.rise - r;
+        let invert_radius = (r * r + sagitta * sagitta) / (2.0 * sagitta);
+
+        let invert = |y: f64| {
+            let theta = 2.0 * ((invert_radius - y) / invert_radius).acos();
+            (
+                invert_radius * invert_radius / 2.0 * (theta - theta.sin()),
+                invert_radius * theta,
+                2.0 * (y * (2.0 * invert_radius - y)).sqrt(),
+            )
+        };
+
+        let depth = depth.clamp(0.0, self.rise);
+        if depth <= sagitta {
+            return invert(depth);
+        }
+        let (area, perimeter, _) = invert(sagitta);
+        let z = depth - sagitta;
+        let angle = (z / r).min(1.0).asin();
+        (
+            area + r * r * angle + z * (r * r - z * z).max(0.0).sqrt(),
+            perimeter + 2.0 * r * angle,
+            2.0 * (r * r - z * z).max(0.0).sqrt(),
+        )
+    }
+}
+
+impl CrossSection for Arch {
+    fn area(&self, depth: f64) -> f64 {
+        self.geometry(depth).0
+    }
+
+    fn wetted_perimeter(&self, depth: f64) -> f64 {
+        self.geometry(depth).1
+    }
+
+    fn top_width(&self, depth: f64) -> f64 {
+        self.geometry(depth).2
+    }
+
+    fn max_depth(&self) -> Option<f64> {
+        Some(self.rise)
+    }
+
+    fn crown(&self) -> Option<f64> {
+        Some(self.rise)
+    }
+}
+
+/// Trapezoidal channel with equal side slopes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trapezoidal {
+    /// Bottom width (ft or m)
+    pub bottom_width: f64,
+    /// Side slope (H:V)
+    pub side_slope: f64,
+}
+
+impl CrossSection for Trapezoidal {
+    fn area(&self, depth: f64) -> f64 {
+        let y = depth.max(0.0);
+        (self.bottom_width + self.side_slope * y) * y
+    }
+
+    fn wetted_perimeter(&self, depth: f64) -> f64 {
+        self.bottom_width + 2.0 * depth.max(0.0) * (1.0 + self.side_slope * self.side_slope).sqrt()
+    }
+
+    fn top_width(&self, depth: f64) -> f64 {
+        self.bottom_width + 2.0 * self.side_slope * depth.max(0.0)
+    }
+
+    fn max_depth(&self) -> Option<f64> {
+        None
+    }
+
+    fn first_moment(&self, depth: f64) -> f64 {
+        let y = depth.max(0.0);
+        self.bottom_width * y * y / 2.0 + self.side_slope * y.powi(3) / 3.0
+    }
+}
+
+/// Triangular channel, with a side slope for each bank
+///
+/// A side slope of zero is a vertical wall, as at a curb.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Triangular {
+    /// Left side slope (H:V)
+    pub left_slope: f64,
+    /// Right side slope (H:V)
+    pub right_slope: f64,
+}
+
+impl CrossSection for Triangular {
+    fn area(&self, depth: f64) -> f64 {
+        (self.left_slope + self.right_slope) * depth.max(0.0).powi(2) / 2.0
+    }
+
+    fn wetted_perimeter(&self, depth: f64) -> f64 {
+        let sides = (1.0 + self.left_slope.powi(2)).sqrt() + (1.0 + self.right_slope.powi(2)).sqrt();
+        depth.max(0.0) * sides
+    }
+
+    fn top_width(&self, depth: f64) -> f64 {
+        (self.left_slope + self.right_slope) * depth.max(0.0)
+    }
+
+    fn max_depth(&self) -> Option<f64> {
+        None
+    }
+
+    fn first_moment(&self, depth: f64) -> f64 {
+        (self.left_slope + self.right_slope) * depth.max(0.0).powi(3) / 6.0
+    }
+}
+
+/// Natural channel cross section from surveyed station/elevation points
+///
+/// Depths are measured from the lowest point of the section, so the survey
+/// may use any datum. Water rising above either end point is held by a
+/// vertical wall there. Conveyance is the sum of the conveyances of each
+/// roughness subsection, so shallow, rough overbanks carry less of the flow
+/// than the main channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Irregular {
+    /// (station, height above the thalweg), left to right, with a point at
+    /// each subsection boundary
+    points: Vec<(f64, f64)>,
+    /// Roughness subsection of each segment between consecutive points
+    segments: Vec<usize>,
+    /// Manning's n of each roughness subsection; the first applies left of
+    /// any subsection start
+    roughness: Vec<f64>,
+}
+
+/// Wetted geometry of a natural channel, in total and by subsection
+struct Wetted {
+    area: f64,
+    perimeter: f64,
+    top_width: f64,
+    /// First moment of area about the water surface
+    moment: f64,
+    /// (area, perimeter) of each roughness subsection
+    subsections: Vec<(f64, f64)>,
+}
+
+impl Irregular {
+    /// Build a section from station/elevation points and roughness subsections
+    ///
+    /// # Arguments
+    /// * `points` - Station/elevation points, left to right
+    /// * `subsections` - Roughness subsections in station order
+    /// * `manning_n` - Manning's n where no subsection applies
+    pub fn new(
+        points: &[StationElevation],
+        subsections: &[ChannelSubsection],
+        manning_n: f64,
+    ) -> Result<Self, String> {
+        if points.len() < 2 {
+            return Err("cross section needs at least two points".to_string());
+        }
+        if points.windows(2).any(|w| w[1].station < w[0].station) {
+            return Err("cross section stations must increase left to right".to_string());
+        }
+        if subsections.windows(2).any(|w| w[1].start_station <= w[0].start_station) {
+            return Err("subsection start stations must increase left to right".to_string());
+        }
+        if subsections.iter().any(|s| s.manning_n <= 0.0) || manning_n <= 0.0 {
+            return Err("Manning's n must be positive".to_string());
+        }
+
+        let thalweg = points.iter().map(|p| p.elevation).fold(f64::INFINITY, f64::min);
+        let mut stations: Vec<(f64, f64)> =
+            points.iter().map(|p| (p.station, p.elevation - thalweg)).collect();
+
+        // Break the ground line at each subsection boundary
+        for boundary in subsections.iter().map(|s| s.start_station) {
+            let Some(i) = stations.windows(2).position(|w| w[0].0 < boundary && boundary < w[1].0)
+            else {
+                continue;
+            };
+            let ((x1, z1), (x2, z2)) = (stations[i], stations[i + 1]);
+            let z = z1 + (z2 - z1) * (boundary - x1) / (x2 - x1);
+            stations.insert(i + 1, (boundary, z));
+        }
+
+        let segments = stations
+            .windows(2)
+            .map(|w| {
+                let middle = (w[0].0 + w[1].0) / 2.0;
+                subsections.iter().filter(|s| s.start_station <= middle).count()
+            })
+            .collect();
+        let roughness = std::iter::once(manning_n)
+            .chain(subsections.iter().map(|s| s.manning_n))
+            .collect();
+
+        Ok(Self {
+            points: stations,
+            segments,
+            roughness,
+        })
+    }
+
+    /// Height of the lower bank above the thalweg
+    pub fn bank_height(&self) -> f64 {
+        let first = self.points[0].1;
+        let last = self.points[self.points.len() - 1].1;
+        first.min(last)
+    }
+
+    /// Wetted geometry below a water surface at `depth` above the thalweg
+    fn wetted(&self, depth: f64) -> Wetted {
+        let mut wetted = Wetted {
+            area: 0.0,
+            perimeter: 0.0,
+            top_width: 0.0,
+            moment: 0.0,
+            subsections: vec![(0.0, 0.0); self.roughness.len()],
+        };
+
+        for (pair, &subsection) in self.points.windows(2).zip(&self.segments) {
+            let ((x1, z1), (x2, z2)) = (pair[0], pair[1]);
+            if z1 >= depth && z2 >= depth {
+                continue;
+            }
+
+            // Clip the segment to the part below the water surface
+            let clip = |x: f64, z: f64, x_other: f64, z_other: f64| {
+                if z <= depth {
+                    (x, z)
+                } else {
+                    (x + (x_other - x) * (z - depth) / (z - z_other), depth)
+                }
+            };
+            let (xa, za) = clip(x1, z1, x2, z2);
+            let (xb, zb) = clip(x2, z2, x1, z1);
+            let (da, db) = (depth - za, depth - zb);
+
+            let width = xb - xa;
+            let area = width * (da + db) / 2.0;
+            let perimeter = width.hypot(zb - za);
+            wetted.area += area;
+            wetted.perimeter += perimeter;
+            wetted.top_width += width;
+            wetted.moment += width * (da * da + da * db + db * db) / 6.0;
+            wetted.subsections[subsection].0 += area;
+            wetted.subsections[subsection].1 += perimeter;
+        }
+
+        // Vertical walls above the end points
+        let (first, last) = (self.points[0].1, self.points[self.points.len() - 1].1);
+        for (height, subsection) in [(first, self.segments[0]), (last, self.segments[self.segments.len() - 1])] {
+            if depth > height {
+                wetted.perimeter += depth - height;
+                wetted.subsections[subsection].1 += depth - height;
+            }
+        }
+
+        wetted
+    }
+}
+
+impl CrossSection for Irregular {
+    fn area(&self, depth: f64) -> f64 {
+        self.wetted(depth).area
+    }
+
+    fn wetted_perimeter(&self, depth: f64) -> f64 {
+        self.wetted(depth).perimeter
+    }
+
+    fn top_width(&self, depth: f64) -> f64 {
+        self.wetted(depth).top_width
+    }
+
+    fn max_depth(&self) -> Option<f64> {
+        Some(self.bank_height())
+    }
+
+    /// Conveyance summed over the roughness subsections; the section's own
+    /// roughness replaces `manning_n`
+    fn conveyance(&self, depth: f64, _manning_n: f64, k: f64) -> f64 {
+        self.wetted(depth)
+            .subsections
+            .iter()
+            .zip(&self.roughness)
+            .filter(|((_, perimeter), _)| *perimeter > 0.0)
+            .map(|(&(area, perimeter), n)| k / n * area * (area / perimeter).powf(2.0 / 3.0))
+            .sum()
+    }
+
+    fn first_moment(&self, depth: f64) -> f64 {
+        self.wetted(depth).moment
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_closed_section_geometry() {
+        // 4 ft × 3 ft box
+        let section = Rectangular { width: 4.0, height: Some(3.0) };
+        assert_relative_eq!(section.full_area().unwrap(), 12.0);
+        assert_relative_eq!(section.wetted_perimeter(3.0), 14.0);
+        assert_relative_eq!(section.hydraulic_radius(1.5), 6.0 / 7.0);
+        assert_eq!(section.top_width(3.0), 0.0);
+        assert_eq!(section.crown(), Some(3.0));
+
+        // Horizontal ellipse: full area πab and a perimeter close to
+        // Ramanujan's approximation
+        let (span, rise) = (38.0 / 12.0, 24.0 / 12.0);
+        let section = Elliptical { span, rise };
+        let (a, b) = (span / 2.0, rise / 2.0);
+        let h = ((a - b) / (a + b)).powi(2);
+        let ramanujan = PI * (a + b) * (1.0 + 3.0 * h / (10.0 + (4.0 - 3.0 * h).sqrt()));
+        assert_relative_eq!(section.full_area().unwrap(), PI * a * b, epsilon = 1e-9);
+        assert_relative_eq!(section.wetted_perimeter(rise), ramanujan, epsilon = 1e-4);
+        assert_relative_eq!(section.top_width(rise / 2.0), span, epsilon = 1e-9);
+        assert_relative_eq!(section.area(rise / 2.0), PI * a * b / 2.0, epsilon = 1e-9);
+
+        // Arch: semicircular crown over a circular segment invert
+        let (span, rise) = (43.75 / 12.0, 26.625 / 12.0);
+        let section = Arch { span, rise };
+        let sagitta = rise - span / 2.0;
+        let radius = (span * span / 4.0 + sagitta * sagitta) / (2.0 * sagitta);
+        let theta = 2.0 * (span / (2.0 * radius)).asin();
+        let invert = radius * radius / 2.0 * (theta - theta.sin());
+        assert_relative_eq!(section.full_area().unwrap(), PI * span * span / 8.0 + invert, epsilon = 1e-6);
+        assert_relative_eq!(section.wetted_perimeter(rise), PI * span / 2.0 + radius * theta, epsilon = 1e-6);
+        assert_relative_eq!(section.top_width(sagitta), span, epsilon = 1e-6);
+
+        // Half-full circle
+        let section = Circular { diameter: 2.0 };
+        assert_relative_eq!(section.area(1.0), PI / 2.0, epsilon = 1e-12);
+        assert_relative_eq!(section.hydraulic_radius(1.0), 0.5, epsilon = 1e-12);
+        assert_relative_eq!(section.depth_for_area(PI / 2.0), 1.0, epsilon = 1e-9);
+        assert_eq!(section.depth_for_area(4.0), 2.0);
+    }
+
+    #[test]
+    fn test_open_section_geometry() {
+        let trapezoid = Trapezoidal { bottom_width: 20.0, side_slope: 2.0 };
+        assert_relative_eq!(trapezoid.area(3.0), 78.0);
+        assert_relative_eq!(trapezoid.wetted_perimeter(3.0), 20.0 + 6.0 * 5.0_f64.sqrt());
+        assert_relative_eq!(trapezoid.top_width(3.0), 32.0);
+        assert_eq!(trapezoid.crown(), None);
+
+        // A curb and 2% cross slope
+        let gutter = Triangular { left_slope: 0.0, right_slope: 50.0 };
+        assert_relative_eq!(gutter.top_width(0.16), 8.0);
+        assert_relative_eq!(gutter.area(0.16), 0.64);
+        assert_relative_eq!(gutter.wetted_perimeter(0.16), 0.16 + 0.16 * 2501.0_f64.sqrt());
+
+        // The closed-form moments match the generic integral
+        let moment = |section: &dyn CrossSection, depth: f64| {
+            simpson(|eta| (depth - eta) * section.top_width(eta), 0.0, depth)
+        };
+        assert_relative_eq!(trapezoid.first_moment(3.0), moment(&trapezoid, 3.0), epsilon = 1e-9);
+        assert_relative_eq!(gutter.first_moment(0.16), moment(&gutter, 0.16), epsilon = 1e-12);
+        let circle = Circular { diameter: 2.0 };
+        assert_relative_eq!(circle.first_moment(1.3), moment(&circle, 1.3), epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_normal_and_critical_depth() {
+        // Rectangular critical depth (q²/g)^(1/3) and Froude number of one
+        let channel = Rectangular { width: 4.0, height: None };
+        let yc = channel.critical_depth(40.0, 32.2);
+        assert_relative_eq!(yc, (100.0_f64 / 32.2).cbrt(), epsilon = 1e-9);
+        assert_relative_eq!(channel.froude_number(40.0, yc, 32.2), 1.0, epsilon = 1e-9);
+
+        // A box is full once the flow passes its capacity
+        let culvert = Rectangular { width: 4.0, height: Some(3.0) };
+        let flow = 1.486 / 0.013 * 6.0 * (6.0_f64 / 7.0).powf(2.0 / 3.0) * 0.0025_f64.sqrt();
+        assert_relative_eq!(culvert.normal_depth(flow, 0.0025, 0.013, 1.486).unwrap(), 1.5, epsilon = 1e-9);
+        assert_eq!(culvert.normal_depth(10.0 * flow, 0.0025, 0.013, 1.486), Some(3.0));
+        assert_eq!(culvert.froude_number(flow, 3.0, 32.2), 0.0);
+    }
+
+    /// Parabolic swale described only by its geometry
+    #[derive(Debug)]
+    struct Swale {
+        /// Top width at 1 ft of depth
+        width: f64,
+    }
+
+    impl CrossSection for Swale {
+        fn area(&self, depth: f64) -> f64 {
+            2.0 / 3.0 * self.top_width(depth) * depth.max(0.0)
+        }
+
+        fn wetted_perimeter(&self, depth: f64) -> f64 {
+            // Shallow parabola approximation
+            let t = self.top_width(depth);
+            t + 8.0 * depth.max(0.0).powi(2) / (3.0 * t.max(1e-12))
+        }
+
+        fn top_width(&self, depth: f64) -> f64 {
+            self.width * depth.max(0.0).sqrt()
+        }
+
+        fn max_depth(&self) -> Option<f64> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_custom_section() {
+        let swale = Swale { width: 12.0 };
+        let depth = swale.normal_depth(20.0, 0.01, 0.035, 1.486).unwrap();
+        let conveyance = swale.conveyance(depth, 0.035, 1.486);
+        assert_relative_eq!(conveyance * 0.1, 20.0, epsilon = 1e-9);
+
+        // Q²T = gA³ at critical depth
+        let yc = swale.critical_depth(20.0, 32.2);
+        assert_relative_eq!(400.0 * swale.top_width(yc), 32.2 * swale.area(yc).powi(3), epsilon = 1e-6);
+        assert_relative_eq!(swale.froude_number(20.0, yc, 32.2), 1.0, epsilon = 1e-6);
+
+        // The generic moment integrates (y - η)T(η): 4/15 W y^(5/2)
+        assert_relative_eq!(swale.first_moment(1.0), 4.0 / 15.0 * 12.0, max_relative = 1e-3);
+    }
+
+    #[test]
+    fn test_irregular_section() {
+        let points: Vec<StationElevation> = [(0.0, 58.0), (16.0, 50.0), (36.0, 50.0), (52.0, 58.0)]
+            .iter()
+            .map(|&(station, elevation)| StationElevation { station, elevation })
+            .collect();
+        let natural = Irregular::new(&points, &[], 0.025).unwrap();
+        let trapezoid = Trapezoidal { bottom_width: 20.0, side_slope: 2.0 };
+
+        // Matches the trapezoid it was surveyed from, measured from the thalweg
+        for depth in [0.5, 3.0, 8.0] {
+            assert_relative_eq!(natural.area(depth), trapezoid.area(depth), epsilon = 1e-9);
+            assert_relative_eq!(natural.wetted_perimeter(depth), trapezoid.wetted_perimeter(depth), epsilon = 1e-9);
+            assert_relative_eq!(natural.top_width(depth), trapezoid.top_width(depth), epsilon = 1e-9);
+            assert_relative_eq!(natural.first_moment(depth), trapezoid.first_moment(depth), epsilon = 1e-9);
+        }
+        assert_eq!(natural.max_depth(), Some(8.0));
+        assert_eq!(natural.crown(), None);
+
+        // Above the banks the water is held by vertical walls
+        assert_relative_eq!(natural.top_width(10.0), 52.0);
+        assert_relative_eq!(natural.wetted_perimeter(10.0), trapezoid.wetted_perimeter(8.0) + 4.0, epsilon = 1e-9);
+
+        // Dividing down the centreline leaves the hydraulic radius, and so
+        // the conveyance, unchanged; roughening one half lowers it
+        let split = |n: f64| {
+            let subsections = [
+                ChannelSubsection { start_station: 0.0, manning_n: 0.025 },
+                ChannelSubsection { start_station: 26.0, manning_n: n },
+            ];
+            Irregular::new(&points, &subsections, 0.025).unwrap()
+        };
+        let whole = natural.conveyance(4.0, 0.025, 1.486);
+        assert_relative_eq!(whole, trapezoid.conveyance(4.0, 0.025, 1.486), max_relative = 1e-9);
+        assert_relative_eq!(split(0.025).conveyance(4.0, 0.025, 1.486), whole, max_relative = 1e-9);
+        assert_relative_eq!(split(0.05).conveyance(4.0, 0.025, 1.486), 0.75 * whole, max_relative = 1e-9);
+
+        assert!(Irregular::new(&points[..1], &[], 0.025).is_err());
+    }
+}
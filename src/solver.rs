@@ -14,22 +14,30 @@
 //! The procedure starts at the outfall and works upstream through the network.
 
 use crate::analysis::{
-    Analysis, AnalysisMethod, ConduitResult, DrainageAreaResult, HeadLoss, NodeResult,
-    Violation, ViolationType, Severity,
+    Analysis, AnalysisMethod, ConduitResult, DesignCriteria, DrainageAreaResult, HeadLoss, NodeResult,
+    TailwaterControl, Violation, ViolationType, Severity,
 };
-use crate::conduit::{Conduit, ConduitType};
+use crate::conduit::{Conduit, ConduitType, GutterProperties};
+use crate::culvert::{Culvert, CulvertControl, CulvertHydraulics};
 use crate::drainage::DrainageArea;
-use crate::gutter::{UniformGutter, GUTTER_K_US, GUTTER_K_SI};
+use crate::gutter::{GutterFlowResult, GutterSection, UniformGutter, GUTTER_K_US, GUTTER_K_SI};
+use crate::gvf::{DirectStep, Reach};
+use crate::section;
 use crate::hydraulics::{
-    EnergyLoss, FlowRegime, ManningsEquation, PipeFlowResult,
+    EnergyLoss, FlowRegime, ManningsEquation,
     FhwaAccessHoleMethod, InflowPipe, BenchingType, AccessHoleResult,
 };
 use crate::inlet::{
-    BarConfiguration as InletBarConfig, CombinationInletOnGrade, CurbOpeningInletOnGrade,
-    GrateInletOnGrade, InletInterceptionResult, ThroatType as InletThroatType,
+    BarConfiguration as InletBarConfig, CombinationInletOnGrade, CombinationInletSag,
+    CurbOpeningInletOnGrade, CurbOpeningInletSag, GrateInletOnGrade, GrateInletSag,
+    GrateType as InletGrateType, InletInterceptionResult, SlottedInletOnGrade, SlottedInletSag,
+    SweeperInletOnGrade, ThroatType as InletThroatType,
 };
 use crate::network::Network;
-use crate::node::{BoundaryCondition, Node, NodeType, InletLocation};
+use crate::node::{
+    BoundaryCondition, DiversionMethod, FlowDivider, InletProperties, InletType, Node, NodeType,
+    InletLocation, TailwaterRule,
+};
 use crate::project::UnitSystem;
 use std::collections::HashMap;
 
@@ -45,6 +53,11 @@ pub struct SolverConfig {
     pub max_iterations: usize,
     /// Convergence tolerance
     pub tolerance: f64,
+    /// Whether the HGL may rise above pipe crowns without a violation
+    pub allow_surcharge: bool,
+    /// Time of peak flow at each node after the storm begins (minutes), for
+    /// reading tidal outfalls at the time of peak
+    pub peak_times: HashMap<String, f64>,
 }
 
 impl SolverConfig {
@@ -56,6 +69,8 @@ impl SolverConfig {
             manning_k: 1.486,
             max_iterations: 50,
             tolerance: 0.001,
+            allow_surcharge: true,
+            peak_times: HashMap::new(),
         }
     }
 
@@ -67,7 +82,23 @@ impl SolverConfig {
             manning_k: 1.0,
             max_iterations: 50,
             tolerance: 0.001,
+            allow_surcharge: true,
+            peak_times: HashMap::new(),
+        }
+    }
+
+    /// Take the surcharge allowance from project design criteria
+    pub fn with_criteria(mut self, criteria: &DesignCriteria) -> Self {
+        if let Some(allow) = criteria.hgl_criteria.as_ref().and_then(|h| h.allow_surcharge) {
+            self.allow_surcharge = allow;
         }
+        self
+    }
+
+    /// Take times of peak flow, such as the rational method node Tc
+    pub fn with_peak_times(mut self, peak_times: HashMap<String, f64>) -> Self {
+        self.peak_times = peak_times;
+        self
     }
 }
 
@@ -77,6 +108,8 @@ pub struct HglSolver {
     mannings: ManningsEquation,
     energy_loss: EnergyLoss,
     fhwa_access_hole: FhwaAccessHoleMethod,
+    direct_step: DirectStep,
+    culvert: CulvertHydraulics,
 }
 
 impl HglSolver {
@@ -85,12 +118,26 @@ impl HglSolver {
         let mannings = ManningsEquation { k: config.manning_k };
         let energy_loss = EnergyLoss { gravity: config.gravity };
         let fhwa_access_hole = FhwaAccessHoleMethod { gravity: config.gravity };
+        let direct_step = DirectStep {
+            mannings: ManningsEquation { k: config.manning_k },
+            gravity: config.gravity,
+        };
+        let culvert = CulvertHydraulics {
+            mannings: ManningsEquation { k: config.manning_k },
+            gravity: config.gravity,
+            ..match config.unit_system {
+                UnitSystem::US => CulvertHydraulics::us_customary(),
+                UnitSystem::SI => CulvertHydraulics::si_metric(),
+            }
+        };
 
         Self {
             config,
             mannings,
             energy_loss,
             fhwa_access_hole,
+            direct_step,
+            culvert,
         }
     }
 
@@ -131,9 +178,11 @@ impl HglSolver {
             return Err("Network has no outfall nodes".to_string());
         }
 
+        let mut tailwater_controls = HashMap::new();
         for outfall in outfalls {
-            let tailwater = self.get_tailwater_elevation(outfall)?;
+            let (tailwater, control) = self.get_tailwater_elevation(outfall, network, flows)?;
             node_hgls.insert(outfall.id.clone(), tailwater);
+            tailwater_controls.insert(outfall.id.clone(), control);
 
             // For outfall, EGL = HGL (assume minimal velocity)
             node_egls.insert(outfall.id.clone(), tailwater);
@@ -160,11 +209,21 @@ impl HglSolver {
                 .get(&conduit.to_node)
                 .ok_or_else(|| format!("HGL not computed for node {}", conduit.to_node))?;
 
+            // Velocity carried away from the downstream structure, which
+            // offsets the exit loss (zero into an outfall pool)
+            let downstream_velocity = network
+                .downstream_conduits(&conduit.to_node)
+                .iter()
+                .find_map(|c| conduit_velocities.get(&c.id))
+                .copied()
+                .unwrap_or(0.0);
+
             // Solve for upstream HGL/EGL
             let (upstream_hgl, upstream_egl, conduit_result) = self.solve_conduit(
                 conduit,
                 flow,
                 *downstream_hgl,
+                downstream_velocity,
                 network,
             )?;
 
@@ -177,13 +236,9 @@ impl HglSolver {
                 conduit_velocities.insert(conduit.id.clone(), velocity);
             }
             if let Some(depth) = conduit_result.depth {
-                // Calculate area from depth for circular pipe
-                if let Some(ref pipe) = conduit.pipe {
-                    if let Some(diameter) = pipe.diameter {
-                        let d = diameter / 12.0; // Convert inches to feet
-                        let area = self.circular_pipe_area(d, depth);
-                        conduit_areas.insert(conduit.id.clone(), area);
-                    }
+                // Calculate area from depth for the conduit's section
+                if let Ok(section) = section::from_conduit(conduit, self.config.unit_system) {
+                    conduit_areas.insert(conduit.id.clone(), section.area(depth));
                 }
             }
 
@@ -279,6 +334,14 @@ impl HglSolver {
                     false
                 };
 
+                // Outfalls sit under the tailwater, so surcharge is only
+                // measured at structures within the network
+                let crown = if node.is_outfall() {
+                    None
+                } else {
+                    self.crown_elevation(node, network)
+                };
+
                 node_results.push(NodeResult {
                     node_id: node.id.clone(),
                     hgl: Some(hgl),
@@ -288,19 +351,30 @@ impl HglSolver {
                     flooding: Some(flooding),
                     pressure_head: Some(hgl - node.invert_elevation),
                     junction_loss: node_junction_losses.get(&node.id).copied(),
+                    time_of_peak: None,
+                    surcharge_duration: None,
+                    surcharge_depth: crown.map(|crown| (hgl - crown).max(0.0)),
+                    depth_above_rim: node.rim_elevation.map(|rim| (hgl - rim).max(0.0)),
+                    flood_volume: None,
+                    tailwater_control: tailwater_controls.get(&node.id).copied(),
                 });
 
                 // Check for HGL violations
-                if let Some(rim) = node.rim_elevation {
-                    if hgl > rim {
-                        let violation = Violation::hgl_violation(
-                            node.id.clone(),
-                            hgl,
-                            rim,
-                            Severity::Error,
-                        );
-                        analysis.add_violation(violation);
-                    }
+                if let Some(rim) = node.rim_elevation.filter(|&rim| hgl > rim) {
+                    let violation = Violation::hgl_violation(
+                        node.id.clone(),
+                        hgl,
+                        rim,
+                        Severity::Error,
+                    );
+                    analysis.add_violation(violation);
+                } else if let Some(crown) = crown.filter(|&c| !self.config.allow_surcharge && hgl > c) {
+                    analysis.add_violation(Violation::surcharge_violation(
+                        node.id.clone(),
+                        hgl,
+                        crown,
+                        Severity::Warning,
+                    ));
                 }
             }
         }
@@ -310,37 +384,91 @@ impl HglSolver {
         Ok(analysis)
     }
 
-    /// Get tailwater elevation at outfall
-    fn get_tailwater_elevation(&self, outfall: &Node) -> Result<f64, String> {
+    /// Get tailwater elevation at outfall and the rule that set it
+    ///
+    /// Normal-depth outfalls take the greater of normal depth in the outlet
+    /// conduit at its design flow and any specified tailwater. Under
+    /// [`TailwaterRule::Hec22`] the tailwater is the greater of (dc + D)/2 in
+    /// the outlet pipe and the boundary water level. With several outlet
+    /// conduits the highest water surface governs.
+    fn get_tailwater_elevation(
+        &self,
+        outfall: &Node,
+        network: &Network,
+        flows: &HashMap<String, f64>,
+    ) -> Result<(f64, TailwaterControl), String> {
         let outfall_props = outfall
             .outfall
             .as_ref()
             .ok_or_else(|| "Node is not an outfall".to_string())?;
-
-        match outfall_props.boundary_condition {
-            BoundaryCondition::Free => {
-                // Free outfall: use critical depth at invert
-                Ok(outfall.invert_elevation)
-            }
-            BoundaryCondition::FixedStage => {
-                // Fixed stage: use specified tailwater
+        let invert = outfall.invert_elevation;
+
+        // Invert, normal depth, critical depth and rise of each outlet conduit
+        let outlets: Vec<(f64, Option<f64>, f64, Option<f64>)> = network
+            .upstream_conduits(&outfall.id)
+            .into_iter()
+            .filter_map(|conduit| {
+                let flow = flows.get(&conduit.id).copied().filter(|&q| q > 0.0)?;
+                let manning_n = match (&conduit.pipe, &conduit.channel) {
+                    (Some(pipe), _) => pipe.manning_n,
+                    (None, Some(channel)) => channel.manning_n,
+                    (None, None) => return None,
+                };
+                let reach = Reach {
+                    section: section::from_conduit(conduit, self.config.unit_system).ok()?,
+                    length: conduit.length,
+                    slope: conduit.effective_slope()?,
+                    manning_n,
+                    downstream_invert: conduit.downstream_invert.unwrap_or(invert),
+                };
+                Some((
+                    reach.downstream_invert,
+                    self.direct_step.normal_depth(&reach, flow),
+                    self.direct_step.critical_depth(&reach, flow),
+                    reach.section.crown(),
+                ))
+            })
+            .collect();
+
+        let boundary = match outfall_props.boundary_condition {
+            // Free outfall: the outlet pipe sets its own critical or normal depth
+            BoundaryCondition::Free => (invert, TailwaterControl::Free),
+            BoundaryCondition::FixedStage => (
                 outfall_props
                     .tailwater_elevation
-                    .ok_or_else(|| "Fixed stage outfall missing tailwater elevation".to_string())
-            }
+                    .ok_or_else(|| "Fixed stage outfall missing tailwater elevation".to_string())?,
+                TailwaterControl::ReceivingWater,
+            ),
+            // Normal depth in the outlet conduit, unless a higher receiving
+            // water level is specified
             BoundaryCondition::NormalDepth => {
-                // Normal depth: use specified tailwater or invert
-                Ok(outfall_props
-                    .tailwater_elevation
-                    .unwrap_or(outfall.invert_elevation))
+                let normal = outlets
+                    .iter()
+                    .filter_map(|&(outlet_invert, yn, _, _)| Some(outlet_invert + yn?))
+                    .fold(invert, f64::max);
+                match outfall_props.tailwater_elevation {
+                    Some(tailwater) if tailwater >= normal => (tailwater, TailwaterControl::ReceivingWater),
+                    _ => (normal, TailwaterControl::NormalDepth),
+                }
             }
-            BoundaryCondition::Tidal => {
-                // Tidal: for steady-state analysis, use mean tide level
-                outfall_props
-                    .tailwater_elevation
-                    .ok_or_else(|| "Tidal outfall missing tailwater elevation".to_string())
+            // Tidal: the mean tide, the tide at the time of peak, or the
+            // highest tide over a window
+            BoundaryCondition::Tidal => (
+                outfall_props.steady_tide(self.config.peak_times.get(&outfall.id).copied())?,
+                TailwaterControl::ReceivingWater,
+            ),
+        };
+
+        if outfall_props.tailwater_rule == Some(TailwaterRule::Hec22) {
+            let critical = outlets
+                .iter()
+                .filter_map(|&(outlet_invert, _, yc, rise)| Some(outlet_invert + 0.5 * (yc + rise?)))
+                .reduce(f64::max);
+            if let Some(surface) = critical.filter(|&surface| surface > boundary.0) {
+                return Ok((surface, TailwaterControl::CriticalDepth));
             }
         }
+        Ok(boundary)
     }
 
     /// Calculate access hole loss using FHWA Access Hole Method (Equations 9.11-9.31)
@@ -367,12 +495,7 @@ impl HglSolver {
         let v_outlet = velocities.get(&outlet_conduit.id).cloned().unwrap_or(0.0);
         let a_outlet = areas.get(&outlet_conduit.id).cloned().unwrap_or(1.0);
 
-        // Get outlet pipe diameter (assuming circular pipe)
-        let d_outlet = if let Some(pipe_props) = &outlet_conduit.pipe {
-            pipe_props.diameter.unwrap_or(24.0) / 12.0 // Convert inches to feet
-        } else {
-            2.0 // Default 24" diameter
-        };
+        let d_outlet = self.junction_diameter(outlet_conduit, a_outlet);
 
         // Get outflow EGL at the junction
         let outflow_egl = node_egls.get(&node.id).cloned().unwrap_or(node.invert_elevation);
@@ -385,12 +508,7 @@ impl HglSolver {
             let velocity = velocities.get(&conduit.id).cloned().unwrap_or(0.0);
             let area = areas.get(&conduit.id).cloned().unwrap_or(1.0);
 
-            // Get diameter (assuming circular pipe)
-            let diameter = if let Some(pipe_props) = &conduit.pipe {
-                pipe_props.diameter.unwrap_or(24.0) / 12.0 // inches to feet
-            } else {
-                2.0 // Default 24"
-            };
+            let diameter = self.junction_diameter(conduit, area);
 
             // Determine angle: first pipe is straight through (180°), others at 90°
             let angle = if idx == 0 { 180.0 } else { 90.0 };
@@ -514,27 +632,41 @@ impl HglSolver {
         conduit: &Conduit,
         flow: f64,
         downstream_hgl: f64,
+        downstream_velocity: f64,
         network: &Network,
     ) -> Result<(f64, f64, ConduitResult), String> {
         match conduit.conduit_type {
-            ConduitType::Pipe => self.solve_pipe(conduit, flow, downstream_hgl, network),
+            ConduitType::Pipe => {
+                self.solve_pipe(conduit, flow, downstream_hgl, downstream_velocity, network)
+            }
             ConduitType::Gutter => {
                 // For now, simplified gutter solution
                 Ok((downstream_hgl, downstream_hgl, self.default_conduit_result(conduit, flow)))
             }
-            ConduitType::Channel => {
-                // For now, simplified channel solution
-                Ok((downstream_hgl, downstream_hgl, self.default_conduit_result(conduit, flow)))
-            }
+            ConduitType::Channel => self.solve_channel(conduit, flow, downstream_hgl, network),
         }
     }
 
     /// Solve for HGL/EGL through a pipe
+    ///
+    /// The water surface entering the outlet end is the higher of the
+    /// downstream HGL and normal depth, and never below the crown once the
+    /// flow exceeds full-pipe capacity. The barrel profile is traced from
+    /// there by the direct-step method, with friction slope from conveyance
+    /// capped at full-pipe conveyance, so a pressurized run follows HEC-22
+    /// Equation 9.4 and eases into partial flow as the pipe unsubmerges.
+    /// The upstream HGL adds minor losses to the profile's upstream water
+    /// surface but is not allowed to fall below normal depth (Table 9.7,
+    /// conditions A–C). Flat and adverse pipes have no normal depth and
+    /// run full, with the full-pipe friction slope over their length. Box,
+    /// elliptical and arch pipes use their own geometry throughout. Pipes
+    /// flagged as culverts are handed to [`Self::solve_culvert`] instead.
     fn solve_pipe(
         &self,
         conduit: &Conduit,
         flow: f64,
         downstream_hgl: f64,
+        downstream_velocity: f64,
         network: &Network,
     ) -> Result<(f64, f64, ConduitResult), String> {
         let pipe_props = conduit
@@ -542,10 +674,11 @@ impl HglSolver {
             .as_ref()
             .ok_or_else(|| "Conduit is not a pipe".to_string())?;
 
-        let diameter = pipe_props
-            .diameter
-            .ok_or_else(|| "Pipe diameter not specified".to_string())?
-            / 12.0; // Convert inches to feet
+        let section = section::from_conduit(conduit, self.config.unit_system)?;
+        let rise = section
+            .crown()
+            .ok_or_else(|| format!("Pipe {} is not a closed section", conduit.id))?;
+        let manning_n = pipe_props.manning_n;
 
         let slope = conduit
             .effective_slope()
@@ -563,92 +696,136 @@ impl HglSolver {
             .upstream_invert
             .unwrap_or(downstream_invert + slope * conduit.length);
 
-        // Calculate flow properties
-        let flow_result = if flow > 0.0 {
-            let q_full = self.mannings.full_pipe_capacity(diameter, slope, pipe_props.manning_n);
-
-            if flow >= q_full {
-                // Pressurized flow
-                self.mannings.partial_pipe_flow(
-                    diameter,
-                    diameter,
-                    slope,
-                    pipe_props.manning_n,
-                    self.config.gravity,
-                )
-            } else {
-                // Calculate normal depth
-                let yn = self.mannings.normal_depth(
-                    flow,
-                    diameter,
-                    slope,
-                    pipe_props.manning_n,
-                    self.config.gravity,
-                );
-
-                if let Some(depth) = yn {
-                    self.mannings.partial_pipe_flow(
-                        diameter,
-                        depth,
-                        slope,
-                        pipe_props.manning_n,
-                        self.config.gravity,
-                    )
-                } else {
-                    return Err("Could not calculate normal depth".to_string());
-                }
-            }
-        } else {
+        if flow <= 0.0 {
             return Ok((
                 downstream_hgl,
                 downstream_hgl,
                 self.default_conduit_result(conduit, flow),
             ));
+        }
+
+        let reach = Reach {
+            section,
+            length: conduit.length,
+            slope,
+            manning_n,
+            downstream_invert,
         };
+        if let Some(culvert) = &pipe_props.culvert {
+            let culvert = Culvert {
+                reach,
+                rise,
+                inlet: culvert.inlet,
+                entrance_loss: pipe_props.entrance_loss.unwrap_or(culvert.inlet.entrance_loss()),
+                exit_loss: pipe_props.exit_loss.unwrap_or(1.0),
+                bend_loss: pipe_props.bend_loss.unwrap_or(0.0),
+                roadway: culvert.roadway.clone(),
+            };
+            return Ok(self.solve_culvert(conduit, &culvert, flow, downstream_hgl, downstream_velocity));
+        }
 
-        // Calculate energy losses
-        let friction_loss = self.energy_loss.friction_loss(
-            flow,
-            conduit.length,
-            flow_result.area,
-            flow_result.hydraulic_radius,
-            pipe_props.manning_n,
-            self.config.manning_k,
-        );
+        let full_conveyance = self
+            .direct_step
+            .full_conveyance(&reach)
+            .ok_or_else(|| "Could not calculate full-pipe capacity".to_string())?;
+
+        // A flat or adverse pipe has no normal depth or capacity and is
+        // taken as pressurized, with friction slope (Q/K_full)² along its
+        // length in place of a profile
+        let downhill = reach.slope > 0.0;
+        let q_full = self.direct_step.full_capacity(&reach).unwrap_or(0.0);
+
+        // Normal depth is the full rise once flow exceeds capacity
+        let normal_depth = if downhill {
+            self.direct_step
+                .normal_depth(&reach, flow)
+                .ok_or_else(|| "Could not calculate normal depth".to_string())?
+        } else {
+            rise
+        };
+
+        // Water surface at the outlet end and the depth it fills in the pipe
+        let outlet_surface = downstream_hgl.max(downstream_invert + normal_depth);
+        let outlet_depth = (outlet_surface - downstream_invert).min(rise);
+        let surcharged = flow >= q_full || outlet_surface >= downstream_invert + rise;
+
+        // A steep pipe runs at normal depth from its upstream end (Table 9.7,
+        // condition D) until tailwater backing up the barrel forces a jump.
+        // The jump takes over the whole pipe once pushed past the upstream end.
+        let critical_depth = self.direct_step.critical_depth(&reach, flow);
+        let upstream_control = (flow < q_full && normal_depth < critical_depth).then_some(normal_depth);
+        let profile = if downhill {
+            Some(self.direct_step.profile(
+                &reach,
+                flow,
+                upstream_control,
+                outlet_surface - downstream_invert,
+            )?)
+        } else {
+            None
+        };
+        let supercritical = upstream_control.is_some()
+            && profile.as_ref().is_some_and(|p| p.upstream().depth < critical_depth);
+
+        let depth = if supercritical { normal_depth } else { outlet_depth };
+        let area = reach.section.area(depth);
+        let velocity = flow / area;
+        let velocity_head = velocity.powi(2) / (2.0 * self.config.gravity);
+        let friction_loss = profile
+            .as_ref()
+            .map_or((flow / full_conveyance).powi(2) * conduit.length, |p| p.friction_loss);
+        let upstream_surface = profile
+            .as_ref()
+            .map_or(outlet_surface + friction_loss, |p| p.upstream().water_surface);
 
         let entrance_loss = self.energy_loss.entrance_loss(
-            flow_result.velocity,
+            velocity,
             pipe_props.entrance_loss.unwrap_or(0.5),
         );
 
         let exit_loss = self.energy_loss.exit_loss(
-            flow_result.velocity,
-            0.0, // Assume zero downstream velocity for now
+            velocity,
+            downstream_velocity,
             pipe_props.exit_loss.unwrap_or(1.0),
         );
 
         let bend_loss = if let Some(k_bend) = pipe_props.bend_loss {
-            k_bend * flow_result.velocity_head
+            k_bend * velocity_head
         } else {
             0.0
         };
 
-        let total_loss = friction_loss + entrance_loss + exit_loss + bend_loss;
+        let minor_loss = entrance_loss + exit_loss + bend_loss;
+        let total_loss = friction_loss + minor_loss;
 
-        // Calculate upstream HGL/EGL
-        let downstream_egl = downstream_hgl + flow_result.velocity_head;
-        let upstream_egl = downstream_egl + total_loss;
-        let upstream_hgl = upstream_egl - flow_result.velocity_head;
+        // Calculate upstream HGL/EGL; losses in a supercritical pipe are not
+        // carried upstream
+        let upstream_hgl = if supercritical {
+            upstream_invert + normal_depth
+        } else {
+            (upstream_surface + minor_loss).max(upstream_invert + normal_depth)
+        };
+        let upstream_area = reach.section.area(upstream_hgl - upstream_invert);
+        let upstream_egl = upstream_hgl + (flow / upstream_area).powi(2) / (2.0 * self.config.gravity);
+
+        // Pressure flow has no free surface, so no Froude number
+        let (froude_number, flow_regime) = if surcharged && !supercritical {
+            (None, crate::analysis::FlowRegime::Subcritical)
+        } else {
+            let froude = reach.section.froude_number(flow, depth, self.config.gravity);
+            (Some(froude), self.flow_regime(froude))
+        };
 
         // Build conduit result
         let conduit_result = ConduitResult {
             conduit_id: conduit.id.clone(),
             flow: Some(flow),
-            velocity: Some(flow_result.velocity),
-            depth: Some(flow_result.depth),
-            capacity_used: Some(flow / self.mannings.full_pipe_capacity(diameter, slope, pipe_props.manning_n)),
-            froude_number: None, // Calculate if needed
-            flow_regime: Some(crate::analysis::FlowRegime::Subcritical), // Simplified
+            velocity: Some(velocity),
+            depth: Some(depth),
+            capacity_used: (q_full > 0.0).then(|| flow / q_full),
+            froude_number,
+            flow_regime: Some(flow_regime),
+            jump_location: profile.as_ref().and_then(|p| p.jump_location),
             headloss: Some(HeadLoss {
                 friction: Some(friction_loss),
                 entrance: Some(entrance_loss),
@@ -656,12 +833,217 @@ impl HglSolver {
                 bend: Some(bend_loss),
                 total: Some(total_loss),
             }),
+            profile,
+            culvert: None,
+            time_of_peak: None,
+            time_of_concentration: None,
+            intensity: None,
+            sum_ca: None,
+            travel_time: None,
+        };
+
+        Ok((upstream_hgl, upstream_egl, conduit_result))
+    }
+
+    /// Solve for the headwater of a culvert
+    ///
+    /// The governing HDS-5 inlet or outlet control headwater is the HGL at
+    /// the upstream node. HDS-5 neglects the approach velocity, so the EGL
+    /// there equals the headwater. Velocity and depth are reported at the
+    /// outlet for the barrel flow, and barrel losses only under outlet
+    /// control, where they set the headwater.
+    fn solve_culvert(
+        &self,
+        conduit: &Conduit,
+        culvert: &Culvert,
+        flow: f64,
+        tailwater: f64,
+        downstream_velocity: f64,
+    ) -> (f64, f64, ConduitResult) {
+        let result = self.culvert.analyze(culvert, flow, tailwater, downstream_velocity);
+        let barrel_flow = result.barrel_flow;
+        let depth = self.culvert.outlet_depth(culvert, &result, tailwater);
+        let section = &culvert.reach.section;
+        let velocity = barrel_flow / section.area(depth);
+
+        // A full outlet has no free surface, so no Froude number
+        let (froude_number, flow_regime) = if depth >= culvert.rise {
+            (None, crate::analysis::FlowRegime::Subcritical)
+        } else {
+            let froude = section.froude_number(barrel_flow, depth, self.config.gravity);
+            (Some(froude), self.flow_regime(froude))
+        };
+
+        let headloss = (result.control == CulvertControl::Outlet).then(|| {
+            let outlet = self
+                .culvert
+                .outlet_control(culvert, barrel_flow, tailwater, downstream_velocity);
+            HeadLoss {
+                friction: Some(outlet.friction),
+                entrance: Some(outlet.entrance),
+                exit: Some(outlet.exit),
+                bend: Some(outlet.bend),
+                total: Some(outlet.losses()),
+            }
+        });
+
+        let headwater = result.headwater;
+        let conduit_result = ConduitResult {
+            conduit_id: conduit.id.clone(),
+            flow: Some(flow),
+            velocity: Some(velocity),
+            depth: Some(depth),
+            capacity_used: self
+                .direct_step
+                .full_capacity(&culvert.reach)
+                .map(|q_full| barrel_flow / q_full),
+            froude_number,
+            flow_regime: Some(flow_regime),
+            jump_location: None,
+            headloss,
+            profile: None,
+            culvert: Some(result),
+            time_of_peak: None,
+            time_of_concentration: None,
+            intensity: None,
+            sum_ca: None,
+            travel_time: None,
+        };
+
+        (headwater, headwater, conduit_result)
+    }
+
+    /// Solve for HGL/EGL through an open channel
+    ///
+    /// Trapezoidal, rectangular, triangular and natural sections are traced
+    /// with the same direct-step profile as pipes. The water surface entering
+    /// the downstream end is the higher of the downstream HGL and normal
+    /// depth; a channel on a flat or adverse grade has no normal depth and is
+    /// left to the profile's critical depth control. Channels carry no
+    /// entrance or exit losses, so only friction is reported.
+    fn solve_channel(
+        &self,
+        conduit: &Conduit,
+        flow: f64,
+        downstream_hgl: f64,
+        network: &Network,
+    ) -> Result<(f64, f64, ConduitResult), String> {
+        let channel = conduit
+            .channel
+            .as_ref()
+            .ok_or_else(|| "Conduit is not a channel".to_string())?;
+
+        let slope = conduit
+            .effective_slope()
+            .ok_or_else(|| "Channel slope cannot be determined".to_string())?;
+
+        let downstream_node = network
+            .find_node(&conduit.to_node)
+            .ok_or_else(|| format!("Downstream node {} not found", conduit.to_node))?;
+
+        let downstream_invert = conduit
+            .downstream_invert
+            .unwrap_or(downstream_node.invert_elevation);
+
+        let upstream_invert = conduit
+            .upstream_invert
+            .unwrap_or(downstream_invert + slope * conduit.length);
+
+        if flow <= 0.0 {
+            return Ok((
+                downstream_hgl,
+                downstream_hgl,
+                self.default_conduit_result(conduit, flow),
+            ));
+        }
+
+        let reach = Reach {
+            section: section::from_conduit(conduit, self.config.unit_system)?,
+            length: conduit.length,
+            slope,
+            manning_n: channel.manning_n,
+            downstream_invert,
+        };
+        let normal_depth = self.direct_step.normal_depth(&reach, flow);
+        let critical_depth = self.direct_step.critical_depth(&reach, flow);
+
+        let outlet_surface = normal_depth
+            .map_or(downstream_hgl, |yn| downstream_hgl.max(downstream_invert + yn));
+        let upstream_control = normal_depth.filter(|&yn| yn < critical_depth);
+        let profile = self.direct_step.profile(
+            &reach,
+            flow,
+            upstream_control,
+            outlet_surface - downstream_invert,
+        )?;
+        let supercritical = upstream_control.is_some() && profile.upstream().depth < critical_depth;
+
+        // Report the upstream depth of a supercritical channel and the outlet
+        // depth otherwise, as for pipes
+        let depth = match (supercritical, normal_depth) {
+            (true, Some(yn)) => yn,
+            _ => profile.downstream().depth,
+        };
+        let area = reach.section.area(depth);
+        let velocity = flow / area;
+        let froude = reach.section.froude_number(flow, depth, self.config.gravity);
+        let flow_regime = self.flow_regime(froude);
+
+        let friction_loss = profile.friction_loss;
+        let upstream_surface = profile.upstream().water_surface;
+        let upstream_hgl = match normal_depth {
+            Some(yn) if supercritical => upstream_invert + yn,
+            Some(yn) => upstream_surface.max(upstream_invert + yn),
+            None => upstream_surface,
+        };
+        let upstream_area = reach.section.area(upstream_hgl - upstream_invert);
+        let upstream_egl = upstream_hgl + (flow / upstream_area).powi(2) / (2.0 * self.config.gravity);
+
+        // Only a surveyed natural section has a defined bank-full capacity
+        let capacity_used = reach
+            .section
+            .max_depth()
+            .filter(|_| slope > 0.0)
+            .map(|bank| flow / (reach.section.conveyance(bank, channel.manning_n, self.config.manning_k) * slope.sqrt()));
+
+        let conduit_result = ConduitResult {
+            conduit_id: conduit.id.clone(),
+            flow: Some(flow),
+            velocity: Some(velocity),
+            depth: Some(depth),
+            capacity_used,
+            froude_number: Some(froude),
+            flow_regime: Some(flow_regime),
+            jump_location: profile.jump_location,
+            headloss: Some(HeadLoss {
+                friction: Some(friction_loss),
+                entrance: None,
+                exit: None,
+                bend: None,
+                total: Some(friction_loss),
+            }),
+            profile: Some(profile),
+            culvert: None,
+            time_of_peak: None,
+            time_of_concentration: None,
+            intensity: None,
+            sum_ca: None,
+            travel_time: None,
         };
 
         Ok((upstream_hgl, upstream_egl, conduit_result))
     }
 
-    /// Create default conduit result for non-pipe conduits
+    /// Flow regime reported for a Froude number
+    fn flow_regime(&self, froude: f64) -> crate::analysis::FlowRegime {
+        match self.mannings.flow_regime(froude) {
+            FlowRegime::Subcritical => crate::analysis::FlowRegime::Subcritical,
+            FlowRegime::Critical => crate::analysis::FlowRegime::Critical,
+            FlowRegime::Supercritical => crate::analysis::FlowRegime::Supercritical,
+        }
+    }
+
+    /// Create default conduit result for gutters and conduits without flow
     fn default_conduit_result(&self, conduit: &Conduit, flow: f64) -> ConduitResult {
         ConduitResult {
             conduit_id: conduit.id.clone(),
@@ -671,36 +1053,48 @@ impl HglSolver {
             capacity_used: None,
             froude_number: None,
             flow_regime: None,
+            jump_location: None,
             headloss: None,
+            profile: None,
+            culvert: None,
+            time_of_peak: None,
+            time_of_concentration: None,
+            intensity: None,
+            sum_ca: None,
+            travel_time: None,
         }
     }
 
-    /// Calculate flow area for a circular pipe at a given depth
+    /// Diameter used in the access hole loss equations (ft or m)
     ///
-    /// Uses the circular segment formula:
-    /// A = (D²/4) × (θ - sin(θ))
-    /// where θ is the central angle in radians
-    fn circular_pipe_area(&self, diameter: f64, depth: f64) -> f64 {
-        if depth <= 0.0 {
-            return 0.0;
-        }
-        if depth >= diameter {
-            // Full pipe
-            return std::f64::consts::PI * diameter * diameter / 4.0;
-        }
-
-        // Partial flow - calculate area of circular segment
-        let r = diameter / 2.0;
-        let h = depth;
-
-        // Central angle θ = 2 × arccos((r - h) / r)
-        let cos_half_theta = (r - h) / r;
-        let theta = 2.0 * cos_half_theta.acos();
+    /// Closed conduits use their rise; open channels use the diameter of a
+    /// circle with the same flow area.
+    fn junction_diameter(&self, conduit: &Conduit, area: f64) -> f64 {
+        section::from_conduit(conduit, self.config.unit_system)
+            .ok()
+            .and_then(|section| section.crown())
+            .unwrap_or_else(|| (4.0 * area / std::f64::consts::PI).sqrt())
+    }
 
-        // Area = (r² / 2) × (θ - sin(θ))
-        let area = (r * r / 2.0) * (theta - theta.sin());
+    /// Highest crown elevation among the pipes connected to a node
+    fn crown_elevation(&self, node: &Node, network: &Network) -> Option<f64> {
+        let crown = |conduit: &Conduit, invert: Option<f64>| {
+            conduit.pipe.as_ref()?;
+            let rise = section::from_conduit(conduit, self.config.unit_system).ok()?.crown()?;
+            Some(invert.unwrap_or(node.invert_elevation) + rise)
+        };
 
-        area
+        network
+            .upstream_conduits(&node.id)
+            .into_iter()
+            .filter_map(|c| crown(c, c.downstream_invert))
+            .chain(
+                network
+                    .downstream_conduits(&node.id)
+                    .into_iter()
+                    .filter_map(|c| crown(c, c.upstream_invert)),
+            )
+            .fold(None, |acc: Option<f64>, c| Some(acc.map_or(c, |a| a.max(c))))
     }
 
     /// Perform topological sort to get conduit processing order
@@ -793,17 +1187,21 @@ pub fn compute_rational_flows(
 /// Route node inflows through network to get conduit flows
 ///
 /// Performs a topological traversal from outfalls upstream,
-/// accumulating flows at each junction.
+/// accumulating flows at each junction. Nodes with a flow divider split
+/// their outflow by it; other nodes share it equally among their
+/// downstream conduits.
 ///
 /// # Arguments
 /// * `network` - The drainage network
 /// * `node_inflows` - Direct inflows at each node (from drainage areas)
+/// * `unit_system` - Unit system for weir and orifice dividers
 ///
 /// # Returns
 /// Map of conduit ID to flow rate
 pub fn route_flows(
     network: &Network,
     node_inflows: &HashMap<String, f64>,
+    unit_system: UnitSystem,
 ) -> Result<HashMap<String, f64>, String> {
     let mut conduit_flows = HashMap::new();
     let mut node_total_flows: HashMap<String, f64> = HashMap::new();
@@ -822,23 +1220,171 @@ pub fn route_flows(
         let node_flow = node_total_flows.get(&node_id).cloned().unwrap_or(0.0);
 
         // Route flow to downstream conduits
+        let node = network
+            .find_node(&node_id)
+            .ok_or_else(|| format!("Node {} not found", node_id))?;
         let downstream_conduits = network.downstream_conduits(&node_id);
-        if !downstream_conduits.is_empty() {
-            let flow_per_conduit = node_flow / downstream_conduits.len() as f64;
+        for (conduit, flow) in split_outflow(network, node, &downstream_conduits, node_flow, unit_system)? {
+            conduit_flows.insert(conduit.id.clone(), flow);
+
+            // Add this flow to the total for the downstream node
+            let downstream_flow = node_total_flows
+                .entry(conduit.to_node.clone())
+                .or_insert(0.0);
+            *downstream_flow += flow;
+        }
+    }
 
-            for conduit in downstream_conduits {
-                conduit_flows.insert(conduit.id.clone(), flow_per_conduit);
+    Ok(conduit_flows)
+}
 
-                // Add this flow to the total for the downstream node
-                let downstream_flow = node_total_flows
-                    .entry(conduit.to_node.clone())
-                    .or_insert(0.0);
-                *downstream_flow += flow_per_conduit;
-            }
+/// Split a node's outflow among its downstream conduits
+///
+/// With a flow divider, the diverted flow goes to the diverted conduit and
+/// the rest to the one other conduit. Without one, the flow is shared
+/// equally.
+pub(crate) fn split_outflow<'a>(
+    network: &Network,
+    node: &Node,
+    conduits: &[&'a Conduit],
+    flow: f64,
+    unit_system: UnitSystem,
+) -> Result<Vec<(&'a Conduit, f64)>, String> {
+    let Some(ref divider) = node.divider else {
+        let flow_per_conduit = flow / conduits.len().max(1) as f64;
+        return Ok(conduits.iter().map(|&c| (c, flow_per_conduit)).collect());
+    };
+
+    let diverted = conduits
+        .iter()
+        .copied()
+        .find(|c| c.id == divider.diverted_conduit)
+        .ok_or_else(|| {
+            format!(
+                "Divider at {} diverts to {}, which does not leave the node",
+                node.id, divider.diverted_conduit
+            )
+        })?;
+    let continuing = match conduits
+        .iter()
+        .copied()
+        .filter(|c| c.id != divider.diverted_conduit)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [conduit] => *conduit,
+        _ => {
+            return Err(format!(
+                "Divider at {} needs exactly one continuing conduit besides {}",
+                node.id, divider.diverted_conduit
+            ))
         }
+    };
+
+    let diverted_flow = diverted_flow(network, divider, continuing, flow, unit_system)?
+        .clamp(0.0, flow.max(0.0));
+    Ok(vec![(diverted, diverted_flow), (continuing, flow - diverted_flow)])
+}
+
+/// Flow a divider sends to its diverted conduit
+///
+/// Weir and orifice dividers take the stage at the node as the normal
+/// depth of the continuing flow in the continuing conduit, of any pipe or
+/// channel shape, and find the split where the overflow at that stage is
+/// the diverted flow. Flow beyond a continuing pipe's full capacity is
+/// always diverted.
+fn diverted_flow(
+    network: &Network,
+    divider: &FlowDivider,
+    continuing: &Conduit,
+    flow: f64,
+    unit_system: UnitSystem,
+) -> Result<f64, String> {
+    let config = match unit_system {
+        UnitSystem::US => SolverConfig::us_customary(),
+        UnitSystem::SI => SolverConfig::si_metric(),
+    };
+    match divider.method {
+        DiversionMethod::Fraction { fraction } => return Ok(flow * fraction.clamp(0.0, 1.0)),
+        DiversionMethod::Rating { ref table } => return Ok(interpolate_rating(table, flow)),
+        DiversionMethod::Weir { .. } | DiversionMethod::Orifice { .. } => {}
     }
+    let overflow = |stage: f64| match divider.method {
+        DiversionMethod::Weir { crest_height, length, coefficient } => {
+            let coefficient = coefficient.unwrap_or(match unit_system {
+                UnitSystem::US => 3.0,
+                UnitSystem::SI => 1.66,
+            });
+            coefficient * length * (stage - crest_height).max(0.0).powf(1.5)
+        }
+        DiversionMethod::Orifice { center_height, area, coefficient } => {
+            let head = (stage - center_height).max(0.0);
+            coefficient.unwrap_or(0.6) * area * (2.0 * config.gravity * head).sqrt()
+        }
+        DiversionMethod::Fraction { .. } | DiversionMethod::Rating { .. } => 0.0,
+    };
 
-    Ok(conduit_flows)
+    let section = section::from_conduit(continuing, unit_system)?;
+    let manning_n = match (&continuing.pipe, &continuing.channel) {
+        (Some(pipe), _) => pipe.manning_n,
+        (None, Some(channel)) => channel.manning_n,
+        (None, None) => 0.013,
+    };
+    let slope = network
+        .conduit_slope(continuing)
+        .filter(|s| *s > 0.0)
+        .ok_or_else(|| format!("Divider continuing conduit {} needs a positive slope", continuing.id))?;
+
+    let stage = |continuing_flow: f64| {
+        section
+            .normal_depth(continuing_flow, slope, manning_n, config.manning_k)
+            .or(section.max_depth())
+            .unwrap_or(0.0)
+    };
+    let excess = |diverted: f64| overflow(stage(flow - diverted)) - diverted;
+
+    // Diverting more lowers the stage, so the excess falls as the
+    // diverted flow rises
+    let full_capacity = section
+        .crown()
+        .map_or(f64::INFINITY, |crown| section.conveyance(crown, manning_n, config.manning_k) * slope.sqrt());
+    let mut low = (flow - full_capacity).max(0.0);
+    if excess(low) <= 0.0 {
+        return Ok(low);
+    }
+    let mut high = flow;
+    for _ in 0..60 {
+        let mid = (low + high) / 2.0;
+        if excess(mid) > 0.0 {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    Ok((low + high) / 2.0)
+}
+
+/// Diverted flow from a rating table by linear interpolation
+///
+/// Inflows outside the table hold the diverted flow at the nearest end,
+/// and the diversion never exceeds the inflow.
+fn interpolate_rating(table: &[(f64, f64)], inflow: f64) -> f64 {
+    let diverted = match table {
+        [] => 0.0,
+        [(q, d), ..] if inflow <= *q => *d,
+        [.., (q, d)] if inflow >= *q => *d,
+        _ => table
+            .windows(2)
+            .find(|w| inflow <= w[1].0)
+            .map(|w| {
+                let (q0, d0) = w[0];
+                let (q1, d1) = w[1];
+                d0 + (d1 - d0) * (inflow - q0) / (q1 - q0)
+            })
+            .unwrap_or(0.0),
+    };
+    diverted.min(inflow)
 }
 
 /// Inlet interception tracking for flow routing
@@ -856,6 +1402,17 @@ pub struct InletInterception {
     pub efficiency: f64,
     /// Gutter spread at inlet (ft)
     pub spread: f64,
+    /// Gutter section used for spread and velocity
+    pub gutter_section: Option<GutterSection>,
+    /// Ponding depth at the curb for sag inlets (ft)
+    pub ponding_depth: Option<f64>,
+    /// Node receiving the bypass flow, or `None` if it leaves the system
+    pub bypass_to: Option<String>,
+    /// Nodes the bypass passes along the surface until it is captured or leaves
+    pub bypass_chain: Vec<String>,
+    /// Surface flow leaving the system at the end of the bypass chain (cfs),
+    /// including carryover from other inlets joining the chain
+    pub lost_flow: f64,
 }
 
 /// Route flows through network accounting for inlet interception
@@ -863,8 +1420,18 @@ pub struct InletInterception {
 /// This enhanced routing function:
 /// 1. Routes flows from upstream to downstream
 /// 2. At each inlet node, calculates inlet interception efficiency
-/// 3. Tracks bypass flows that continue in gutters to downstream inlets
-/// 4. Sag inlets capture 100% of flow
+/// 3. Carries bypass along the surface to the inlet's `bypass_to` node, or
+///    else along its downstream gutter conduit
+/// 4. Sag inlets capture flow up to their capacity at the curb height,
+///    ponding to the depth their weir or orifice flow requires; overflow
+///    continues like bypass
+///
+/// Surface flow (runoff and carryover) and piped flow are kept apart: only
+/// surface flow approaches an inlet, and only intercepted flow enters the
+/// pipes. Nodes that are not inlets pass surface flow along a downstream
+/// gutter if they have one and otherwise take it into the system. Bypass
+/// from an inlet with nowhere to go leaves the system and is reported as
+/// `lost_flow`.
 ///
 /// # Arguments
 /// * `network` - The drainage network
@@ -872,79 +1439,131 @@ pub struct InletInterception {
 /// * `unit_system` - Unit system for gutter calculations
 ///
 /// # Returns
-/// Tuple of (conduit flows, inlet interception results)
+/// Tuple of (conduit flows, inlet interception results). Gutter conduits
+/// carry the surface flow passed along them.
 pub fn route_flows_with_inlets(
     network: &Network,
     node_inflows: &HashMap<String, f64>,
     unit_system: UnitSystem,
+) -> Result<(HashMap<String, f64>, Vec<InletInterception>), String> {
+    route_flows_with_gutters(network, node_inflows, &HashMap::new(), unit_system)
+}
+
+/// Route flows through network with gutter sections given per inlet
+///
+/// Works like [`route_flows_with_inlets`], with the gutter at each on-grade
+/// inlet taken from `gutters` (for example rows of `gutter_parameters.csv`),
+/// else from the gutter conduit approaching the inlet, else a uniform
+/// n = 0.016, S_x = 0.02, S_L = 0.01 gutter.
+///
+/// # Arguments
+/// * `network` - The drainage network
+/// * `node_inflows` - Direct inflows at each node (from drainage areas)
+/// * `gutters` - Gutter properties keyed by inlet node ID
+/// * `unit_system` - Unit system for gutter calculations
+pub fn route_flows_with_gutters(
+    network: &Network,
+    node_inflows: &HashMap<String, f64>,
+    gutters: &HashMap<String, GutterProperties>,
+    unit_system: UnitSystem,
 ) -> Result<(HashMap<String, f64>, Vec<InletInterception>), String> {
     let mut conduit_flows = HashMap::new();
-    let mut node_total_flows: HashMap<String, f64> = HashMap::new();
-    let mut bypass_flows: HashMap<String, f64> = HashMap::new();
+    let mut surface_flows: HashMap<String, f64> = node_inflows.clone();
+    let mut system_flows: HashMap<String, f64> = HashMap::new();
+    let mut surface_targets: HashMap<String, String> = HashMap::new();
+    let mut carried_flows: HashMap<String, f64> = HashMap::new();
     let mut inlet_results = Vec::new();
 
-    let k = match unit_system {
-        UnitSystem::US => GUTTER_K_US,
-        UnitSystem::SI => GUTTER_K_SI,
-    };
-
-    // Initialize with direct inflows
-    for (node_id, &flow) in node_inflows {
-        node_total_flows.insert(node_id.clone(), flow);
-    }
-
-    // Get traversal order
-    let sorted_nodes = topological_sort_upstream_to_downstream(network)?;
+    // Bypass targets are surface links, so they take part in the ordering
+    let bypass_links: Vec<(String, String)> = network
+        .nodes
+        .iter()
+        .filter_map(|n| {
+            let target = n.inlet.as_ref()?.bypass_to.as_ref()?;
+            Some((n.id.clone(), target.clone()))
+        })
+        .collect();
+    let sorted_nodes = topological_sort_with_links(network, &bypass_links)?;
 
-    // Process nodes in topological order
     for node_id in sorted_nodes {
-
-        // Find the node
         let node = network
-            .nodes
-            .iter()
-            .find(|n| n.id == node_id)
+            .find_node(&node_id)
             .ok_or_else(|| format!("Node {} not found", node_id))?;
+        let approach_flow = surface_flows.get(&node_id).copied().unwrap_or(0.0);
+        let surface_target = surface_target(network, node)?;
+
+        // Split surface flow into captured and carried
+        let (captured_flow, carried_flow) = if let Some(ref inlet_props) = node.inlet {
+            let gutter = inlet_gutter(network, node, gutters);
+            let max_ponding_depth = gutter
+                .and_then(|g| g.curb_height)
+                .map(|h| match unit_system {
+                    UnitSystem::US => h / 12.0,
+                    UnitSystem::SI => h / 1000.0,
+                });
+            let (intercepted_flow, bypass_flow, interception_result) = calculate_inlet_interception(
+                node,
+                inlet_props,
+                approach_flow,
+                &gutter_section(gutter, unit_system),
+                max_ponding_depth,
+                unit_system,
+            )?;
+            if let Some(mut result) = interception_result {
+                result.bypass_to = surface_target.clone();
+                inlet_results.push(result);
+            }
+            (intercepted_flow, bypass_flow)
+        } else if surface_target.is_some() {
+            (0.0, approach_flow)
+        } else {
+            (approach_flow, 0.0)
+        };
 
-        // Get total flow approaching this node
-        let direct_inflow = node_total_flows.get(&node_id).cloned().unwrap_or(0.0);
-        let bypass_inflow = bypass_flows.get(&node_id).cloned().unwrap_or(0.0);
-        let approach_flow = direct_inflow + bypass_inflow;
-
-        // Determine intercepted vs bypass flow
-        let (intercepted_flow, bypass_flow, interception_result) =
-            if let Some(ref inlet_props) = node.inlet {
-                // This is an inlet - calculate interception
-                calculate_inlet_interception(node, inlet_props, approach_flow, k)?
+        // Carry the surface flow on, along the gutter where there is one
+        for gutter in network
+            .downstream_conduits(&node_id)
+            .into_iter()
+            .filter(|c| c.conduit_type == ConduitType::Gutter)
+        {
+            let flow = if surface_target.as_deref() == Some(gutter.to_node.as_str()) {
+                carried_flow
             } else {
-                // Not an inlet - all flow enters system
-                (approach_flow, 0.0, None)
+                0.0
             };
-
-        // Store inlet interception result
-        if let Some(result) = interception_result {
-            inlet_results.push(result);
+            conduit_flows.insert(gutter.id.clone(), flow);
+        }
+        if let Some(target) = surface_target {
+            *surface_flows.entry(target.clone()).or_insert(0.0) += carried_flow;
+            surface_targets.insert(node_id.clone(), target);
+        }
+        carried_flows.insert(node_id.clone(), carried_flow);
+
+        // Route the system flow through the pipes
+        let system_flow = system_flows.get(&node_id).copied().unwrap_or(0.0) + captured_flow;
+        let pipes: Vec<&Conduit> = network
+            .downstream_conduits(&node_id)
+            .into_iter()
+            .filter(|c| c.conduit_type != ConduitType::Gutter)
+            .collect();
+        for (conduit, flow) in split_outflow(network, node, &pipes, system_flow, unit_system)? {
+            conduit_flows.insert(conduit.id.clone(), flow);
+            *system_flows.entry(conduit.to_node.clone()).or_insert(0.0) += flow;
         }
+    }
 
-        // Route intercepted flow to downstream conduits
-        let downstream_conduits = network.downstream_conduits(&node_id);
-        if !downstream_conduits.is_empty() {
-            let flow_per_conduit = intercepted_flow / downstream_conduits.len() as f64;
-
-            for conduit in downstream_conduits {
-                // Add intercepted flow to the underground system
-                conduit_flows.insert(conduit.id.clone(), flow_per_conduit);
-                let downstream_total = node_total_flows
-                    .entry(conduit.to_node.clone())
-                    .or_insert(0.0);
-                *downstream_total += flow_per_conduit;
-
-                // Add bypass flow to the downstream gutter
-                if bypass_flow > 0.0 {
-                    let downstream_bypass = bypass_flows
-                        .entry(conduit.to_node.clone())
-                        .or_insert(0.0);
-                    *downstream_bypass += bypass_flow;
+    // Follow each inlet's bypass across the surface
+    for result in &mut inlet_results {
+        let mut current = result.node_id.clone();
+        while carried_flows.get(&current).copied().unwrap_or(0.0) > 0.0 {
+            match surface_targets.get(&current) {
+                Some(next) => {
+                    result.bypass_chain.push(next.clone());
+                    current = next.clone();
+                }
+                None => {
+                    result.lost_flow = carried_flows[&current];
+                    break;
                 }
             }
         }
@@ -953,6 +1572,136 @@ pub fn route_flows_with_inlets(
     Ok((conduit_flows, inlet_results))
 }
 
+/// Node receiving surface flow that passes a node: an inlet's `bypass_to`
+/// target, or else the end of the node's downstream gutter
+fn surface_target(network: &Network, node: &Node) -> Result<Option<String>, String> {
+    if let Some(target) = node.inlet.as_ref().and_then(|i| i.bypass_to.as_ref()) {
+        if network.find_node(target).is_none() {
+            return Err(format!("Inlet {} bypasses to unknown node {}", node.id, target));
+        }
+        return Ok(Some(target.clone()));
+    }
+
+    let gutters: Vec<&Conduit> = network
+        .downstream_conduits(&node.id)
+        .into_iter()
+        .filter(|c| c.conduit_type == ConduitType::Gutter)
+        .collect();
+    match gutters.as_slice() {
+        [] => Ok(None),
+        [gutter] => Ok(Some(gutter.to_node.clone())),
+        _ => Err(format!(
+            "Node {} has more than one downstream gutter; set bypassTo to choose where its bypass goes",
+            node.id
+        )),
+    }
+}
+
+/// Gutter approaching an inlet
+///
+/// Properties given for the inlet win over those of an upstream gutter
+/// conduit.
+fn inlet_gutter<'a>(
+    network: &'a Network,
+    node: &Node,
+    gutters: &'a HashMap<String, GutterProperties>,
+) -> Option<&'a GutterProperties> {
+    gutters.get(&node.id).or_else(|| {
+        network
+            .upstream_conduits(&node.id)
+            .into_iter()
+            .find_map(|c| c.gutter.as_ref())
+    })
+}
+
+/// Section of a gutter, assuming a uniform gutter with typical asphalt
+/// values when there is none
+fn gutter_section(gutter: Option<&GutterProperties>, unit_system: UnitSystem) -> GutterSection {
+    gutter
+        .map(|g| g.section(unit_system))
+        .unwrap_or_else(|| GutterSection::Uniform(UniformGutter::new(0.016, 0.02, 0.01, None)))
+}
+
+/// Sag capacity model for an inlet
+enum SagInlet {
+    Grate(GrateInletSag),
+    CurbOpening(CurbOpeningInletSag),
+    Combination(CombinationInletSag),
+    Slotted(SlottedInletSag),
+}
+
+impl SagInlet {
+    /// Sag model for the inlet's geometry
+    ///
+    /// Combination and sweeper inlets share one sag model, which counts the
+    /// curb opening beyond the grate. Slotted drains default to a 10 ft
+    /// slot 1.75 in wide.
+    fn from_properties(inlet_props: &InletProperties) -> Self {
+        let clogging_factor = inlet_props.clogging_factor.unwrap_or(0.15);
+        let grate = || {
+            let grate = inlet_props.grate.as_ref();
+            let inlet = GrateInletSag::new(
+                grate.and_then(|g| g.length).unwrap_or(3.0),
+                grate.and_then(|g| g.width).unwrap_or(2.0),
+                1,
+                clogging_factor,
+            );
+            match grate.and_then(|g| g.grate_type) {
+                Some(grate_type) => inlet.with_grate_type(inlet_grate_type(grate_type)),
+                None => inlet,
+            }
+        };
+        let curb_opening = || {
+            let curb = inlet_props.curb_opening.as_ref();
+            let throat_type = match curb.and_then(|c| c.throat_type) {
+                Some(crate::node::ThroatType::Inclined) => InletThroatType::Inclined,
+                Some(crate::node::ThroatType::Vertical) => InletThroatType::Vertical,
+                _ => InletThroatType::Horizontal,
+            };
+            CurbOpeningInletSag::new(
+                curb.and_then(|c| c.length).unwrap_or(5.0),
+                curb.and_then(|c| c.height).unwrap_or(0.5),
+                throat_type,
+                clogging_factor,
+            )
+        };
+
+        match inlet_props.inlet_type {
+            InletType::Grate => SagInlet::Grate(grate()),
+            InletType::CurbOpening => SagInlet::CurbOpening(curb_opening()),
+            InletType::Combination | InletType::Sweeper => {
+                SagInlet::Combination(CombinationInletSag::new(grate(), curb_opening()))
+            }
+            InletType::Slotted => {
+                let slotted = inlet_props.slotted.as_ref();
+                SagInlet::Slotted(SlottedInletSag::new(
+                    slotted.and_then(|s| s.length).unwrap_or(10.0),
+                    slotted.and_then(|s| s.width).unwrap_or(1.75 / 12.0),
+                    clogging_factor,
+                ))
+            }
+        }
+    }
+
+    fn capacity(&self, ponding_depth: f64, unit_system: UnitSystem) -> f64 {
+        match self {
+            SagInlet::Grate(inlet) => inlet.capacity(ponding_depth, unit_system),
+            SagInlet::CurbOpening(inlet) => inlet.capacity(ponding_depth, unit_system),
+            SagInlet::Combination(inlet) => inlet.capacity(ponding_depth, unit_system),
+            SagInlet::Slotted(inlet) => inlet.capacity(ponding_depth, unit_system),
+        }
+    }
+
+    fn ponding_depth(&self, flow: f64, unit_system: UnitSystem) -> f64 {
+        match self {
+            SagInlet::Grate(inlet) => inlet.ponding_depth(flow, unit_system),
+            SagInlet::CurbOpening(inlet) => inlet.ponding_depth(flow, unit_system),
+            SagInlet::Combination(inlet) => inlet.ponding_depth(flow, unit_system),
+            SagInlet::Slotted(inlet) => inlet.ponding_depth(flow, unit_system),
+        }
+    }
+}
+
 /// Calculate inlet interception for a given inlet node
 ///
 /// Returns (intercepted_flow, bypass_flow, inlet_result)
@@ -960,41 +1709,199 @@ fn calculate_inlet_interception(
     node: &Node,
     inlet_props: &crate::node::InletProperties,
     approach_flow: f64,
-    k: f64,
+    gutter: &GutterSection,
+    max_ponding_depth: Option<f64>,
+    unit_system: UnitSystem,
 ) -> Result<(f64, f64, Option<InletInterception>), String> {
     if approach_flow <= 0.0 {
         return Ok((0.0, 0.0, None));
     }
+    let k = match unit_system {
+        UnitSystem::US => GUTTER_K_US,
+        UnitSystem::SI => GUTTER_K_SI,
+    };
 
-    // Check if this is a sag inlet (100% capture)
     if inlet_props.location == InletLocation::Sag {
+        let sag = SagInlet::from_properties(inlet_props);
+
+        // Flow beyond the capacity at the curb height overflows
+        let (intercepted_flow, ponding_depth) = match max_ponding_depth {
+            Some(max_depth) if sag.ponding_depth(approach_flow, unit_system) > max_depth => {
+                (sag.capacity(max_depth, unit_system), max_depth)
+            }
+            _ => (approach_flow, sag.ponding_depth(approach_flow, unit_system)),
+        };
+        let bypass_flow = approach_flow - intercepted_flow;
+
         let result = InletInterception {
             node_id: node.id.clone(),
             approach_flow,
-            intercepted_flow: approach_flow,
-            bypass_flow: 0.0,
-            efficiency: 1.0,
-            spread: 0.0, // Ponded at sag
+            intercepted_flow,
+            bypass_flow,
+            efficiency: intercepted_flow / approach_flow,
+            spread: gutter.spread_for_depth(ponding_depth, k),
+            gutter_section: Some(gutter.clone()),
+            ponding_depth: Some(ponding_depth),
+            bypass_to: None,
+            bypass_chain: Vec::new(),
+            lost_flow: 0.0,
         };
-        return Ok((approach_flow, 0.0, Some(result)));
+        return Ok((intercepted_flow, bypass_flow, Some(result)));
     }
 
-    // On-grade inlet - need to calculate interception
+    // On-grade inlet - spread and velocity from the approaching gutter
+    let gutter_result = gutter.result_for_flow(approach_flow, k);
 
-    // Get gutter properties from upstream conduit (if it's a gutter)
-    // For now, use default gutter assumptions
-    let manning_n = 0.016; // Asphalt
-    let cross_slope = 0.02; // 2%
-    let longitudinal_slope = 0.01; // 1% (default)
+    let interception = on_grade_interception(inlet_props, approach_flow, gutter, &gutter_result, unit_system);
 
-    let gutter = UniformGutter::new(manning_n, cross_slope, longitudinal_slope, None);
-    let gutter_result = gutter.result_for_flow(approach_flow, k);
+    let result = InletInterception {
+        node_id: node.id.clone(),
+        approach_flow: interception.approach_flow,
+        intercepted_flow: interception.intercepted_flow,
+        bypass_flow: interception.bypass_flow,
+        efficiency: interception.efficiency,
+        spread: interception.spread,
+        gutter_section: Some(gutter.clone()),
+        ponding_depth: None,
+        bypass_to: None,
+        bypass_chain: Vec::new(),
+        lost_flow: 0.0,
+    };
+
+    Ok((interception.intercepted_flow, interception.bypass_flow, Some(result)))
+}
 
-    // Determine inlet type and calculate interception
+/// Check gutter spread at inlets against the allowable spread
+///
+/// Sag inlets are checked at the spread of their ponding depth, and sag
+/// overflow that leaves the system is reported as flooding. Flanking
+/// inlets are sag inlets whose overflow runs to another sag inlet at the
+/// low point. Per HEC-22 Section 7.4.3 they must take the flow reaching the
+/// sag when the low point inlet is clogged. Flanking inlets sit where the
+/// ponding is 63 percent of the depth at the low point, so the depth each
+/// needs for its share of the flow sets the spread at the low point.
+///
+/// # Arguments
+/// * `network` - The drainage network
+/// * `inlet_results` - Results of [`route_flows_with_gutters`]
+/// * `gutters` - Gutter properties keyed by inlet node ID, as used for routing
+/// * `max_spread` - Maximum allowable spread (ft or m)
+/// * `unit_system` - Unit system for gutter calculations
+pub fn check_inlet_spread(
+    network: &Network,
+    inlet_results: &[InletInterception],
+    gutters: &HashMap<String, GutterProperties>,
+    max_spread: f64,
+    unit_system: UnitSystem,
+) -> Vec<Violation> {
+    let (k, flow_unit) = match unit_system {
+        UnitSystem::US => (GUTTER_K_US, "cfs"),
+        UnitSystem::SI => (GUTTER_K_SI, "cms"),
+    };
+    let is_sag = |node: &Node| {
+        node.inlet
+            .as_ref()
+            .is_some_and(|i| i.location == InletLocation::Sag)
+    };
+    let result_for = |id: &str| inlet_results.iter().find(|r| r.node_id == id);
+    let mut violations = Vec::new();
+
+    for result in inlet_results {
+        if result.spread > max_spread {
+            violations.push(Violation::spread_violation(
+                result.node_id.clone(),
+                result.spread,
+                max_spread,
+                Severity::Warning,
+            ));
+        }
+        if result.ponding_depth.is_some() && result.bypass_flow > 0.0 && result.bypass_to.is_none() {
+            violations.push(Violation {
+                violation_type: ViolationType::Flooding,
+                severity: Severity::Error,
+                element_id: result.node_id.clone(),
+                message: format!(
+                    "Sag inlet {} ponds over the curb, losing {:.2} {}",
+                    result.node_id, result.bypass_flow, flow_unit
+                ),
+                value: Some(result.bypass_flow),
+                limit: Some(0.0),
+            });
+        }
+    }
+
+    for low_point in network.nodes.iter().filter(|n| is_sag(n)) {
+        let flanking: Vec<&Node> = network
+            .nodes
+            .iter()
+            .filter(|n| is_sag(n))
+            .filter(|n| {
+                surface_target(network, n).ok().flatten().as_deref() == Some(low_point.id.as_str())
+            })
+            .collect();
+        if flanking.is_empty() {
+            continue;
+        }
+
+        // Flow reaching the sag, including what the flanking inlets catch
+        let flow = result_for(&low_point.id).map_or(0.0, |r| r.approach_flow)
+            + flanking
+                .iter()
+                .filter_map(|n| result_for(&n.id))
+                .map(|r| r.intercepted_flow)
+                .sum::<f64>();
+        let share = flow / flanking.len() as f64;
+
+        let low_point_depth = flanking
+            .iter()
+            .filter_map(|n| n.inlet.as_ref())
+            .map(SagInlet::from_properties)
+            .map(|sag| sag.ponding_depth(share, unit_system) / 0.63)
+            .fold(0.0, f64::max);
+        let spread = gutter_section(inlet_gutter(network, low_point, gutters), unit_system)
+            .spread_for_depth(low_point_depth, k);
+
+        if spread > max_spread {
+            let mut violation = Violation::spread_violation(
+                low_point.id.clone(),
+                spread,
+                max_spread,
+                Severity::Warning,
+            );
+            violation.message = format!(
+                "With {} clogged, flanking inlets pond {:.2} ft at the low point, a spread of {:.1} ft over the maximum allowable {:.1} ft",
+                low_point.id, low_point_depth, spread, max_spread
+            );
+            violations.push(violation);
+        }
+    }
+
+    violations
+}
+
+/// Interception of an on-grade inlet described by node inlet properties
+///
+/// Missing dimensions fall back to a 3 ft × 2 ft grate, a 5 ft × 0.5 ft
+/// curb opening and a 10 ft slotted drain, with 15% clogging unless
+/// specified.
+///
+/// # Arguments
+/// * `inlet_props` - Inlet properties from the node
+/// * `approach_flow` - Gutter flow approaching the inlet (cfs or cms)
+/// * `gutter` - Gutter section approaching the inlet
+/// * `gutter_result` - Gutter flow state at the inlet
+/// * `unit_system` - Unit system for gutter calculations
+pub(crate) fn on_grade_interception(
+    inlet_props: &crate::node::InletProperties,
+    approach_flow: f64,
+    gutter: &GutterSection,
+    gutter_result: &GutterFlowResult,
+    unit_system: UnitSystem,
+) -> InletInterceptionResult {
     let local_depression = inlet_props.local_depression.unwrap_or(0.0);
     let clogging_factor = inlet_props.clogging_factor.unwrap_or(0.15);
 
-    let interception: InletInterceptionResult = match inlet_props.inlet_type {
+    match inlet_props.inlet_type {
         crate::node::InletType::Grate => {
             if let Some(ref grate_props) = inlet_props.grate {
                 let length = grate_props.length.unwrap_or(3.0);
@@ -1004,20 +1911,23 @@ fn calculate_inlet_interception(
                     _ => InletBarConfig::Perpendicular,
                 };
 
-                let inlet = GrateInletOnGrade::new(
+                let mut inlet = GrateInletOnGrade::new(
                     length,
                     width,
                     bar_config,
                     clogging_factor,
                     local_depression,
                 );
+                if let Some(grate_type) = grate_props.grate_type {
+                    inlet = inlet.with_grate_type(inlet_grate_type(grate_type));
+                }
 
-                inlet.interception(approach_flow, &gutter_result)
+                inlet.interception(approach_flow, gutter_result, unit_system)
             } else {
                 // No grate properties - assume default
                 let inlet =
                     GrateInletOnGrade::new(3.0, 2.0, InletBarConfig::Perpendicular, 0.15, 2.0);
-                inlet.interception(approach_flow, &gutter_result)
+                inlet.interception(approach_flow, gutter_result, unit_system)
             }
         }
 
@@ -1032,15 +1942,15 @@ fn calculate_inlet_interception(
                 };
 
                 let inlet = CurbOpeningInletOnGrade::new(length, height, throat_type, clogging_factor);
-                inlet.interception(approach_flow, &gutter_result)
+                inlet.interception(approach_flow, gutter_result)
             } else {
                 // Default curb opening
                 let inlet = CurbOpeningInletOnGrade::new(5.0, 0.5, InletThroatType::Horizontal, 0.10);
-                inlet.interception(approach_flow, &gutter_result)
+                inlet.interception(approach_flow, gutter_result)
             }
         }
 
-        crate::node::InletType::Combination => {
+        crate::node::InletType::Combination | crate::node::InletType::Sweeper => {
             // Combination inlet with both grate and curb opening
             let grate_length = inlet_props.grate.as_ref()
                 .and_then(|g| g.length).unwrap_or(3.0);
@@ -1067,44 +1977,47 @@ fn calculate_inlet_interception(
                 })
                 .unwrap_or(InletThroatType::Horizontal);
 
-            let grate = GrateInletOnGrade::new(
+            let mut grate = GrateInletOnGrade::new(
                 grate_length,
                 grate_width,
                 bar_config,
                 clogging_factor,
                 local_depression,
             );
+            if let Some(grate_type) = inlet_props.grate.as_ref().and_then(|g| g.grate_type) {
+                grate = grate.with_grate_type(inlet_grate_type(grate_type));
+            }
 
             let curb = CurbOpeningInletOnGrade::new(curb_length, curb_height, curb_throat, clogging_factor);
 
-            let combo = CombinationInletOnGrade::new(grate, curb);
-            combo.interception(approach_flow, &gutter_result)
+            if inlet_props.inlet_type == crate::node::InletType::Sweeper {
+                SweeperInletOnGrade::new(grate, curb).interception(approach_flow, gutter, gutter_result, unit_system)
+            } else {
+                CombinationInletOnGrade::new(grate, curb).interception(approach_flow, gutter_result, unit_system)
+            }
         }
 
         crate::node::InletType::Slotted => {
-            // Slotted drains typically have high efficiency
-            // For now, assume 80% efficiency
-            InletInterceptionResult {
-                approach_flow,
-                intercepted_flow: approach_flow * 0.80,
-                bypass_flow: approach_flow * 0.20,
-                efficiency: 0.80,
-                spread: gutter_result.spread,
-                velocity: gutter_result.velocity,
-            }
+            let length = inlet_props.slotted.as_ref()
+                .and_then(|s| s.length).unwrap_or(10.0);
+            let inlet = SlottedInletOnGrade::new(length, clogging_factor);
+            inlet.interception(approach_flow, gutter, gutter_result, unit_system)
         }
-    };
-
-    let result = InletInterception {
-        node_id: node.id.clone(),
-        approach_flow: interception.approach_flow,
-        intercepted_flow: interception.intercepted_flow,
-        bypass_flow: interception.bypass_flow,
-        efficiency: interception.efficiency,
-        spread: interception.spread,
-    };
+    }
+}
 
-    Ok((interception.intercepted_flow, interception.bypass_flow, Some(result)))
+/// Calculation grate type for a grate type named in node properties
+fn inlet_grate_type(grate_type: crate::node::GrateType) -> InletGrateType {
+    match grate_type {
+        crate::node::GrateType::P50 => InletGrateType::P50,
+        crate::node::GrateType::P50x100 => InletGrateType::P50x100,
+        crate::node::GrateType::P30 => InletGrateType::P30,
+        crate::node::GrateType::CurvedVane => InletGrateType::CurvedVane,
+        crate::node::GrateType::TiltBar45x60 => InletGrateType::TiltBar45x60,
+        crate::node::GrateType::TiltBar45x85 => InletGrateType::TiltBar45x85,
+        crate::node::GrateType::TiltBar30x85 => InletGrateType::TiltBar30x85,
+        crate::node::GrateType::Reticuline => InletGrateType::Reticuline,
+    }
 }
 
 /// Perform an upstream-to-downstream topological sort of the network nodes.
@@ -1119,8 +2032,17 @@ fn calculate_inlet_interception(
 /// # Returns
 /// A `Vec<String>` containing the node IDs in topologically sorted order,
 /// or an error if a cycle is detected.
-fn topological_sort_upstream_to_downstream(
+pub(crate) fn topological_sort_upstream_to_downstream(
+    network: &Network,
+) -> Result<Vec<String>, String> {
+    topological_sort_with_links(network, &[])
+}
+
+/// Topological sort over the network conduits plus extra `(from, to)` links,
+/// such as inlet bypass targets
+fn topological_sort_with_links(
     network: &Network,
+    links: &[(String, String)],
 ) -> Result<Vec<String>, String> {
     let mut in_degree: HashMap<String, usize> = HashMap::new();
     let mut queue: Vec<String> = Vec::new();
@@ -1128,7 +2050,8 @@ fn topological_sort_upstream_to_downstream(
 
     // Initialize in-degree for all nodes
     for node in &network.nodes {
-        in_degree.insert(node.id.clone(), network.upstream_conduits(&node.id).len());
+        let linked = links.iter().filter(|(_, to)| *to == node.id).count();
+        in_degree.insert(node.id.clone(), network.upstream_conduits(&node.id).len() + linked);
         if *in_degree.get(&node.id).unwrap() == 0 {
             queue.push(node.id.clone());
         }
@@ -1139,11 +2062,16 @@ fn topological_sort_upstream_to_downstream(
         sorted_nodes.push(node_id.clone());
 
         // For each downstream node, decrement its in-degree
-        for conduit in network.downstream_conduits(&node_id) {
-            if let Some(degree) = in_degree.get_mut(&conduit.to_node) {
+        let downstream = network
+            .downstream_conduits(&node_id)
+            .into_iter()
+            .map(|c| &c.to_node)
+            .chain(links.iter().filter(|(from, _)| *from == node_id).map(|(_, to)| to));
+        for to_node in downstream {
+            if let Some(degree) = in_degree.get_mut(to_node) {
                 *degree -= 1;
                 if *degree == 0 {
-                    queue.push(conduit.to_node.clone());
+                    queue.push(to_node.clone());
                 }
             }
         }
@@ -1161,8 +2089,93 @@ fn topological_sort_upstream_to_downstream(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::conduit::{PipeMaterial, PipeProperties, PipeShape};
-    use crate::node::OutfallProperties;
+    use approx::assert_relative_eq;
+    use crate::conduit::{
+        ChannelProperties, ChannelShape, CulvertInlet, CulvertProperties, PipeMaterial, PipeProperties,
+        PipeShape, RoadwayProperties,
+    };
+    use crate::gutter::{GutterSectionType, UniformGutter};
+    use crate::section::CrossSection;
+    use crate::node::{
+        BarConfiguration, GrateProperties, InletProperties, InletType, JunctionProperties, OutfallProperties,
+        SlottedProperties, TailwaterRule, TidalPoint, TidalStage,
+    };
+
+    /// Two on-grade grates on a gutter run, each piped to the outfall
+    fn gutter_run_network(bypass_to: Option<&str>) -> Network {
+        let grate = |bypass_to: Option<&str>| InletProperties {
+            inlet_type: InletType::Grate,
+            location: InletLocation::OnGrade,
+            grate: Some(GrateProperties {
+                length: Some(3.0),
+                width: Some(2.0),
+                bar_configuration: Some(BarConfiguration::Perpendicular),
+                grate_type: None,
+            }),
+            curb_opening: None,
+            slotted: None,
+            local_depression: None,
+            clogging_factor: Some(0.0),
+            bypass_to: bypass_to.map(str::to_string),
+        };
+        let pipe = PipeProperties {
+            shape: PipeShape::Circular,
+            diameter: Some(18.0),
+            width: None,
+            height: None,
+            material: Some(PipeMaterial::RCP),
+            manning_n: 0.013,
+            entrance_loss: None,
+            exit_loss: None,
+            bend_loss: None,
+            culvert: None,
+        };
+        let gutter = GutterProperties {
+            cross_slope: 0.02,
+            longitudinal_slope: 0.01,
+            width: Some(2.0),
+            manning_n: 0.016,
+            depression: None,
+            curb_height: None,
+            crown_height: None,
+            width_to_crown: None,
+        };
+
+        let mut network = Network::new();
+        network.add_node(Node::new_inlet("IN-1".to_string(), 96.0, 100.0, grate(bypass_to)));
+        network.add_node(Node::new_inlet("IN-2".to_string(), 94.0, 98.0, grate(None)));
+        network.add_node(Node::new_inlet("IN-3".to_string(), 94.0, 98.0, grate(None)));
+        network.add_node(Node::new_outfall(
+            "OUT".to_string(),
+            90.0,
+            OutfallProperties {
+                boundary_condition: BoundaryCondition::Free,
+                tailwater_elevation: None,
+                tidal_curve: None,
+                tidal_stage: None,
+                storm_start: None,
+                flap_gate: None,
+                tailwater_rule: None,
+            },
+        ));
+        network.add_conduit(Conduit::new_gutter(
+            "G-1".to_string(),
+            "IN-1".to_string(),
+            "IN-2".to_string(),
+            200.0,
+            gutter,
+        ));
+        for (id, from) in [("P-1", "IN-1"), ("P-2", "IN-2"), ("P-3", "IN-3")] {
+            network.add_conduit(Conduit::new_pipe(
+                id.to_string(),
+                from.to_string(),
+                "OUT".to_string(),
+                100.0,
+                pipe.clone(),
+            ));
+        }
+        network
+    }
 
     #[test]
     fn test_solver_config() {
@@ -1193,4 +2206,911 @@ mod tests {
 
         assert_eq!(flows.get("IN-001"), Some(&3.2)); // 0.8 × 4.0 × 1.0
     }
+
+    /// Junction with a main line and a relief line to separate outfalls
+    fn divider_network(method: DiversionMethod) -> Network {
+        let pipe = PipeProperties {
+            shape: PipeShape::Circular,
+            diameter: Some(18.0),
+            width: None,
+            height: None,
+            material: Some(PipeMaterial::RCP),
+            manning_n: 0.013,
+            entrance_loss: None,
+            exit_loss: None,
+            bend_loss: None,
+            culvert: None,
+        };
+        let outfall = |id: &str| {
+            Node::new_outfall(
+                id.to_string(),
+                94.0,
+                OutfallProperties {
+                    boundary_condition: BoundaryCondition::Free,
+                    tailwater_elevation: None,
+                    tidal_curve: None,
+                    tidal_stage: None,
+                    storm_start: None,
+                    flap_gate: None,
+                    tailwater_rule: None,
+                },
+            )
+        };
+
+        let mut divider = Node::new_junction(
+            "DIV".to_string(),
+            95.0,
+            101.0,
+            JunctionProperties {
+                diameter: Some(4.0),
+                sump_depth: None,
+                loss_coefficient: None,
+                benching: None,
+                drop_structure: None,
+            },
+        );
+        divider.divider = Some(FlowDivider {
+            diverted_conduit: "P-RELIEF".to_string(),
+            method,
+        });
+
+        let mut network = Network::new();
+        network.add_node(divider);
+        network.add_node(outfall("OUT-1"));
+        network.add_node(outfall("OUT-2"));
+        for (id, to) in [("P-MAIN", "OUT-1"), ("P-RELIEF", "OUT-2")] {
+            network.add_conduit(Conduit::new_pipe(
+                id.to_string(),
+                "DIV".to_string(),
+                to.to_string(),
+                100.0,
+                pipe.clone(),
+            ));
+        }
+        network
+    }
+
+    #[test]
+    fn test_flow_divider_fraction_and_rating() {
+        let inflows = HashMap::from([("DIV".to_string(), 8.0)]);
+
+        let network = divider_network(DiversionMethod::Fraction { fraction: 0.25 });
+        let flows = route_flows(&network, &inflows, UnitSystem::US).unwrap();
+        assert_relative_eq!(flows["P-RELIEF"], 2.0);
+        assert_relative_eq!(flows["P-MAIN"], 6.0);
+
+        let network = divider_network(DiversionMethod::Rating {
+            table: vec![(0.0, 0.0), (4.0, 0.0), (12.0, 6.0)],
+        });
+        let flows = route_flows(&network, &inflows, UnitSystem::US).unwrap();
+        assert_relative_eq!(flows["P-RELIEF"], 3.0);
+        assert_relative_eq!(flows["P-MAIN"], 5.0);
+
+        // Beyond the table the diversion holds at its last value
+        let inflows = HashMap::from([("DIV".to_string(), 20.0)]);
+        let flows = route_flows(&network, &inflows, UnitSystem::US).unwrap();
+        assert_relative_eq!(flows["P-RELIEF"], 6.0);
+    }
+
+    #[test]
+    fn test_flow_divider_weir() {
+        let network = divider_network(DiversionMethod::Weir {
+            crest_height: 0.75,
+            length: 4.0,
+            coefficient: None,
+        });
+        let mannings = ManningsEquation::us_customary();
+
+        // Below the crest everything continues down the main line
+        let inflows = HashMap::from([("DIV".to_string(), 2.0)]);
+        let flows = route_flows(&network, &inflows, UnitSystem::US).unwrap();
+        assert_relative_eq!(flows["P-RELIEF"], 0.0);
+        assert_relative_eq!(flows["P-MAIN"], 2.0);
+
+        // Above it the overflow at the main line's stage is diverted
+        let inflows = HashMap::from([("DIV".to_string(), 8.0)]);
+        let flows = route_flows(&network, &inflows, UnitSystem::US).unwrap();
+        let stage = mannings.normal_depth(flows["P-MAIN"], 1.5, 0.01, 0.013, 32.17).unwrap();
+        assert!(flows["P-RELIEF"] > 0.0);
+        assert_relative_eq!(flows["P-RELIEF"], 3.0 * 4.0 * (stage - 0.75).powf(1.5), epsilon = 1e-3);
+        assert_relative_eq!(flows["P-MAIN"] + flows["P-RELIEF"], 8.0);
+
+        // The main line never carries more than it can when full
+        let inflows = HashMap::from([("DIV".to_string(), 40.0)]);
+        let flows = route_flows(&network, &inflows, UnitSystem::US).unwrap();
+        assert!(flows["P-MAIN"] <= mannings.full_pipe_capacity(1.5, 0.01, 0.013) + 1e-9);
+    }
+
+    #[test]
+    fn test_flow_divider_box_pipe() {
+        // A 2 ft × 1.5 ft box main line sets the stage from its own geometry
+        let mut network = divider_network(DiversionMethod::Weir {
+            crest_height: 0.75,
+            length: 4.0,
+            coefficient: None,
+        });
+        let main = network.conduits.iter_mut().find(|c| c.id == "P-MAIN").unwrap();
+        let pipe = main.pipe.as_mut().unwrap();
+        pipe.shape = PipeShape::Rectangular;
+        pipe.diameter = None;
+        pipe.width = Some(24.0);
+        pipe.height = Some(18.0);
+        let main_box = section::Rectangular { width: 2.0, height: Some(1.5) };
+
+        let inflows = HashMap::from([("DIV".to_string(), 12.0)]);
+        let flows = route_flows(&network, &inflows, UnitSystem::US).unwrap();
+        let stage = main_box.normal_depth(flows["P-MAIN"], 0.01, 0.013, 1.486).unwrap();
+        assert!(flows["P-RELIEF"] > 0.0);
+        assert_relative_eq!(flows["P-RELIEF"], 3.0 * 4.0 * (stage - 0.75).powf(1.5), epsilon = 1e-3);
+        assert_relative_eq!(flows["P-MAIN"] + flows["P-RELIEF"], 12.0);
+    }
+
+    #[test]
+    fn test_flow_divider_unknown_conduit() {
+        let mut network = divider_network(DiversionMethod::Fraction { fraction: 0.5 });
+        network.nodes[0].divider.as_mut().unwrap().diverted_conduit = "P-NONE".to_string();
+        let inflows = HashMap::from([("DIV".to_string(), 8.0)]);
+        assert!(route_flows(&network, &inflows, UnitSystem::US).is_err());
+    }
+
+    #[test]
+    fn test_bypass_follows_gutter() {
+        let network = gutter_run_network(None);
+        let inflows = HashMap::from([("IN-1".to_string(), 4.0), ("IN-2".to_string(), 1.0)]);
+
+        let (flows, results) = route_flows_with_inlets(&network, &inflows, UnitSystem::US).unwrap();
+        let in1 = results.iter().find(|r| r.node_id == "IN-1").unwrap();
+        let in2 = results.iter().find(|r| r.node_id == "IN-2").unwrap();
+
+        assert!(in1.bypass_flow > 0.0);
+        assert_eq!(in1.bypass_to.as_deref(), Some("IN-2"));
+        assert_relative_eq!(flows["G-1"], in1.bypass_flow, epsilon = 1e-9);
+        assert_relative_eq!(in2.approach_flow, 1.0 + in1.bypass_flow, epsilon = 1e-9);
+
+        // IN-2 ends the run, so its bypass leaves the system
+        assert_eq!(in1.bypass_chain, vec!["IN-2".to_string()]);
+        assert_eq!(in2.bypass_to, None);
+        assert_relative_eq!(in1.lost_flow, in2.bypass_flow, epsilon = 1e-9);
+        assert_relative_eq!(flows["P-1"] + flows["P-2"] + in2.lost_flow, 5.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_bypass_to_overrides_gutter() {
+        let network = gutter_run_network(Some("IN-3"));
+        let inflows = HashMap::from([("IN-1".to_string(), 4.0)]);
+
+        let (flows, results) = route_flows_with_inlets(&network, &inflows, UnitSystem::US).unwrap();
+        let in1 = results.iter().find(|r| r.node_id == "IN-1").unwrap();
+        let in3 = results.iter().find(|r| r.node_id == "IN-3").unwrap();
+
+        assert_eq!(in1.bypass_to.as_deref(), Some("IN-3"));
+        assert_eq!(flows["G-1"], 0.0);
+        assert_eq!(flows["P-2"], 0.0);
+        assert_relative_eq!(in3.approach_flow, in1.bypass_flow, epsilon = 1e-9);
+        assert_eq!(in1.bypass_chain, vec!["IN-3".to_string()]);
+    }
+
+    #[test]
+    fn test_inlet_gutter_sections() {
+        let network = gutter_run_network(None);
+        let inflows = HashMap::from([("IN-1".to_string(), 3.0), ("IN-3".to_string(), 3.0)]);
+        let depressed = GutterProperties {
+            cross_slope: 0.02,
+            longitudinal_slope: 0.01,
+            width: Some(2.0),
+            manning_n: 0.016,
+            depression: Some(2.0),
+            curb_height: None,
+            crown_height: None,
+            width_to_crown: None,
+        };
+        let gutters = HashMap::from([("IN-1".to_string(), depressed)]);
+
+        let (_, results) = route_flows_with_gutters(&network, &inflows, &gutters, UnitSystem::US).unwrap();
+        let section = |id: &str| {
+            let result = results.iter().find(|r| r.node_id == id).unwrap();
+            (result.gutter_section.as_ref().unwrap().section_type(), result.spread)
+        };
+
+        // Given parameters, the approaching gutter conduit, then the default
+        let (in1_type, in1_spread) = section("IN-1");
+        let (in2_type, _) = section("IN-2");
+        let (in3_type, in3_spread) = section("IN-3");
+        assert_eq!(in1_type, GutterSectionType::Composite);
+        assert_eq!(in2_type, GutterSectionType::UniformSlope);
+        assert_eq!(in3_type, GutterSectionType::UniformSlope);
+
+        // The depression narrows the spread of the same flow
+        assert!(in1_spread < in3_spread);
+    }
+
+    /// Sag inlet at a low point with a flanking inlet on each side
+    fn sag_network() -> Network {
+        let grate = |bypass_to: Option<&str>| InletProperties {
+            inlet_type: InletType::Grate,
+            location: InletLocation::Sag,
+            grate: Some(GrateProperties {
+                length: Some(3.0),
+                width: Some(2.0),
+                bar_configuration: Some(BarConfiguration::Perpendicular),
+                grate_type: None,
+            }),
+            curb_opening: None,
+            slotted: None,
+            local_depression: None,
+            clogging_factor: Some(0.5),
+            bypass_to: bypass_to.map(str::to_string),
+        };
+
+        let mut network = Network::new();
+        network.add_node(Node::new_inlet("LOW".to_string(), 94.0, 98.0, grate(None)));
+        network.add_node(Node::new_inlet("FL-1".to_string(), 94.2, 98.2, grate(Some("LOW"))));
+        network.add_node(Node::new_inlet("FL-2".to_string(), 94.2, 98.2, grate(Some("LOW"))));
+        network.add_node(Node::new_outfall(
+            "OUT".to_string(),
+            90.0,
+            OutfallProperties {
+                boundary_condition: BoundaryCondition::Free,
+                tailwater_elevation: None,
+                tidal_curve: None,
+                tidal_stage: None,
+                storm_start: None,
+                flap_gate: None,
+                tailwater_rule: None,
+            },
+        ));
+        for (id, from) in [("P-1", "LOW"), ("P-2", "FL-1"), ("P-3", "FL-2")] {
+            network.add_conduit(Conduit::new_pipe(
+                id.to_string(),
+                from.to_string(),
+                "OUT".to_string(),
+                100.0,
+                PipeProperties {
+                    shape: PipeShape::Circular,
+                    diameter: Some(18.0),
+                    width: None,
+                    height: None,
+                    material: Some(PipeMaterial::RCP),
+                    manning_n: 0.013,
+                    entrance_loss: None,
+                    exit_loss: None,
+                    bend_loss: None,
+                    culvert: None,
+                },
+            ));
+        }
+        network
+    }
+
+    fn curb_gutter(curb_height: f64) -> GutterProperties {
+        GutterProperties {
+            cross_slope: 0.02,
+            longitudinal_slope: 0.005,
+            width: None,
+            manning_n: 0.016,
+            depression: None,
+            curb_height: Some(curb_height),
+            crown_height: None,
+            width_to_crown: None,
+        }
+    }
+
+    #[test]
+    fn test_sag_ponding_and_overflow() {
+        let network = sag_network();
+        let inflows = HashMap::from([("LOW".to_string(), 2.0)]);
+        let gutters = HashMap::from([("LOW".to_string(), curb_gutter(6.0))]);
+
+        let (_, results) = route_flows_with_gutters(&network, &inflows, &gutters, UnitSystem::US).unwrap();
+        let low = &results[0];
+        let depth = low.ponding_depth.unwrap();
+        assert_relative_eq!(low.intercepted_flow, 2.0);
+        assert_relative_eq!(low.spread, depth / 0.02, epsilon = 1e-6);
+
+        // A 2 inch curb caps the ponding, and the rest leaves the system
+        let gutters = HashMap::from([("LOW".to_string(), curb_gutter(2.0))]);
+        let (_, results) = route_flows_with_gutters(&network, &inflows, &gutters, UnitSystem::US).unwrap();
+        let low = &results[0];
+        assert_relative_eq!(low.ponding_depth.unwrap(), 2.0 / 12.0);
+        assert!(low.bypass_flow > 0.0);
+        assert_relative_eq!(low.lost_flow, low.bypass_flow);
+
+        let violations = check_inlet_spread(&network, &results, &gutters, 10.0, UnitSystem::US);
+        assert!(violations
+            .iter()
+            .any(|v| v.violation_type == ViolationType::Flooding && v.element_id == "LOW"));
+    }
+
+    #[test]
+    fn test_slotted_drain_interception() {
+        let slotted = |location: InletLocation| InletProperties {
+            inlet_type: InletType::Slotted,
+            location,
+            grate: None,
+            curb_opening: None,
+            slotted: Some(SlottedProperties {
+                length: Some(5.0),
+                width: Some(1.75 / 12.0),
+            }),
+            local_depression: None,
+            clogging_factor: Some(0.0),
+            bypass_to: None,
+        };
+
+        // On grade the slot passes part of the flow along the gutter
+        let mut network = gutter_run_network(None);
+        network.nodes[0].inlet = Some(slotted(InletLocation::OnGrade));
+        let inflows = HashMap::from([("IN-1".to_string(), 3.0)]);
+        let (_, results) = route_flows_with_gutters(&network, &inflows, &HashMap::new(), UnitSystem::US).unwrap();
+        let in1 = results.iter().find(|r| r.node_id == "IN-1").unwrap();
+        let gutter = GutterSection::Uniform(UniformGutter::new(0.016, 0.02, 0.01, Some(2.0)));
+        let expected = SlottedInletOnGrade::new(5.0, 0.0).interception(
+            3.0,
+            &gutter,
+            &gutter.result_for_flow(3.0, GUTTER_K_US),
+            UnitSystem::US,
+        );
+        assert_relative_eq!(in1.intercepted_flow, expected.intercepted_flow);
+        assert!(in1.efficiency < 1.0);
+
+        // In sag it ponds to the weir/orifice depth
+        let mut network = sag_network();
+        network.nodes[0].inlet = Some(slotted(InletLocation::Sag));
+        let inflows = HashMap::from([("LOW".to_string(), 2.0)]);
+        let (_, results) = route_flows_with_gutters(&network, &inflows, &HashMap::new(), UnitSystem::US).unwrap();
+        let low = results.iter().find(|r| r.node_id == "LOW").unwrap();
+        let depth = SlottedInletSag::new(5.0, 1.75 / 12.0, 0.0).ponding_depth(2.0, UnitSystem::US);
+        assert_relative_eq!(low.ponding_depth.unwrap(), depth);
+        assert_relative_eq!(low.intercepted_flow, 2.0);
+    }
+
+    #[test]
+    fn test_flanking_inlets_with_clogged_low_point() {
+        let network = sag_network();
+        let inflows = HashMap::from([
+            ("LOW".to_string(), 1.0),
+            ("FL-1".to_string(), 0.25),
+            ("FL-2".to_string(), 0.25),
+        ]);
+        let gutters = HashMap::new();
+
+        let (_, results) = route_flows_with_gutters(&network, &inflows, &gutters, UnitSystem::US).unwrap();
+        let low = results.iter().find(|r| r.node_id == "LOW").unwrap();
+        assert!(low.spread < 12.0);
+
+        // Flanking inlets share 1.5 cfs, ponding to 63% of the low point depth
+        let flanking_depth = GrateInletSag::new(3.0, 2.0, 1, 0.5).ponding_depth(0.75, UnitSystem::US);
+        let low_point_spread = flanking_depth / 0.63 / 0.02;
+        assert!(low_point_spread > 12.0 && low_point_spread < 15.0);
+
+        let violations = check_inlet_spread(&network, &results, &gutters, 12.0, UnitSystem::US);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].element_id, "LOW");
+        assert_relative_eq!(violations[0].value.unwrap(), low_point_spread, epsilon = 1e-3);
+        assert!(check_inlet_spread(&network, &results, &gutters, 15.0, UnitSystem::US).is_empty());
+    }
+
+    /// One 200 ft pipe from a junction to an outfall held at `tailwater`
+    fn single_pipe_network(diameter: f64, upstream_invert: f64, tailwater: f64) -> Network {
+        let mut conduit = Conduit::new_pipe(
+            "P-1".to_string(),
+            "MH-1".to_string(),
+            "OUT".to_string(),
+            200.0,
+            PipeProperties {
+                shape: PipeShape::Circular,
+                diameter: Some(diameter),
+                width: None,
+                height: None,
+                material: Some(PipeMaterial::RCP),
+                manning_n: 0.013,
+                entrance_loss: Some(0.0),
+                exit_loss: Some(0.0),
+                bend_loss: None,
+                culvert: None,
+            },
+        );
+        conduit.upstream_invert = Some(upstream_invert);
+        conduit.downstream_invert = Some(100.0);
+
+        let mut network = Network::new();
+        network.add_node(Node::new_junction(
+            "MH-1".to_string(),
+            upstream_invert,
+            upstream_invert + 9.5,
+            JunctionProperties {
+                diameter: Some(4.0),
+                sump_depth: None,
+                loss_coefficient: None,
+                benching: None,
+                drop_structure: None,
+            },
+        ));
+        network.add_node(Node::new_outfall(
+            "OUT".to_string(),
+            100.0,
+            OutfallProperties {
+                boundary_condition: BoundaryCondition::FixedStage,
+                tailwater_elevation: Some(tailwater),
+                tidal_curve: None,
+                tidal_stage: None,
+                storm_start: None,
+                flap_gate: None,
+                tailwater_rule: None,
+            },
+        ));
+        network.add_conduit(conduit);
+        network
+    }
+
+    fn solve_single_pipe(network: &Network, flow: f64, config: SolverConfig) -> Analysis {
+        let flows = HashMap::from([("P-1".to_string(), flow)]);
+        HglSolver::new(config)
+            .solve(network, &flows, "Test".to_string())
+            .unwrap()
+    }
+
+    /// 6.75 cfs in a 24 in pipe on a 0.25% grade
+    fn solve_pressure(tailwater: f64, config: SolverConfig) -> Analysis {
+        solve_single_pipe(&single_pipe_network(24.0, 100.5, tailwater), 6.75, config)
+    }
+
+    /// 3.3 cfs in an 18 in pipe on a 5% grade
+    fn solve_steep(tailwater: f64) -> Analysis {
+        solve_single_pipe(
+            &single_pipe_network(18.0, 110.0, tailwater),
+            3.3,
+            SolverConfig::us_customary(),
+        )
+    }
+
+    fn manhole_hgl(analysis: &Analysis) -> f64 {
+        analysis.node_results.as_ref().unwrap()[0].hgl.unwrap()
+    }
+
+    #[test]
+    fn test_surcharged_pipe_friction_slope() {
+        // HEC-22 Chapter 9 example: 6.75 cfs in a 24 in pipe, Sf = 0.00090
+        let analysis = solve_pressure(104.0, SolverConfig::us_customary());
+        let conduit = &analysis.conduit_results.as_ref().unwrap()[0];
+        let friction = conduit.headloss.as_ref().unwrap().friction.unwrap();
+        assert_relative_eq!(friction / 200.0, 0.00090, epsilon = 1e-5);
+        assert_eq!(conduit.depth, Some(2.0));
+
+        let manhole = &analysis.node_results.as_ref().unwrap()[0];
+        assert_relative_eq!(manhole.hgl.unwrap(), 104.0 + friction, epsilon = 1e-9);
+        assert_relative_eq!(manhole.surcharge_depth.unwrap(), 104.0 + friction - 102.5, epsilon = 1e-9);
+        assert_eq!(manhole.depth_above_rim, Some(0.0));
+        assert!(analysis.get_violations_by_type(ViolationType::Hgl).is_empty());
+    }
+
+    #[test]
+    fn test_flat_and_adverse_pipes_run_full() {
+        // Full-pipe friction slope for 6.75 cfs in a 24 in pipe, Sf = 0.00090
+        let conveyance = 1.486 / 0.013 * std::f64::consts::PI * 0.5_f64.powf(2.0 / 3.0);
+        let friction = (6.75 / conveyance).powi(2) * 200.0;
+
+        // A flat pipe below a low tailwater still starts full at the outlet
+        let analysis = solve_single_pipe(
+            &single_pipe_network(24.0, 100.0, 101.0),
+            6.75,
+            SolverConfig::us_customary(),
+        );
+        let conduit = &analysis.conduit_results.as_ref().unwrap()[0];
+        assert_relative_eq!(conduit.headloss.as_ref().unwrap().friction.unwrap(), friction, epsilon = 1e-9);
+        assert_eq!(conduit.depth, Some(2.0));
+        assert_eq!(conduit.capacity_used, None);
+        assert_relative_eq!(manhole_hgl(&analysis), 102.0 + friction, epsilon = 1e-9);
+
+        // An adverse pipe carries the tailwater up by the same friction loss
+        let analysis = solve_single_pipe(
+            &single_pipe_network(24.0, 99.5, 104.0),
+            6.75,
+            SolverConfig::us_customary(),
+        );
+        assert_relative_eq!(manhole_hgl(&analysis), 104.0 + friction, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_partial_to_full_flow_transition() {
+        let config = SolverConfig::us_customary;
+
+        // Friction slope is continuous as the outlet submerges
+        let friction = |tailwater: f64| {
+            let analysis = solve_pressure(tailwater, config());
+            analysis.conduit_results.as_ref().unwrap()[0].headloss.as_ref().unwrap().friction.unwrap()
+        };
+        assert_relative_eq!(friction(102.0), friction(101.999), epsilon = 1e-5);
+
+        // So is the HGL, which tracks the tailwater along the M1 profile, from
+        // either side of the crown
+        let rise = |tailwater: f64| manhole_hgl(&solve_pressure(tailwater, config())) - tailwater;
+        assert_relative_eq!(rise(102.0), rise(101.99999), epsilon = 1e-5);
+        assert_relative_eq!(rise(102.0), rise(102.00001), epsilon = 1e-5);
+
+        // A low tailwater leaves the pipe at normal depth
+        let mannings = ManningsEquation { k: 1.486 };
+        let normal = mannings.normal_depth(6.75, 2.0, 0.0025, 0.013, 32.17).unwrap();
+        let free = manhole_hgl(&solve_pressure(100.2, config()));
+        assert_relative_eq!(free, 100.5 + normal, epsilon = 1e-3);
+
+        // Backwater between normal depth and the crown rises monotonically
+        let mut previous = free;
+        for tailwater in [101.2, 101.5, 101.8, 102.0, 102.5] {
+            let hgl = manhole_hgl(&solve_pressure(tailwater, config()));
+            assert!(hgl >= previous);
+            previous = hgl;
+        }
+    }
+
+    #[test]
+    fn test_surcharge_criteria() {
+        let criteria = DesignCriteria {
+            gutter_spread: None,
+            hgl_criteria: Some(crate::analysis::HglCriteria {
+                max_hgl_below_rim: None,
+                allow_surcharge: Some(false),
+            }),
+            velocity: None,
+            cover: None,
+            capacity: None,
+        };
+        let config = || SolverConfig::us_customary().with_criteria(&criteria);
+
+        let analysis = solve_pressure(104.0, config());
+        let violations = analysis.get_violations_by_type(ViolationType::Hgl);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].element_id, "MH-1");
+        assert_eq!(violations[0].severity, Severity::Warning);
+        assert_eq!(violations[0].limit, Some(102.5));
+
+        // Outfall submergence alone is not surcharge
+        let analysis = solve_pressure(101.5, config());
+        assert!(analysis.get_violations_by_type(ViolationType::Hgl).is_empty());
+
+        // Above the rim the HGL violation takes over
+        let analysis = solve_pressure(111.0, config());
+        let manhole = &analysis.node_results.as_ref().unwrap()[0];
+        assert!(manhole.depth_above_rim.unwrap() > 1.0);
+        let violations = analysis.get_violations_by_type(ViolationType::Hgl);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_outfall_tailwater_rules() {
+        let outfall_result = |analysis: &Analysis| {
+            analysis
+                .node_results
+                .as_ref()
+                .unwrap()
+                .iter()
+                .find(|n| n.node_id == "OUT")
+                .cloned()
+                .unwrap()
+        };
+        let set_outfall = |network: &mut Network, boundary, tailwater, rule| {
+            let outfall = network.nodes.iter_mut().find(|n| n.id == "OUT").unwrap();
+            let props = outfall.outfall.as_mut().unwrap();
+            props.boundary_condition = boundary;
+            props.tailwater_elevation = tailwater;
+            props.tailwater_rule = rule;
+        };
+        let mannings = ManningsEquation { k: 1.486 };
+        let normal = mannings.normal_depth(6.75, 2.0, 0.0025, 0.013, 32.17).unwrap();
+        let critical = mannings.critical_depth(6.75, 2.0, 32.17).unwrap();
+
+        // Normal depth in the outlet pipe at its design flow
+        let mut network = single_pipe_network(24.0, 100.5, 100.0);
+        set_outfall(&mut network, BoundaryCondition::NormalDepth, None, None);
+        let outfall = outfall_result(&solve_single_pipe(&network, 6.75, SolverConfig::us_customary()));
+        assert_relative_eq!(outfall.hgl.unwrap(), 100.0 + normal, epsilon = 1e-3);
+        assert_eq!(outfall.tailwater_control, Some(TailwaterControl::NormalDepth));
+
+        // A tailwater below normal depth does not lower the outfall HGL...
+        set_outfall(&mut network, BoundaryCondition::NormalDepth, Some(100.2), None);
+        let outfall = outfall_result(&solve_single_pipe(&network, 6.75, SolverConfig::us_customary()));
+        assert_relative_eq!(outfall.hgl.unwrap(), 100.0 + normal, epsilon = 1e-3);
+        assert_eq!(outfall.tailwater_control, Some(TailwaterControl::NormalDepth));
+
+        // ...while one above it governs
+        set_outfall(&mut network, BoundaryCondition::NormalDepth, Some(102.5), None);
+        let outfall = outfall_result(&solve_single_pipe(&network, 6.75, SolverConfig::us_customary()));
+        assert_eq!(outfall.hgl, Some(102.5));
+        assert_eq!(outfall.tailwater_control, Some(TailwaterControl::ReceivingWater));
+
+        // HEC-22: (dc + D)/2 governs over a low receiving water...
+        set_outfall(&mut network, BoundaryCondition::FixedStage, Some(100.2), Some(TailwaterRule::Hec22));
+        let outfall = outfall_result(&solve_single_pipe(&network, 6.75, SolverConfig::us_customary()));
+        assert_relative_eq!(outfall.hgl.unwrap(), 100.0 + 0.5 * (critical + 2.0), epsilon = 1e-3);
+        assert_eq!(outfall.tailwater_control, Some(TailwaterControl::CriticalDepth));
+
+        // ...and a submerged outlet keeps the receiving water level
+        set_outfall(&mut network, BoundaryCondition::FixedStage, Some(102.5), Some(TailwaterRule::Hec22));
+        let outfall = outfall_result(&solve_single_pipe(&network, 6.75, SolverConfig::us_customary()));
+        assert_eq!(outfall.hgl, Some(102.5));
+        assert_eq!(outfall.tailwater_control, Some(TailwaterControl::ReceivingWater));
+    }
+
+    #[test]
+    fn test_tidal_tailwater_at_time_of_peak() {
+        let mut network = single_pipe_network(24.0, 100.5, 104.0);
+        let outfall = network.nodes.iter_mut().find(|n| n.id == "OUT").unwrap();
+        let props = outfall.outfall.as_mut().unwrap();
+        props.boundary_condition = BoundaryCondition::Tidal;
+        props.tailwater_elevation = None;
+        props.tidal_curve = Some(vec![
+            TidalPoint { time: 0.0, elevation: 101.0 },
+            TidalPoint { time: 60.0, elevation: 105.0 },
+        ]);
+        props.tidal_stage = Some(TidalStage::TimeOfPeak { peak_time: None });
+
+        // The tide is read at the outfall's time of peak flow
+        let peak_times = HashMap::from([("OUT".to_string(), 30.0)]);
+        let analysis = solve_single_pipe(
+            &network,
+            6.75,
+            SolverConfig::us_customary().with_peak_times(peak_times),
+        );
+        let expected = solve_pressure(103.0, SolverConfig::us_customary());
+        assert_relative_eq!(manhole_hgl(&analysis), manhole_hgl(&expected), epsilon = 1e-9);
+
+        let flows = HashMap::from([("P-1".to_string(), 6.75)]);
+        let result = HglSolver::new(SolverConfig::us_customary()).solve(&network, &flows, "Test".to_string());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_supercritical_pipe_upstream_control() {
+        let mannings = ManningsEquation { k: 1.486 };
+        let normal = mannings.normal_depth(3.3, 1.5, 0.05, 0.013, 32.17).unwrap();
+
+        // Low tailwater: the flow leaves the pipe supercritical and the
+        // upstream HGL sits at normal depth (HEC-22 Table 9.7, condition D)
+        let analysis = solve_steep(100.5);
+        let conduit = &analysis.conduit_results.as_ref().unwrap()[0];
+        assert_eq!(conduit.flow_regime, Some(crate::analysis::FlowRegime::Supercritical));
+        assert!(conduit.froude_number.unwrap() > 1.0);
+        assert_eq!(conduit.jump_location, None);
+        assert_relative_eq!(manhole_hgl(&analysis), 110.0 + normal, epsilon = 1e-9);
+
+        // The subcritical pipe upstream of a mild grade stays subcritical
+        let analysis = solve_pressure(100.2, SolverConfig::us_customary());
+        let conduit = &analysis.conduit_results.as_ref().unwrap()[0];
+        assert_eq!(conduit.flow_regime, Some(crate::analysis::FlowRegime::Subcritical));
+        assert!(conduit.froude_number.unwrap() < 1.0);
+    }
+
+    #[test]
+    fn test_hydraulic_jump_location() {
+        let jump = |tailwater: f64| {
+            let analysis = solve_steep(tailwater);
+            let conduit = analysis.conduit_results.as_ref().unwrap()[0].clone();
+            (conduit, manhole_hgl(&analysis))
+        };
+
+        // Tailwater above the sequent depth forces a jump into the barrel,
+        // leaving the upstream end under supercritical control
+        let (low, low_hgl) = jump(103.0);
+        let (high, high_hgl) = jump(106.0);
+        let low_jump = low.jump_location.unwrap();
+        let high_jump = high.jump_location.unwrap();
+        assert!(low_jump > 0.0 && low_jump < 200.0);
+        assert!(high_jump < low_jump);
+        assert_eq!(low.flow_regime, Some(crate::analysis::FlowRegime::Supercritical));
+        assert_relative_eq!(low_hgl, high_hgl, epsilon = 1e-9);
+
+        // Enough tailwater drowns the jump and carries backwater upstream
+        let (drowned, drowned_hgl) = jump(120.0);
+        assert_eq!(drowned.jump_location, None);
+        assert_eq!(drowned.flow_regime, Some(crate::analysis::FlowRegime::Subcritical));
+        assert!(drowned_hgl > 120.0);
+    }
+
+    #[test]
+    fn test_box_culvert() {
+        // 48 in × 36 in box on a 0.25% grade at half depth
+        let mut network = single_pipe_network(0.0, 100.5, 99.0);
+        let pipe = network.conduits[0].pipe.as_mut().unwrap();
+        pipe.shape = PipeShape::Rectangular;
+        pipe.diameter = None;
+        pipe.width = Some(48.0);
+        pipe.height = Some(36.0);
+
+        let slope = 0.0025_f64.sqrt();
+        let flow = 1.486 / 0.013 * 6.0 * (6.0_f64 / 7.0).powf(2.0 / 3.0) * slope;
+        let full = 1.486 / 0.013 * 12.0 * (12.0_f64 / 14.0).powf(2.0 / 3.0) * slope;
+        let analysis = solve_single_pipe(&network, flow, SolverConfig::us_customary());
+
+        let conduit = &analysis.conduit_results.as_ref().unwrap()[0];
+        assert_relative_eq!(conduit.depth.unwrap(), 1.5, epsilon = 1e-3);
+        assert_relative_eq!(conduit.velocity.unwrap(), flow / 6.0, epsilon = 1e-2);
+        assert_relative_eq!(conduit.capacity_used.unwrap(), flow / full, epsilon = 1e-6);
+        assert_eq!(conduit.flow_regime, Some(crate::analysis::FlowRegime::Subcritical));
+
+        // A box without its rise is an error rather than a 24 in pipe
+        let pipe = network.conduits[0].pipe.as_mut().unwrap();
+        pipe.height = None;
+        let flows = HashMap::from([("P-1".to_string(), flow)]);
+        assert!(HglSolver::new(SolverConfig::us_customary()).solve(&network, &flows, "Test".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_elliptical_standard_size() {
+        // 24 in equivalent horizontal ellipse is 19 in × 30 in
+        let mut network = single_pipe_network(24.0, 100.5, 99.0);
+        network.conduits[0].pipe.as_mut().unwrap().shape = PipeShape::Elliptical;
+
+        let section = Box::new(section::Elliptical { span: 2.5, rise: 19.0 / 12.0 });
+        let reach = Reach {
+            section,
+            length: 200.0,
+            slope: 0.0025,
+            manning_n: 0.013,
+            downstream_invert: 100.0,
+        };
+        let full = DirectStep::us_customary().full_capacity(&reach).unwrap();
+
+        let analysis = solve_single_pipe(&network, 0.5 * full, SolverConfig::us_customary());
+        let conduit = &analysis.conduit_results.as_ref().unwrap()[0];
+        assert_relative_eq!(conduit.capacity_used.unwrap(), 0.5, epsilon = 1e-6);
+        assert!(conduit.depth.unwrap() < 19.0 / 12.0);
+
+        // Surcharged flow fills the ellipse, not a round pipe
+        let analysis = solve_single_pipe(&network, 1.5 * full, SolverConfig::us_customary());
+        let conduit = &analysis.conduit_results.as_ref().unwrap()[0];
+        assert_relative_eq!(conduit.velocity.unwrap(), 1.5 * full / reach.section.area(19.0 / 12.0), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_culvert_headwater() {
+        // 48 in concrete culvert on a 1% grade with a square edged headwall
+        let mut network = single_pipe_network(48.0, 102.0, 101.0);
+        let pipe = network.conduits[0].pipe.as_mut().unwrap();
+        pipe.entrance_loss = None;
+        pipe.exit_loss = None;
+        pipe.culvert = Some(CulvertProperties {
+            inlet: CulvertInlet::ConcreteSquareEdge,
+            roadway: None,
+        });
+
+        let analysis = solve_single_pipe(&network, 200.0, SolverConfig::us_customary());
+        let conduit = &analysis.conduit_results.as_ref().unwrap()[0];
+        let culvert = conduit.culvert.as_ref().unwrap();
+        assert_eq!(culvert.control, CulvertControl::Inlet);
+        assert!(conduit.headloss.is_none());
+
+        // Submerged inlet control sets the HGL and EGL at the upstream node
+        let x = 200.0 / (4.0 * std::f64::consts::PI * 2.0);
+        let headwater = 102.0 + 4.0 * (0.0398 * x * x + 0.67 - 0.5 * 0.01);
+        assert_relative_eq!(culvert.headwater, headwater, epsilon = 1e-9);
+        assert_relative_eq!(manhole_hgl(&analysis), headwater, epsilon = 1e-9);
+        let manhole = &analysis.node_results.as_ref().unwrap()[0];
+        assert_relative_eq!(manhole.egl.unwrap(), headwater, epsilon = 1e-9);
+
+        // A high tailwater moves control to the outlet
+        let mut network = network;
+        network.nodes[1].outfall.as_mut().unwrap().tailwater_elevation = Some(108.0);
+        let analysis = solve_single_pipe(&network, 200.0, SolverConfig::us_customary());
+        let conduit = &analysis.conduit_results.as_ref().unwrap()[0];
+        let culvert = conduit.culvert.as_ref().unwrap();
+        assert_eq!(culvert.control, CulvertControl::Outlet);
+        let losses = conduit.headloss.as_ref().unwrap().total.unwrap();
+        assert_relative_eq!(manhole_hgl(&analysis), 108.0 + losses, epsilon = 1e-9);
+
+        // A road at 112 ft is overtopped and caps the headwater
+        network.conduits[0].pipe.as_mut().unwrap().culvert = Some(CulvertProperties {
+            inlet: CulvertInlet::ConcreteSquareEdge,
+            roadway: Some(RoadwayProperties {
+                crest_elevation: 112.0,
+                length: 150.0,
+                weir_coefficient: Some(2.9),
+            }),
+        });
+        let analysis = solve_single_pipe(&network, 300.0, SolverConfig::us_customary());
+        let conduit = &analysis.conduit_results.as_ref().unwrap()[0];
+        let culvert = conduit.culvert.as_ref().unwrap();
+        let overtopping = culvert.overtopping_flow.unwrap();
+        assert!(overtopping > 0.0);
+        assert_relative_eq!(conduit.flow.unwrap(), 300.0);
+        assert_relative_eq!(
+            manhole_hgl(&analysis),
+            112.0 + (overtopping / (2.9 * 150.0)).powf(2.0 / 3.0),
+            epsilon = 1e-6
+        );
+    }
+
+    /// 1000 ft channel on a 0.16% grade draining MH-1 to a fixed stage outfall
+    fn solve_channel_network(channel: ChannelProperties, flow: f64, tailwater: f64) -> Analysis {
+        let mut conduit = Conduit::new_channel(
+            "P-1".to_string(),
+            "MH-1".to_string(),
+            "OUT".to_string(),
+            1000.0,
+            channel,
+        );
+        conduit.upstream_invert = Some(101.6);
+        conduit.downstream_invert = Some(100.0);
+
+        let mut network = single_pipe_network(24.0, 101.6, tailwater);
+        network.conduits = vec![conduit];
+        network.nodes[0].rim_elevation = Some(115.0);
+        solve_single_pipe(&network, flow, SolverConfig::us_customary())
+    }
+
+    #[test]
+    fn test_trapezoidal_channel() {
+        // Chow (1959) Example 10.1: 400 cfs, b = 20 ft, z = 2, n = 0.025
+        let channel = ChannelProperties {
+            shape: ChannelShape::Trapezoidal,
+            bottom_width: Some(20.0),
+            side_slope: Some(2.0),
+            manning_n: 0.025,
+            cross_section: None,
+            subsections: None,
+        };
+
+        // A low tailwater leaves uniform flow at normal depth
+        let analysis = solve_channel_network(channel.clone(), 400.0, 100.5);
+        let conduit = &analysis.conduit_results.as_ref().unwrap()[0];
+        assert_relative_eq!(conduit.depth.unwrap(), 3.36, epsilon = 0.01);
+        assert_relative_eq!(conduit.headloss.as_ref().unwrap().friction.unwrap(), 1.6, epsilon = 1e-3);
+        assert_eq!(conduit.flow_regime, Some(crate::analysis::FlowRegime::Subcritical));
+        assert!(conduit.velocity.unwrap() > 0.0);
+        assert_relative_eq!(manhole_hgl(&analysis), 101.6 + conduit.depth.unwrap(), epsilon = 1e-3);
+
+        // Backwater from a high tailwater carries up the channel
+        let analysis = solve_channel_network(channel, 400.0, 106.0);
+        let conduit = &analysis.conduit_results.as_ref().unwrap()[0];
+        assert_relative_eq!(conduit.depth.unwrap(), 6.0);
+        assert_eq!(
+            conduit.profile.as_ref().unwrap().profile_types,
+            vec![crate::gvf::ProfileType::M1]
+        );
+        let hgl = manhole_hgl(&analysis);
+        assert!(hgl > 106.0 && hgl < 101.6 + 6.0);
+    }
+
+    #[test]
+    fn test_natural_channel_subsections() {
+        // The trapezoid of Chow Example 10.1 surveyed as a natural section
+        // with 60 ft overbanks 4 ft above the channel bottom
+        let points = [(-60.0, 104.0), (0.0, 104.0), (8.0, 100.0), (28.0, 100.0), (36.0, 104.0), (96.0, 104.0)];
+        let channel = |overbank_n: f64| ChannelProperties {
+            shape: ChannelShape::Natural,
+            bottom_width: None,
+            side_slope: None,
+            manning_n: 0.025,
+            cross_section: Some(
+                points
+                    .iter()
+                    .map(|&(station, elevation)| crate::conduit::StationElevation { station, elevation })
+                    .collect(),
+            ),
+            subsections: Some(vec![
+                crate::conduit::ChannelSubsection { start_station: -60.0, manning_n: overbank_n },
+                crate::conduit::ChannelSubsection { start_station: 0.0, manning_n: 0.025 },
+                crate::conduit::ChannelSubsection { start_station: 36.0, manning_n: overbank_n },
+            ]),
+        };
+        let depth = |overbank_n: f64| {
+            let analysis = solve_channel_network(channel(overbank_n), 400.0, 100.5);
+            analysis.conduit_results.as_ref().unwrap()[0].depth.unwrap()
+        };
+
+        // Within banks the section behaves as the trapezoid
+        assert_relative_eq!(depth(0.025), 3.36, epsilon = 0.01);
+
+        // Out of bank, rougher overbanks raise the water surface
+        let analysis = solve_channel_network(channel(0.025), 2000.0, 100.5);
+        let conduit = &analysis.conduit_results.as_ref().unwrap()[0];
+        assert!(conduit.capacity_used.unwrap() > 1.0);
+        let smooth = conduit.depth.unwrap();
+        let rough = {
+            let analysis = solve_channel_network(channel(0.08), 2000.0, 100.5);
+            analysis.conduit_results.as_ref().unwrap()[0].depth.unwrap()
+        };
+        assert!(smooth > 4.0);
+        assert!(rough > smooth);
+    }
 }
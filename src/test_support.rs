@@ -0,0 +1,59 @@
+//! Shared network fixtures for unit tests
+
+use crate::conduit::{Conduit, PipeMaterial, PipeProperties, PipeShape};
+use crate::node::{BoundaryCondition, JunctionProperties, Node, OutfallProperties};
+
+/// Circular RCP pipe with n = 0.013
+pub(crate) fn pipe(id: &str, from: &str, to: &str, length: f64, diameter: f64) -> Conduit {
+    Conduit::new_pipe(
+        id.to_string(),
+        from.to_string(),
+        to.to_string(),
+        length,
+        PipeProperties {
+            shape: PipeShape::Circular,
+            diameter: Some(diameter),
+            width: None,
+            height: None,
+            material: Some(PipeMaterial::RCP),
+            manning_n: 0.013,
+            entrance_loss: None,
+            exit_loss: None,
+            bend_loss: None,
+            culvert: None,
+        },
+    )
+}
+
+/// 4 ft diameter junction
+pub(crate) fn junction(id: &str, invert: f64, rim: f64) -> Node {
+    Node::new_junction(
+        id.to_string(),
+        invert,
+        rim,
+        JunctionProperties {
+            diameter: Some(4.0),
+            sump_depth: None,
+            loss_coefficient: None,
+            benching: None,
+            drop_structure: None,
+        },
+    )
+}
+
+/// Outfall with the given boundary condition and tailwater
+pub(crate) fn outfall(id: &str, invert: f64, boundary: BoundaryCondition, tailwater: Option<f64>) -> Node {
+    Node::new_outfall(
+        id.to_string(),
+        invert,
+        OutfallProperties {
+            boundary_condition: boundary,
+            tailwater_elevation: tailwater,
+            tidal_curve: None,
+            tidal_stage: None,
+            storm_start: None,
+            flap_gate: None,
+            tailwater_rule: None,
+        },
+    )
+}
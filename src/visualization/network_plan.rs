@@ -236,7 +236,7 @@ impl<'a> NetworkPlanView<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::node::{Coordinates, InletProperties, InletType, InletLocation, JunctionProperties, OutfallProperties, BoundaryCondition};
+    use crate::node::{Coordinates, InletProperties, InletType, InletLocation, JunctionProperties, Node, OutfallProperties, BoundaryCondition};
 
     #[test]
     fn test_network_plan_basic() {
@@ -252,8 +252,10 @@ mod tests {
                 location: InletLocation::OnGrade,
                 grate: None,
                 curb_opening: None,
+                slotted: None,
                 local_depression: None,
                 clogging_factor: None,
+                bypass_to: None,
             },
         );
         node1.coordinates = Some(Coordinates {
@@ -289,6 +291,10 @@ mod tests {
                 boundary_condition: BoundaryCondition::Free,
                 tailwater_elevation: None,
                 tidal_curve: None,
+                tidal_stage: None,
+                storm_start: None,
+                flap_gate: None,
+                tailwater_rule: None,
             },
         );
         node3.coordinates = Some(Coordinates {
@@ -1,13 +1,15 @@
 //! Profile view visualization
 //!
 //! Generates elevation profile views showing:
-//! - Hydraulic Grade Line (HGL)
+//! - Hydraulic Grade Line (HGL), curved along conduits with a computed
+//!   water surface profile
 //! - Energy Grade Line (EGL)
 //! - Ground/rim elevations
 //! - Pipe inverts and slopes
 //! - Node locations
 
 use crate::analysis::Analysis;
+use crate::gvf::WaterSurfaceProfile;
 use crate::network::Network;
 use crate::node::Node;
 use crate::visualization::svg::SvgBuilder;
@@ -62,6 +64,9 @@ struct ProfilePoint {
     hgl: Option<f64>,
     egl: Option<f64>,
     junction_loss: Option<f64>,
+    /// Interior (station, HGL, EGL) points along the conduit arriving from
+    /// the previous node
+    conduit_profile: Vec<(f64, f64, f64)>,
 }
 
 /// Profile view generator
@@ -151,8 +156,21 @@ impl<'a> ProfileView<'a> {
             HashMap::new()
         };
 
+        // Water surface profiles along conduits
+        let conduit_profile_map: HashMap<&str, &WaterSurfaceProfile> = analysis
+            .and_then(|a| a.conduit_results.as_ref())
+            .map(|results| {
+                results
+                    .iter()
+                    .filter_map(|cr| cr.profile.as_ref().map(|p| (cr.conduit_id.as_str(), p)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
         for (i, node_id) in node_path.iter().enumerate() {
             if let Some(node) = node_map.get(node_id.as_str()) {
+                let mut conduit_profile = Vec::new();
+
                 // Calculate station (cumulative distance)
                 if i > 0 {
                     // Find conduit connecting previous node to this node
@@ -161,6 +179,22 @@ impl<'a> ProfileView<'a> {
                         (&c.from_node == prev_node_id && &c.to_node == node_id) ||
                         (&c.from_node == node_id && &c.to_node == prev_node_id)
                     ) {
+                        // Profile stations run from the conduit's upstream end
+                        if let Some(profile) = conduit_profile_map.get(conduit.id.as_str()) {
+                            let forward = &conduit.from_node == prev_node_id;
+                            conduit_profile = profile
+                                .points
+                                .iter()
+                                .filter(|p| p.station > 0.0 && p.station < conduit.length)
+                                .map(|p| {
+                                    let offset = if forward { p.station } else { conduit.length - p.station };
+                                    (cumulative_station + offset, p.water_surface, p.energy)
+                                })
+                                .collect();
+                            if !forward {
+                                conduit_profile.reverse();
+                            }
+                        }
                         cumulative_station += conduit.length;
                     }
                 }
@@ -179,6 +213,7 @@ impl<'a> ProfileView<'a> {
                     hgl,
                     egl,
                     junction_loss,
+                    conduit_profile,
                 });
             }
         }
@@ -249,6 +284,10 @@ impl<'a> ProfileView<'a> {
             if let Some(egl) = point.egl {
                 max_elev = max_elev.max(egl);
             }
+            for &(_, hgl, egl) in &point.conduit_profile {
+                min_elev = min_elev.min(hgl);
+                max_elev = max_elev.max(egl);
+            }
         }
 
         // Handle edge case where all points are at same elevation
@@ -366,7 +405,10 @@ impl<'a> ProfileView<'a> {
     fn draw_hgl(&self, svg: &mut SvgBuilder, min_elev: f64, max_elev: f64) {
         let mut points = Vec::new();
 
-        for (i, point) in self.profile_points.iter().enumerate() {
+        for point in &self.profile_points {
+            for &(station, hgl, _) in &point.conduit_profile {
+                points.push(self.transform(station, hgl, min_elev, max_elev));
+            }
             if let Some(hgl) = point.hgl {
                 let (x, y) = self.transform(point.station, hgl, min_elev, max_elev);
                 points.push((x, y));
@@ -393,6 +435,9 @@ impl<'a> ProfileView<'a> {
         let mut points = Vec::new();
 
         for point in &self.profile_points {
+            for &(station, _, egl) in &point.conduit_profile {
+                points.push(self.transform(station, egl, min_elev, max_elev));
+            }
             if let Some(egl) = point.egl {
                 let (x, y) = self.transform(point.station, egl, min_elev, max_elev);
                 points.push((x, y));
@@ -544,8 +589,10 @@ mod tests {
                 location: InletLocation::OnGrade,
                 grate: None,
                 curb_opening: None,
+                slotted: None,
                 local_depression: None,
                 clogging_factor: None,
+                bypass_to: None,
             },
         );
 
@@ -568,23 +615,28 @@ mod tests {
         // Add conduit
         let conduit = Conduit {
             id: "C-001".to_string(),
+            conduit_type: ConduitType::Pipe,
+            name: None,
             from_node: "IN-001".to_string(),
             to_node: "MH-001".to_string(),
-            conduit_type: ConduitType::Pipe,
             length: 100.0,
-            slope: Some(0.01),
-            manning_n: 0.013,
             upstream_invert: Some(100.0),
             downstream_invert: Some(99.0),
+            slope: Some(0.01),
             pipe: Some(PipeProperties {
                 shape: PipeShape::Circular,
-                diameter: Some(1.5),
+                diameter: Some(18.0),
                 width: None,
                 height: None,
-                material: PipeMaterial::Rcp,
-                barrel_count: Some(1),
+                material: Some(PipeMaterial::RCP),
+                manning_n: 0.013,
+                entrance_loss: None,
+                exit_loss: None,
+                bend_loss: None,
+                culvert: None,
             }),
             gutter: None,
+            channel: None,
         };
         network.add_conduit(conduit);
 
@@ -596,4 +648,79 @@ mod tests {
         assert!(svg.contains("<svg"));
         assert!(svg.contains("Profile View"));
     }
+
+    #[test]
+    fn test_profile_view_draws_conduit_profile() {
+        use crate::node::{BoundaryCondition, OutfallProperties};
+        use crate::solver::{HglSolver, SolverConfig};
+
+        let mut network = Network::new();
+        network.add_node(Node::new_junction(
+            "MH-001".to_string(),
+            100.0,
+            106.0,
+            JunctionProperties {
+                diameter: Some(4.0),
+                sump_depth: None,
+                loss_coefficient: None,
+                benching: None,
+                drop_structure: None,
+            },
+        ));
+        network.add_node(Node::new_outfall(
+            "OUT-001".to_string(),
+            99.0,
+            OutfallProperties {
+                boundary_condition: BoundaryCondition::FixedStage,
+                tailwater_elevation: Some(100.3),
+                tidal_curve: None,
+                tidal_stage: None,
+                storm_start: None,
+                flap_gate: None,
+                tailwater_rule: None,
+            },
+        ));
+        let mut conduit = Conduit::new_pipe(
+            "C-001".to_string(),
+            "MH-001".to_string(),
+            "OUT-001".to_string(),
+            250.0,
+            PipeProperties {
+                shape: PipeShape::Circular,
+                diameter: Some(18.0),
+                width: None,
+                height: None,
+                material: Some(PipeMaterial::RCP),
+                manning_n: 0.013,
+                entrance_loss: None,
+                exit_loss: None,
+                bend_loss: None,
+                culvert: None,
+            },
+        );
+        conduit.upstream_invert = Some(100.0);
+        conduit.downstream_invert = Some(99.0);
+        network.add_conduit(conduit);
+
+        let flows = HashMap::from([("C-001".to_string(), 4.0)]);
+        let analysis = HglSolver::new(SolverConfig::us_customary())
+            .solve(&network, &flows, "Test".to_string())
+            .unwrap();
+
+        let profile = ProfileView::with_analysis(&network, &["MH-001", "OUT-001"], &analysis);
+        let interior = &profile.profile_points[1].conduit_profile;
+        assert!(!interior.is_empty());
+        assert!(interior.windows(2).all(|w| w[0].0 <= w[1].0));
+        assert!(interior.iter().all(|&(station, _, _)| station > 0.0 && station < 250.0));
+
+        // The HGL polyline carries the interior points between the two nodes
+        let svg = profile.to_svg();
+        let hgl_line = svg
+            .split("<polyline")
+            .skip(1)
+            .find(|element| element.contains("#2196F3"))
+            .unwrap();
+        let hgl_points = hgl_line.split('"').nth(1).unwrap().split(' ').count();
+        assert_eq!(hgl_points, interior.len() + 2);
+    }
 }
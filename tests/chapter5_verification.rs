@@ -118,7 +118,7 @@ fn test_example_5_3_composite_gutter_with_depression() {
         0.02,   // Sx (roadway slope)
         0.005,  // SL (longitudinal slope)
         2.0,    // W (gutter width)
-        2.0,    // a (local depression, inches)
+        2.0 / 12.0, // a (local depression, 2 in)
     );
 
     let total_spread = 10.0;
@@ -130,7 +130,7 @@ fn test_example_5_3_composite_gutter_with_depression() {
     println!("  Gutter width: {:.1} ft", gutter.gutter_width);
     println!("  Gutter slope: {:.4} ({:.1} in/ft)", gutter.gutter_slope, gutter.gutter_slope * 12.0);
     println!("  Roadway slope: {:.3} ({:.0}%)", gutter.roadway_slope, gutter.roadway_slope * 100.0);
-    println!("  Local depression: {:.1} in", gutter.local_depression);
+    println!("  Local depression: {:.1} in", gutter.local_depression_ft * 12.0);
     println!("\nResults:");
     println!("  Total spread: {:.1} ft", total_spread);
     println!("  Capacity: {:.2} cfs", capacity);
@@ -146,10 +146,13 @@ fn test_example_5_3_composite_gutter_with_depression() {
         "Composite gutter should have higher capacity than uniform"
     );
 
-    // Composite gutter with steep cross-slope and depression has much higher capacity
+    // Sw = 0.0417 + 0.167/2 = 0.125, Qs = 0.93 cfs on Ts = 8 ft and
+    // Eo = 0.644 (Equation 5.7), so Q = Qs / (1 - Eo) = 2.62 cfs. This
+    // replaces an earlier `capacity > 10.0` bound, which only held while the
+    // depressed slope was applied across the whole spread.
     assert!(
-        capacity > 10.0,
-        "Capacity {:.2} should be significantly enhanced by steep gutter slope",
+        (capacity - 2.62).abs() < 0.05,
+        "Capacity {:.2} should match Equations 5.5-5.8",
         capacity
     );
 }
@@ -284,7 +287,7 @@ fn test_example_5_6_composite_vs_uniform_comparison() {
         0.02,   // Roadway slope
         sl,
         2.0,    // Gutter width
-        2.0,    // Depression (inches)
+        2.0 / 12.0, // Depression (2 in)
     );
     let spread_composite = composite.spread_for_flow(flow, GUTTER_K_US);
 
@@ -158,6 +158,10 @@ fn test_node_properties() {
     let inlet_props = inlet.inlet.as_ref().unwrap();
     assert_eq!(inlet_props.inlet_type, node::InletType::Combination);
     assert_eq!(inlet_props.location, node::InletLocation::OnGrade);
+    assert_eq!(
+        inlet_props.grate.as_ref().unwrap().grate_type,
+        Some(node::GrateType::P50x100)
+    );
 
     // Find a junction
     let junction = network
@@ -254,7 +258,7 @@ fn test_drainage_area_calculations() {
 
         // Test Tc calculation if breakdown is provided
         if area.tc_calculation.is_some() {
-            let tc = area.calculate_total_tc();
+            let tc = area.calculate_total_tc(network.project.units.system);
             assert!(tc.is_some());
             assert!(tc.unwrap() > 0.0);
         }
@@ -40,14 +40,17 @@ fn test_simple_linear_network() {
                 length: Some(2.0),
                 width: Some(1.5),
                 bar_configuration: Some(node::BarConfiguration::Perpendicular),
+                grate_type: None,
             }),
             curb_opening: Some(node::CurbOpeningProperties {
                 length: Some(3.0),
                 height: Some(0.5),
                 throat_type: Some(node::ThroatType::Horizontal),
             }),
+            slotted: None,
             local_depression: Some(2.0),
             clogging_factor: Some(0.15),
+            bypass_to: None,
         },
     );
 
@@ -62,14 +65,17 @@ fn test_simple_linear_network() {
                 length: Some(2.0),
                 width: Some(1.5),
                 bar_configuration: Some(node::BarConfiguration::Perpendicular),
+                grate_type: None,
             }),
             curb_opening: Some(node::CurbOpeningProperties {
                 length: Some(3.0),
                 height: Some(0.5),
                 throat_type: Some(node::ThroatType::Horizontal),
             }),
+            slotted: None,
             local_depression: Some(2.0),
             clogging_factor: Some(0.15),
+            bypass_to: None,
         },
     );
 
@@ -80,6 +86,10 @@ fn test_simple_linear_network() {
             boundary_condition: node::BoundaryCondition::NormalDepth,
             tailwater_elevation: Some(121.0),
             tidal_curve: None,
+            tidal_stage: None,
+            storm_start: None,
+            flap_gate: None,
+            tailwater_rule: None,
         },
     );
 
@@ -99,6 +109,7 @@ fn test_simple_linear_network() {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
     pipe1.upstream_invert = Some(130.0);
@@ -119,6 +130,7 @@ fn test_simple_linear_network() {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
     pipe2.upstream_invert = Some(125.0);
@@ -214,7 +226,7 @@ fn test_simple_linear_network() {
     );
 
     // Route flows through network
-    let conduit_flows = solver::route_flows(&network, &node_inflows)
+    let conduit_flows = solver::route_flows(&network, &node_inflows, project::UnitSystem::US)
         .expect("Flow routing should succeed");
 
     // Verify conduit flows
@@ -484,10 +496,13 @@ fn test_branching_network() {
                 length: Some(2.0),
                 width: Some(1.5),
                 bar_configuration: Some(node::BarConfiguration::Parallel),
+                grate_type: None,
             }),
             curb_opening: None,
+            slotted: None,
             local_depression: Some(2.0),
             clogging_factor: Some(0.10),
+            bypass_to: None,
         },
     );
 
@@ -502,10 +517,13 @@ fn test_branching_network() {
                 length: Some(2.0),
                 width: Some(1.5),
                 bar_configuration: Some(node::BarConfiguration::Parallel),
+                grate_type: None,
             }),
             curb_opening: None,
+            slotted: None,
             local_depression: Some(2.0),
             clogging_factor: Some(0.10),
+            bypass_to: None,
         },
     );
 
@@ -523,8 +541,10 @@ fn test_branching_network() {
                 height: Some(0.5),
                 throat_type: Some(node::ThroatType::Horizontal),
             }),
+            slotted: None,
             local_depression: Some(0.0),
             clogging_factor: Some(0.15),
+            bypass_to: None,
         },
     );
 
@@ -541,8 +561,10 @@ fn test_branching_network() {
                 height: Some(0.5),
                 throat_type: Some(node::ThroatType::Horizontal),
             }),
+            slotted: None,
             local_depression: Some(0.0),
             clogging_factor: Some(0.15),
+            bypass_to: None,
         },
     );
 
@@ -568,6 +590,10 @@ fn test_branching_network() {
             boundary_condition: node::BoundaryCondition::NormalDepth,
             tailwater_elevation: Some(123.5), // Set reasonable tailwater
             tidal_curve: None,
+            tidal_stage: None,
+            storm_start: None,
+            flap_gate: None,
+            tailwater_rule: None,
         },
     );
 
@@ -587,6 +613,7 @@ fn test_branching_network() {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
     pipe_n1.upstream_invert = Some(140.0);
@@ -607,6 +634,7 @@ fn test_branching_network() {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
     pipe_n2.upstream_invert = Some(135.0);
@@ -628,6 +656,7 @@ fn test_branching_network() {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
     pipe_s1.upstream_invert = Some(138.0);
@@ -648,6 +677,7 @@ fn test_branching_network() {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
     pipe_s2.upstream_invert = Some(133.0);
@@ -669,6 +699,7 @@ fn test_branching_network() {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
     pipe_trunk.upstream_invert = Some(128.0);
@@ -809,7 +840,7 @@ fn test_branching_network() {
     );
 
     // Route flows
-    let conduit_flows = solver::route_flows(&network, &node_inflows)
+    let conduit_flows = solver::route_flows(&network, &node_inflows, project::UnitSystem::US)
         .expect("Flow routing should succeed");
 
     assert_eq!(conduit_flows.len(), 5, "Should have flows in all 5 conduits");
@@ -989,10 +1020,13 @@ fn test_unbalanced_branching_network() {
                 length: Some(2.0),
                 width: Some(1.5),
                 bar_configuration: Some(node::BarConfiguration::Parallel),
+                grate_type: None,
             }),
             curb_opening: None,
+            slotted: None,
             local_depression: Some(2.0),
             clogging_factor: Some(0.10),
+            bypass_to: None,
         },
     );
 
@@ -1010,8 +1044,10 @@ fn test_unbalanced_branching_network() {
                 height: Some(0.5),
                 throat_type: Some(node::ThroatType::Horizontal),
             }),
+            slotted: None,
             local_depression: Some(0.0),
             clogging_factor: Some(0.15),
+            bypass_to: None,
         },
     );
 
@@ -1028,8 +1064,10 @@ fn test_unbalanced_branching_network() {
                 height: Some(0.5),
                 throat_type: Some(node::ThroatType::Horizontal),
             }),
+            slotted: None,
             local_depression: Some(0.0),
             clogging_factor: Some(0.15),
+            bypass_to: None,
         },
     );
 
@@ -1046,8 +1084,10 @@ fn test_unbalanced_branching_network() {
                 height: Some(0.5),
                 throat_type: Some(node::ThroatType::Horizontal),
             }),
+            slotted: None,
             local_depression: Some(0.0),
             clogging_factor: Some(0.15),
+            bypass_to: None,
         },
     );
 
@@ -1073,6 +1113,10 @@ fn test_unbalanced_branching_network() {
             boundary_condition: node::BoundaryCondition::NormalDepth,
             tailwater_elevation: Some(123.5),
             tidal_curve: None,
+            tidal_stage: None,
+            storm_start: None,
+            flap_gate: None,
+            tailwater_rule: None,
         },
     );
 
@@ -1092,6 +1136,7 @@ fn test_unbalanced_branching_network() {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
     pipe_1.upstream_invert = Some(135.0);
@@ -1113,6 +1158,7 @@ fn test_unbalanced_branching_network() {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
     pipe_2a.upstream_invert = Some(145.0);
@@ -1133,6 +1179,7 @@ fn test_unbalanced_branching_network() {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
     pipe_2b.upstream_invert = Some(140.0);
@@ -1153,6 +1200,7 @@ fn test_unbalanced_branching_network() {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
     pipe_2c.upstream_invert = Some(135.0);
@@ -1174,6 +1222,7 @@ fn test_unbalanced_branching_network() {
             entrance_loss: Some(0.5),
             exit_loss: Some(1.0),
             bend_loss: Some(0.0),
+            culvert: None,
         },
     );
     pipe_trunk.upstream_invert = Some(128.0);
@@ -1314,7 +1363,7 @@ fn test_unbalanced_branching_network() {
     );
 
     // Route flows through network
-    let conduit_flows = solver::route_flows(&network, &node_inflows)
+    let conduit_flows = solver::route_flows(&network, &node_inflows, project::UnitSystem::US)
         .expect("Flow routing should succeed");
 
     assert_eq!(conduit_flows.len(), 5, "Should have flows in all 5 conduits");